@@ -0,0 +1,330 @@
+// src/address_book.rs
+use crate::qr;
+use crate::schema::{self, Document};
+use crate::storage;
+use crate::wallet::WalletInfo;
+use serde::{Deserialize, Serialize};
+
+const MAX_NAME_LEN: usize = 32;
+const MAX_NOTE_LEN: usize = 140;
+const MAX_SUGGESTIONS: usize = 8;
+
+/// A saved recipient
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Contact {
+    pub name: String,
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Mint of the token usually sent to this contact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_token: Option<String>,
+}
+
+/// Where an autocomplete suggestion came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionSource {
+    Contact,
+    OwnWallet,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressSuggestion {
+    pub name: String,
+    pub address: String,
+    pub source: SuggestionSource,
+    pub default_token: Option<String>,
+}
+
+fn clean_optional(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Shortened base58 for display, e.g. `HAgk...Kpqk`
+pub fn short_address(address: &str) -> String {
+    if address.len() > 12 {
+        format!("{}...{}", &address[..4], &address[address.len() - 4..])
+    } else {
+        address.to_string()
+    }
+}
+
+/// Saved contacts, stored in `contacts.json` next to `wallets.json`.
+/// Addresses are unique; every change is written back immediately.
+pub struct AddressBook {
+    contacts: Vec<Contact>,
+    #[cfg(not(feature = "web"))]
+    dir: String,
+}
+
+impl AddressBook {
+    /// Load from the app storage directory
+    pub fn load() -> Self {
+        #[cfg(feature = "web")]
+        {
+            Self {
+                contacts: storage::read_web_document(storage::CONTACTS_WEB_KEY, Document::Contacts).unwrap_or_default(),
+            }
+        }
+
+        #[cfg(not(feature = "web"))]
+        {
+            Self::load_from(&storage::get_storage_dir_simple())
+        }
+    }
+
+    /// Load from an explicit directory
+    #[cfg(not(feature = "web"))]
+    pub fn load_from(dir: &str) -> Self {
+        let path = format!("{}/{}", dir, storage::CONTACTS_FILE_NAME);
+        Self {
+            contacts: storage::read_document(&path, Document::Contacts).unwrap_or_default(),
+            dir: dir.to_string(),
+        }
+    }
+
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    pub fn get(&self, address: &str) -> Option<&Contact> {
+        self.contacts.iter().find(|c| c.address == address)
+    }
+
+    /// Validate and normalise a contact. The address may also be a `solana:` link.
+    fn validate(contact: Contact) -> Result<Contact, String> {
+        let name = contact.name.trim().to_string();
+        if name.is_empty() {
+            return Err("Contact name cannot be empty".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LEN {
+            return Err(format!("Contact name must be at most {} characters", MAX_NAME_LEN));
+        }
+        let note = clean_optional(contact.note);
+        if note.as_ref().is_some_and(|n| n.chars().count() > MAX_NOTE_LEN) {
+            return Err(format!("Notes must be at most {} characters", MAX_NOTE_LEN));
+        }
+
+        Ok(Contact {
+            name,
+            address: qr::parse_address_payload(&contact.address)?,
+            note,
+            default_token: clean_optional(contact.default_token),
+        })
+    }
+
+    /// Add a new contact, or update the one saved for `previous_address`
+    pub fn save_contact(&mut self, contact: Contact, previous_address: Option<&str>) -> Result<Contact, String> {
+        let contact = Self::validate(contact)?;
+        if previous_address != Some(contact.address.as_str()) {
+            if let Some(existing) = self.get(&contact.address) {
+                return Err(format!("{} is already saved as {}", contact.address, existing.name));
+            }
+        }
+
+        match previous_address.and_then(|address| self.contacts.iter().position(|c| c.address == address)) {
+            Some(index) => self.contacts[index] = contact.clone(),
+            None => {
+                log::info!("📇 Adding contact {} ({})", contact.name, contact.address);
+                self.contacts.push(contact.clone());
+            }
+        }
+        self.contacts.sort_by_key(|c| c.name.to_lowercase());
+        self.save()?;
+        Ok(contact)
+    }
+
+    pub fn remove(&mut self, address: &str) -> Result<(), String> {
+        let before = self.contacts.len();
+        self.contacts.retain(|c| c.address != address);
+        if self.contacts.len() == before {
+            return Err(format!("No contact with address {}", address));
+        }
+        self.save()
+    }
+
+    /// Add contacts from elsewhere (e.g. a backup), skipping invalid entries and
+    /// addresses already saved. Returns how many were added.
+    pub fn merge(&mut self, contacts: &[Contact]) -> Result<usize, String> {
+        let added = self.add_missing(contacts);
+        self.save()?;
+        Ok(added)
+    }
+
+    /// `merge` without saving
+    pub(crate) fn add_missing(&mut self, contacts: &[Contact]) -> usize {
+        let mut added = 0;
+        for contact in contacts {
+            match Self::validate(contact.clone()) {
+                Ok(contact) if self.get(&contact.address).is_none() => {
+                    self.contacts.push(contact);
+                    added += 1;
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("⚠️ Skipping contact {}: {}", contact.name, e),
+            }
+        }
+        self.contacts.sort_by_key(|c| c.name.to_lowercase());
+        added
+    }
+
+    /// Swap every contact for `contacts`
+    pub fn replace_all(&mut self, contacts: &[Contact]) -> Result<usize, String> {
+        self.contacts.clear();
+        self.merge(contacts)
+    }
+
+    /// Contacts and own wallets whose name or address matches `query`, contacts first.
+    /// An empty query lists everything.
+    pub fn suggestions(&self, query: &str, own_wallets: &[WalletInfo]) -> Vec<AddressSuggestion> {
+        let typed = query.trim();
+        let query = typed.to_lowercase();
+        let matches = |name: &str, address: &str| {
+            query.is_empty() || name.to_lowercase().contains(&query) || address.to_lowercase().starts_with(&query)
+        };
+
+        let contacts = self
+            .contacts
+            .iter()
+            .filter(|c| matches(&c.name, &c.address))
+            .map(|c| AddressSuggestion {
+                name: c.name.clone(),
+                address: c.address.clone(),
+                source: SuggestionSource::Contact,
+                default_token: c.default_token.clone(),
+            });
+        let wallets = own_wallets
+            .iter()
+            .filter(|w| self.get(&w.address).is_none() && matches(&w.name, &w.address))
+            .map(|w| AddressSuggestion {
+                name: w.name.clone(),
+                address: w.address.clone(),
+                source: SuggestionSource::OwnWallet,
+                default_token: None,
+            });
+
+        contacts
+            .chain(wallets)
+            .filter(|s| s.address != typed)
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
+
+    /// A contact's or own wallet's name for `address`
+    pub fn label_for(&self, address: &str, own_wallets: &[WalletInfo]) -> Option<String> {
+        self.get(address)
+            .map(|c| c.name.clone())
+            .or_else(|| own_wallets.iter().find(|w| w.address == address).map(|w| w.name.clone()))
+    }
+
+    /// The name for `address` if known, otherwise the shortened address
+    pub fn display_name(&self, address: &str, own_wallets: &[WalletInfo]) -> String {
+        self.label_for(address, own_wallets)
+            .unwrap_or_else(|| short_address(address))
+    }
+
+    /// Forget every contact in memory. Nothing is written until the next save.
+    pub(crate) fn clear(&mut self) {
+        self.contacts.clear();
+    }
+
+    /// The contacts file as `save` would write it, to write together with other documents
+    pub(crate) fn pending_write(&self) -> Result<storage::PendingWrite, String> {
+        let serialized = schema::encode(Document::Contacts, &self.contacts)?;
+
+        #[cfg(feature = "web")]
+        {
+            Ok((storage::CONTACTS_WEB_KEY, serialized))
+        }
+
+        #[cfg(not(feature = "web"))]
+        {
+            Ok((format!("{}/{}", self.dir, storage::CONTACTS_FILE_NAME), serialized))
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        storage::write_all(&[self.pending_write()?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TREASURY: &str = "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk";
+    const PAYROLL: &str = "11111111111111111111111111111111";
+
+    fn temp_dir() -> String {
+        let dir = std::env::temp_dir().join(format!("address_book_{}", uuid::Uuid::new_v4()));
+        dir.to_string_lossy().into_owned()
+    }
+
+    fn contact(name: &str, address: &str) -> Contact {
+        Contact {
+            name: name.to_string(),
+            address: address.to_string(),
+            note: None,
+            default_token: None,
+        }
+    }
+
+    #[test]
+    fn test_save_edit_remove_persist() {
+        let dir = temp_dir();
+        let mut book = AddressBook::load_from(&dir);
+
+        let saved = book
+            .save_contact(
+                Contact {
+                    note: Some("  ".to_string()),
+                    default_token: Some("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string()),
+                    ..contact(" Treasury ", &format!("solana:{}?amount=1", TREASURY))
+                },
+                None,
+            )
+            .unwrap();
+        assert_eq!(saved.name, "Treasury");
+        assert_eq!(saved.address, TREASURY);
+        assert_eq!(saved.note, None);
+
+        assert!(book.save_contact(contact("Again", TREASURY), None).is_err());
+        assert!(book.save_contact(contact("Bad", "not-an-address"), None).is_err());
+
+        // Editing may change the address
+        book.save_contact(contact("Payroll", PAYROLL), Some(TREASURY)).unwrap();
+        let reloaded = AddressBook::load_from(&dir);
+        assert_eq!(reloaded.contacts().len(), 1);
+        assert_eq!(reloaded.get(PAYROLL).unwrap().name, "Payroll");
+
+        book.remove(PAYROLL).unwrap();
+        assert!(AddressBook::load_from(&dir).contacts().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_suggestions_and_labels() {
+        let dir = temp_dir();
+        let mut book = AddressBook::load_from(&dir);
+        book.save_contact(contact("Treasury", TREASURY), None).unwrap();
+        let own = vec![
+            WalletInfo::watch_only("Trading".to_string(), PAYROLL.to_string()),
+            WalletInfo::watch_only("Also Treasury".to_string(), TREASURY.to_string()),
+        ];
+
+        let names: Vec<String> = book.suggestions("tr", &own).into_iter().map(|s| s.name).collect();
+        // The contact wins over an own wallet with the same address
+        assert_eq!(names, ["Treasury", "Trading"]);
+        assert_eq!(book.suggestions("HAgk", &own)[0].source, SuggestionSource::Contact);
+        assert!(book.suggestions("zzz", &own).is_empty());
+        // A fully typed address needs no suggestion
+        assert!(book.suggestions(TREASURY, &[]).is_empty());
+
+        assert_eq!(book.display_name(TREASURY, &own), "Treasury");
+        assert_eq!(book.display_name(PAYROLL, &own), "Trading");
+        assert_eq!(book.display_name("So11111111111111111111111111111111111111112", &own), "So11...1112");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
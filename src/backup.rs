@@ -0,0 +1,408 @@
+// src/backup.rs
+use crate::address_book::{AddressBook, Contact};
+use crate::currency;
+use crate::keystore::{self, EncryptedSecret, KdfParams};
+use crate::storage::{self, JitoSettings};
+use crate::transaction_notes::{TransactionNote, TransactionNotes};
+use crate::wallet::{SecretExportFormat, Wallet, WalletInfo};
+use crate::wallet_repository::WalletRepository;
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, Zeroizing};
+
+const BACKUP_FORMAT: &str = "unruggable-backup";
+const BACKUP_VERSION: u32 = 1;
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// The file on disk. Only the header is readable without the backup passphrase.
+#[derive(Serialize, Deserialize)]
+struct BackupFile {
+    format: String,
+    version: u32,
+    created_at: i64,
+    secret: EncryptedSecret,
+}
+
+/// A wallet with its keypair in the clear. Only ever exists inside the
+/// encrypted payload, so the backup opens on a device with a different passphrase.
+#[derive(Serialize, Deserialize)]
+struct BackupWallet {
+    /// Stored info with the device keystore entry removed
+    info: WalletInfo,
+    /// Base58 64-byte keypair; `None` for watch-only wallets
+    #[serde(default, with = "secret_string")]
+    keypair: Option<Zeroizing<String>>,
+}
+
+/// Serde for a key held in `Zeroizing`, so no plain copy is left behind
+mod secret_string {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use zeroize::Zeroizing;
+
+    pub fn serialize<S: Serializer>(value: &Option<Zeroizing<String>>, serializer: S) -> Result<S::Ok, S::Error> {
+        value.as_deref().map(String::as_str).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Zeroizing<String>>, D::Error> {
+        Ok(Option::<String>::deserialize(deserializer)?.map(Zeroizing::new))
+    }
+}
+
+/// Everything a backup restores: wallets, contacts, transaction notes and app settings
+#[derive(Serialize, Deserialize, Default)]
+pub struct BackupContents {
+    pub created_at: i64,
+    wallets: Vec<BackupWallet>,
+    #[serde(default)]
+    pub contacts: Vec<Contact>,
+    #[serde(default)]
+    pub notes: Vec<TransactionNote>,
+    #[serde(default)]
+    pub rpc_url: Option<String>,
+    #[serde(default)]
+    pub jito_settings: Option<JitoSettings>,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub background: Option<String>,
+}
+
+impl BackupContents {
+    pub fn wallet_count(&self) -> usize {
+        self.wallets.len()
+    }
+
+    pub fn wallet_names(&self) -> Vec<String> {
+        self.wallets.iter().map(|w| w.info.name.clone()).collect()
+    }
+}
+
+/// How a restore treats what is already on the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Add wallets, contacts and notes that aren't here yet; keep local settings
+    Merge,
+    /// Make wallets, contacts, notes and settings exactly what the backup holds
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RestoreSummary {
+    pub added: usize,
+    /// Wallets already on this device (merge only)
+    pub skipped: usize,
+    pub contacts_added: usize,
+    pub notes_added: usize,
+    pub settings_restored: bool,
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Unseal every wallet's key with the current session
+fn backup_wallets(wallets: &[WalletInfo]) -> Result<Vec<BackupWallet>, String> {
+    wallets
+        .iter()
+        .map(|info| {
+            let keypair = if info.is_watch_only() {
+                None
+            } else {
+                let wallet = Wallet::from_wallet_info(info)?;
+                Some(Zeroizing::new(wallet.export_secret(SecretExportFormat::Base58Keypair).to_string()))
+            };
+            let mut info = info.clone();
+            info.keystore = None;
+            info.encrypted_key.zeroize();
+            Ok(BackupWallet { info, keypair })
+        })
+        .collect()
+}
+
+/// Check each keypair against its address and seal it under this device's session
+fn restored_wallets(contents: &BackupContents) -> Result<Vec<WalletInfo>, String> {
+    contents
+        .wallets
+        .iter()
+        .map(|backup| {
+            let mut info = backup.info.clone();
+            let Some(keypair) = backup.keypair.as_deref() else {
+                if !info.is_watch_only() {
+                    return Err(format!("Backup has no key for {}", info.name));
+                }
+                return Ok(info);
+            };
+
+            let bytes = Zeroizing::new(
+                bs58::decode(keypair)
+                    .into_vec()
+                    .map_err(|e| format!("Invalid key for {} in backup: {}", info.name, e))?,
+            );
+            let wallet = Wallet::from_private_key(&bytes, info.name.clone())?;
+            if wallet.get_public_key() != info.address {
+                return Err(format!("Key for {} does not match its address", info.name));
+            }
+            info.keystore = Some(keystore::seal(&bytes)?);
+            Ok(info)
+        })
+        .collect()
+}
+
+/// Gather wallets and settings from storage. Needs an unlocked session.
+pub fn collect_backup() -> Result<BackupContents, String> {
+    let repository = WalletRepository::load();
+    Ok(BackupContents {
+        created_at: now(),
+        wallets: backup_wallets(repository.wallets())?,
+        contacts: AddressBook::load().contacts().to_vec(),
+        notes: TransactionNotes::load().notes().to_vec(),
+        rpc_url: storage::load_rpc_from_storage(),
+        jito_settings: Some(storage::load_jito_settings_from_storage()),
+        currency: currency::load_currency_from_storage(),
+        background: storage::load_background_from_storage(),
+    })
+}
+
+/// Encrypt `contents` under a backup passphrase and serialize the file
+pub fn seal_backup(contents: &BackupContents, passphrase: &str, params: KdfParams) -> Result<String, String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Backup passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+    }
+    let payload = Zeroizing::new(
+        serde_json::to_vec(contents).map_err(|e| format!("Failed to serialize backup: {}", e))?,
+    );
+    let file = BackupFile {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at: contents.created_at,
+        secret: keystore::encrypt_secret(&payload, passphrase, params)?,
+    };
+    serde_json::to_string_pretty(&file).map_err(|e| format!("Failed to serialize backup: {}", e))
+}
+
+/// Decrypt a backup file
+pub fn open_backup(data: &str, passphrase: &str) -> Result<BackupContents, String> {
+    let file: BackupFile =
+        serde_json::from_str(data.trim()).map_err(|_| "This is not a wallet backup file".to_string())?;
+    if file.format != BACKUP_FORMAT {
+        return Err(format!("Unknown backup format {}", file.format));
+    }
+    if file.version > BACKUP_VERSION {
+        return Err(format!(
+            "Backup version {} was made by a newer version of the app. Please update the app.",
+            file.version
+        ));
+    }
+
+    let payload = keystore::decrypt_secret(&file.secret, passphrase)?;
+    serde_json::from_slice(&payload).map_err(|e| format!("Backup contents are unreadable: {}", e))
+}
+
+/// Collect, encrypt and write a backup to the exports directory
+#[cfg(not(feature = "web"))]
+pub fn export_backup_file(passphrase: &str) -> Result<String, String> {
+    let contents = collect_backup()?;
+    let sealed = seal_backup(&contents, passphrase, KdfParams::default())?;
+
+    let export_file = format!(
+        "{}/exports/backup-{}.json",
+        storage::get_storage_dir_simple(),
+        contents.created_at
+    );
+    storage::write_atomic(&export_file, &sealed).map_err(|e| format!("Failed to write backup file: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&export_file, std::fs::Permissions::from_mode(0o600));
+    }
+
+    log::info!("✅ Exported backup of {} wallets to {}", contents.wallet_count(), export_file);
+    Ok(export_file)
+}
+
+/// Apply `wallets` and the backup's contacts and notes to the loaded stores, then write
+/// the wallet, watch-only, contacts and notes files as one change: if any of them can't
+/// be replaced, none are.
+fn restore_into(
+    mut repository: WalletRepository,
+    mut address_book: AddressBook,
+    mut notes: TransactionNotes,
+    wallets: Vec<WalletInfo>,
+    contents: &BackupContents,
+    mode: RestoreMode,
+) -> Result<RestoreSummary, String> {
+    let mut summary = RestoreSummary::default();
+    if mode == RestoreMode::Replace {
+        address_book.clear();
+        notes.clear();
+    }
+    summary.contacts_added = address_book.add_missing(&contents.contacts);
+    summary.notes_added = notes.add_missing(&contents.notes);
+    let other = [address_book.pending_write()?, notes.pending_write()?];
+
+    let count = wallets.len();
+    match mode {
+        RestoreMode::Replace => {
+            repository.replace_all_with(wallets, &other)?;
+            summary.added = repository.wallets().len();
+        }
+        RestoreMode::Merge => {
+            summary.added = repository.merge_with(wallets, &other)?;
+            summary.skipped = count - summary.added;
+        }
+    }
+    Ok(summary)
+}
+
+/// Rebuild wallets and settings from a decrypted backup. Needs an unlocked session.
+pub fn restore_backup(contents: &BackupContents, mode: RestoreMode) -> Result<RestoreSummary, String> {
+    let wallets = restored_wallets(contents)?;
+    let mut summary = restore_into(
+        WalletRepository::load(),
+        AddressBook::load(),
+        TransactionNotes::load(),
+        wallets,
+        contents,
+        mode,
+    )?;
+
+    if mode == RestoreMode::Replace {
+        match &contents.rpc_url {
+            Some(rpc_url) => storage::save_rpc_to_storage(rpc_url),
+            None => storage::clear_rpc_storage(),
+        }
+        if let Some(settings) = &contents.jito_settings {
+            storage::save_jito_settings_to_storage(settings);
+        }
+        if let Some(currency) = &contents.currency {
+            currency::save_currency_to_storage(currency);
+        }
+        if let Some(background) = &contents.background {
+            storage::save_background_to_storage(background);
+        }
+        summary.settings_restored = true;
+    }
+
+    log::info!(
+        "✅ Restored backup: {} wallets added, {} already present, {} contacts added, {} notes added",
+        summary.added,
+        summary.skipped,
+        summary.contacts_added,
+        summary.notes_added
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> String {
+        let dir = std::env::temp_dir().join(format!("backup_{}", uuid::Uuid::new_v4()));
+        dir.to_string_lossy().into_owned()
+    }
+
+    fn sample_contents() -> (BackupContents, Vec<WalletInfo>) {
+        keystore::unlock_test_session();
+        let wallets = vec![
+            Wallet::new("Main".to_string()).to_wallet_info().unwrap(),
+            WalletInfo::watch_only(
+                "Treasury".to_string(),
+                "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk".to_string(),
+            ),
+        ];
+        let contents = BackupContents {
+            created_at: now(),
+            wallets: backup_wallets(&wallets).unwrap(),
+            contacts: Vec::new(),
+            notes: vec![TransactionNote {
+                signature: bs58::encode([7u8; 64]).into_string(),
+                note: "Rent".to_string(),
+                updated_at: now(),
+            }],
+            rpc_url: Some("https://rpc.example.com".to_string()),
+            jito_settings: Some(JitoSettings::default()),
+            currency: Some("EUR".to_string()),
+            background: Some("Aurora".to_string()),
+        };
+        (contents, wallets)
+    }
+
+    #[test]
+    fn test_seal_and_open_round_trip() {
+        let (contents, wallets) = sample_contents();
+        let sealed = seal_backup(&contents, "backup passphrase", keystore::test_params()).unwrap();
+        // Neither the key nor the settings are visible in the file
+        assert!(!sealed.contains(&wallets[0].address));
+        assert!(!sealed.contains("rpc.example.com"));
+        assert!(!sealed.contains("Rent"));
+
+        let opened = open_backup(&sealed, "backup passphrase").unwrap();
+        assert_eq!(opened.wallet_names(), ["Main", "Treasury"]);
+        assert_eq!(opened.currency.as_deref(), Some("EUR"));
+        assert_eq!(opened.background.as_deref(), Some("Aurora"));
+        assert_eq!(opened.notes, contents.notes);
+
+        // Keys are resealed under the restoring session and still match their address
+        let restored = restored_wallets(&opened).unwrap();
+        assert!(restored[0].keystore.is_some());
+        assert_eq!(
+            Wallet::from_wallet_info(&restored[0]).unwrap().get_public_key(),
+            wallets[0].address
+        );
+        assert!(restored[1].is_watch_only());
+
+        assert_eq!(open_backup(&sealed, "wrong passphrase").err().unwrap(), "Incorrect passphrase");
+        assert!(open_backup("{}", "backup passphrase").is_err());
+        assert!(seal_backup(&contents, "short", keystore::test_params()).is_err());
+    }
+
+    #[test]
+    fn test_mismatched_key_rejected() {
+        let (mut contents, _) = sample_contents();
+        let other = Wallet::new("Other".to_string());
+        contents.wallets[0].keypair =
+            Some(Zeroizing::new(other.export_secret(SecretExportFormat::Base58Keypair).to_string()));
+        assert!(restored_wallets(&contents).is_err());
+    }
+
+    #[test]
+    fn test_merge_and_replace() {
+        let (contents, wallets) = sample_contents();
+        let restored = restored_wallets(&contents).unwrap();
+
+        let dir = temp_dir();
+        let mut repository = WalletRepository::load_from(&dir);
+        repository.add(wallets[0].clone()).unwrap();
+        let local = repository.add(Wallet::new("Main".to_string()).to_wallet_info().unwrap()).unwrap();
+        TransactionNotes::load_from(&dir).set(&bs58::encode([8u8; 64]).into_string(), "Local").unwrap();
+        let restore = |mode| {
+            restore_into(
+                WalletRepository::load_from(&dir),
+                AddressBook::load_from(&dir),
+                TransactionNotes::load_from(&dir),
+                restored.clone(),
+                &contents,
+                mode,
+            )
+        };
+
+        // Merge keeps local wallets and notes and skips addresses already present
+        let summary = restore(RestoreMode::Merge).unwrap();
+        assert_eq!(summary, RestoreSummary { added: 1, skipped: 1, notes_added: 1, ..Default::default() });
+        let reloaded = WalletRepository::load_from(&dir);
+        assert_eq!(reloaded.wallets().len(), 3);
+        assert!(reloaded.get(&local.address).is_some());
+        assert_eq!(TransactionNotes::load_from(&dir).notes().len(), 2);
+
+        // Replace leaves exactly the backup's wallets and notes
+        let summary = restore(RestoreMode::Replace).unwrap();
+        assert_eq!(summary.added, 2);
+        let reloaded = WalletRepository::load_from(&dir);
+        let addresses: Vec<&str> = reloaded.wallets().iter().map(|w| w.address.as_str()).collect();
+        assert_eq!(addresses, [wallets[0].address.as_str(), wallets[1].address.as_str()]);
+        assert_eq!(TransactionNotes::load_from(&dir).notes(), contents.notes.as_slice());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
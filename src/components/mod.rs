@@ -3,5 +3,8 @@ pub mod modals;
 pub mod common;
 pub mod calypso;
 pub mod background_themes;
+pub mod recipient_input;
+pub mod transaction_note_input;
+pub mod transaction_preview;
 
 pub use wallet_view::*;
\ No newline at end of file
@@ -0,0 +1,195 @@
+use dioxus::prelude::*;
+use crate::address_book::{short_address, AddressBook, Contact};
+use crate::components::common::Token;
+
+/// Add, edit and delete saved recipients.
+/// `tokens` are the current wallet's holdings, offered as a contact's default token.
+#[component]
+pub fn AddressBookModal(tokens: Vec<Token>, onclose: EventHandler<()>) -> Element {
+    let mut contacts = use_signal(|| AddressBook::load().contacts().to_vec());
+    // None: list view; Some(None): adding; Some(Some(address)): editing
+    let mut editing = use_signal(|| None as Option<Option<String>>);
+    let mut name = use_signal(|| "".to_string());
+    let mut address = use_signal(|| "".to_string());
+    let mut note = use_signal(|| "".to_string());
+    let mut default_token = use_signal(|| "".to_string());
+    let mut error_message = use_signal(|| None as Option<String>);
+
+    let mut open_form = move |contact: Option<Contact>| {
+        let contact = contact.unwrap_or(Contact {
+            name: String::new(),
+            address: String::new(),
+            note: None,
+            default_token: None,
+        });
+        editing.set(Some(Some(contact.address.clone()).filter(|a| !a.is_empty())));
+        name.set(contact.name);
+        address.set(contact.address);
+        note.set(contact.note.unwrap_or_default());
+        default_token.set(contact.default_token.unwrap_or_default());
+        error_message.set(None);
+    };
+
+    let token_symbol = {
+        let tokens = tokens.clone();
+        move |mint: &str| {
+            tokens
+                .iter()
+                .find(|t| t.mint == mint)
+                .map(|t| t.symbol.clone())
+                .unwrap_or_else(|| short_address(mint))
+        }
+    };
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Address Book" }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                if let Some(previous_address) = editing() {
+                    div { class: "wallet-field",
+                        label { "Name:" }
+                        input {
+                            value: "{name}",
+                            oninput: move |e| name.set(e.value()),
+                            placeholder: "Payroll"
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Address:" }
+                        input {
+                            value: "{address}",
+                            oninput: move |e| address.set(e.value()),
+                            placeholder: "Solana address or solana: link"
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Note (optional):" }
+                        input {
+                            value: "{note}",
+                            oninput: move |e| note.set(e.value()),
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Default token (optional):" }
+                        select {
+                            value: "{default_token}",
+                            onchange: move |e| default_token.set(e.value()),
+                            option { value: "", "None" }
+                            for token in tokens.iter() {
+                                option {
+                                    value: "{token.mint}",
+                                    selected: token.mint == default_token(),
+                                    "{token.symbol}"
+                                }
+                            }
+                        }
+                    }
+
+                    div { class: "modal-buttons",
+                        if let Some(existing) = previous_address.clone() {
+                            button {
+                                class: "modal-button danger",
+                                onclick: move |_| {
+                                    let mut book = AddressBook::load();
+                                    match book.remove(&existing) {
+                                        Ok(()) => {
+                                            contacts.set(book.contacts().to_vec());
+                                            editing.set(None);
+                                        }
+                                        Err(e) => error_message.set(Some(e)),
+                                    }
+                                },
+                                "Delete"
+                            }
+                        }
+                        button {
+                            class: "modal-button cancel",
+                            onclick: move |_| editing.set(None),
+                            "Back"
+                        }
+                        button {
+                            class: "modal-button primary",
+                            onclick: {
+                                let previous_address = previous_address.clone();
+                                move |_| {
+                                    let contact = Contact {
+                                        name: name(),
+                                        address: address(),
+                                        note: Some(note()),
+                                        default_token: Some(default_token()),
+                                    };
+                                    let mut book = AddressBook::load();
+                                    match book.save_contact(contact, previous_address.as_deref()) {
+                                        Ok(_) => {
+                                            contacts.set(book.contacts().to_vec());
+                                            editing.set(None);
+                                        }
+                                        Err(e) => error_message.set(Some(e)),
+                                    }
+                                }
+                            },
+                            "Save"
+                        }
+                    }
+                } else {
+                    if contacts.read().is_empty() {
+                        div { class: "help-text",
+                            "No contacts yet. Saved contacts are suggested when you type a recipient."
+                        }
+                    }
+                    div { class: "candidate-list",
+                        for contact in contacts() {
+                            div {
+                                key: "{contact.address}",
+                                class: "candidate-row",
+                                onclick: {
+                                    let contact = contact.clone();
+                                    move |_| open_form(Some(contact.clone()))
+                                },
+                                div { class: "candidate-info",
+                                    div { class: "candidate-path",
+                                        "{contact.name}"
+                                        if let Some(mint) = contact.default_token.as_deref() {
+                                            span { class: "wallet-tag", "{token_symbol(mint)}" }
+                                        }
+                                    }
+                                    div { class: "candidate-address", "{short_address(&contact.address)}" }
+                                    if let Some(note) = contact.note.as_deref() {
+                                        div { class: "help-text", "{note}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div { class: "modal-buttons",
+                        button {
+                            class: "modal-button cancel",
+                            onclick: move |_| onclose.call(()),
+                            "Done"
+                        }
+                        button {
+                            class: "modal-button primary",
+                            onclick: move |_| open_form(None),
+                            "Add Contact"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
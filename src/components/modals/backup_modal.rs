@@ -0,0 +1,254 @@
+use dioxus::prelude::*;
+use crate::backup::{self, BackupContents, RestoreMode};
+use std::rc::Rc;
+
+fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "unknown date".to_string())
+}
+
+/// Export every wallet and setting to one passphrase-encrypted file, or restore one.
+/// `onrestore` fires after a restore so the caller can reload wallets and settings.
+#[component]
+pub fn BackupModal(onclose: EventHandler<()>, onrestore: EventHandler<()>) -> Element {
+    // "export" or "restore"
+    let mut mode = use_signal(|| "export".to_string());
+    let mut passphrase = use_signal(|| "".to_string());
+    let mut confirm_passphrase = use_signal(|| "".to_string());
+    let mut backup_text = use_signal(|| "".to_string());
+    let mut opened = use_signal(|| None as Option<Rc<BackupContents>>);
+    let mut replace = use_signal(|| false);
+    let mut error_message = use_signal(|| None as Option<String>);
+    let mut result_message = use_signal(|| None as Option<String>);
+
+    let mut switch_mode = move |new_mode: &str| {
+        mode.set(new_mode.to_string());
+        passphrase.set(String::new());
+        confirm_passphrase.set(String::new());
+        backup_text.set(String::new());
+        opened.set(None);
+        error_message.set(None);
+        result_message.set(None);
+    };
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Backup & Restore" }
+
+                div {
+                    class: "mode-toggle",
+                    button {
+                        class: if mode() == "export" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| switch_mode("export"),
+                        "Export"
+                    }
+                    button {
+                        class: if mode() == "restore" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| switch_mode("restore"),
+                        "Restore"
+                    }
+                }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+                if let Some(message) = result_message() {
+                    div {
+                        class: "info-message",
+                        "{message}"
+                    }
+                }
+
+                if mode() == "export" {
+                    div { class: "help-text",
+                        "One encrypted file with every wallet key, watch-only address, contact and app setting. Choose a backup passphrase; it can differ from your unlock passphrase."
+                    }
+                    div { class: "wallet-field",
+                        label { "Backup Passphrase:" }
+                        input {
+                            r#type: "password",
+                            value: "{passphrase}",
+                            oninput: move |e| passphrase.set(e.value()),
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Confirm Passphrase:" }
+                        input {
+                            r#type: "password",
+                            value: "{confirm_passphrase}",
+                            oninput: move |e| confirm_passphrase.set(e.value()),
+                        }
+                    }
+                    if !backup_text().is_empty() {
+                        div { class: "wallet-field",
+                            label { "Backup (copy and keep it somewhere safe):" }
+                            textarea {
+                                readonly: true,
+                                value: "{backup_text}",
+                                rows: 6,
+                            }
+                        }
+                    }
+                } else if let Some(contents) = opened() {
+                    div { class: "wallet-field",
+                        label { "Backup from {format_timestamp(contents.created_at)}:" }
+                        div { class: "candidate-list",
+                            for name in contents.wallet_names() {
+                                div { class: "candidate-row",
+                                    div { class: "candidate-path", "{name}" }
+                                }
+                            }
+                        }
+                    }
+                    div {
+                        class: "mode-toggle",
+                        button {
+                            class: if !replace() { "toggle-button active" } else { "toggle-button" },
+                            onclick: move |_| replace.set(false),
+                            "Merge"
+                        }
+                        button {
+                            class: if replace() { "toggle-button active" } else { "toggle-button" },
+                            onclick: move |_| replace.set(true),
+                            "Replace"
+                        }
+                    }
+                    if replace() {
+                        div { class: "private-key-warning",
+                            "⚠️ Wallets, contacts, notes and settings on this device are replaced by the backup. Wallets that are not in the backup are deleted."
+                        }
+                    } else {
+                        div { class: "help-text",
+                            "Adds wallets, contacts and notes from the backup that are not on this device. Your current settings are kept."
+                        }
+                    }
+                } else {
+                    div { class: "wallet-field",
+                        label { "Backup File:" }
+                        input {
+                            r#type: "file",
+                            accept: ".json,application/json",
+                            onchange: move |evt| {
+                                spawn(async move {
+                                    if let Some(file_engine) = evt.files() {
+                                        if let Some(file_name) = file_engine.files().first() {
+                                            match file_engine.read_file_to_string(file_name).await {
+                                                Some(contents) => {
+                                                    error_message.set(None);
+                                                    backup_text.set(contents);
+                                                }
+                                                None => error_message.set(Some(format!("Could not read {}", file_name))),
+                                            }
+                                        }
+                                    }
+                                });
+                            }
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Or paste the backup:" }
+                        textarea {
+                            value: "{backup_text}",
+                            oninput: move |e| backup_text.set(e.value()),
+                            rows: 4,
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Backup Passphrase:" }
+                        input {
+                            r#type: "password",
+                            value: "{passphrase}",
+                            oninput: move |e| passphrase.set(e.value()),
+                        }
+                    }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Close"
+                    }
+                    if mode() == "export" {
+                        button {
+                            class: "modal-button primary",
+                            disabled: passphrase().is_empty(),
+                            onclick: move |_| {
+                                if passphrase() != confirm_passphrase() {
+                                    error_message.set(Some("Passphrases do not match".to_string()));
+                                    return;
+                                }
+
+                                #[cfg(not(feature = "web"))]
+                                let result = backup::export_backup_file(&passphrase())
+                                    .map(|path| result_message.set(Some(format!("Saved backup to {}", path))));
+
+                                #[cfg(feature = "web")]
+                                let result = backup::collect_backup()
+                                    .and_then(|contents| backup::seal_backup(&contents, &passphrase(), Default::default()))
+                                    .map(|sealed| backup_text.set(sealed));
+
+                                match result {
+                                    Ok(()) => error_message.set(None),
+                                    Err(e) => error_message.set(Some(e)),
+                                }
+                            },
+                            "Create Backup"
+                        }
+                    } else if let Some(contents) = opened() {
+                        button {
+                            class: if replace() { "modal-button danger" } else { "modal-button primary" },
+                            onclick: move |_| {
+                                let restore_mode = if replace() { RestoreMode::Replace } else { RestoreMode::Merge };
+                                match backup::restore_backup(&contents, restore_mode) {
+                                    Ok(summary) => {
+                                        error_message.set(None);
+                                        result_message.set(Some(format!(
+                                            "Restored {} wallets ({} already on this device), {} contacts, {} notes{}",
+                                            summary.added,
+                                            summary.skipped,
+                                            summary.contacts_added,
+                                            summary.notes_added,
+                                            if summary.settings_restored { " and settings" } else { "" }
+                                        )));
+                                        opened.set(None);
+                                        onrestore.call(());
+                                    }
+                                    Err(e) => error_message.set(Some(e)),
+                                }
+                            },
+                            "Restore"
+                        }
+                    } else {
+                        button {
+                            class: "modal-button primary",
+                            disabled: backup_text().trim().is_empty() || passphrase().is_empty(),
+                            onclick: move |_| {
+                                match backup::open_backup(&backup_text(), &passphrase()) {
+                                    Ok(contents) => {
+                                        error_message.set(None);
+                                        result_message.set(None);
+                                        opened.set(Some(Rc::new(contents)));
+                                    }
+                                    Err(e) => error_message.set(Some(e)),
+                                }
+                            },
+                            "Open Backup"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
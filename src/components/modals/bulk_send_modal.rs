@@ -2,11 +2,15 @@
 
 use dioxus::prelude::*;
 use crate::components::common::Token;
-use crate::wallet::{Wallet, WalletInfo};
+use crate::components::recipient_input::RecipientInput;
+use crate::wallet::{cannot_sign_watch_only, WalletInfo, WATCH_ONLY_MESSAGE};
 use crate::hardware::HardwareWallet;
 use crate::components::modals::send_modal::HardwareWalletEvent;
 use crate::transaction::TransactionClient;
 use crate::signing::{SignerType, hardware::HardwareSigner};
+use crate::components::transaction_note_input::TransactionNoteInput;
+use crate::components::transaction_preview::{use_transaction_review, TransactionPreviewView, TransactionReviewOverlay};
+use crate::preview::{PreviewSigner, TransactionPreview};
 use std::sync::Arc;
 use std::collections::HashSet;
 
@@ -18,7 +22,7 @@ pub struct SelectedTokenForBulkSend {
 
 /// Hardware wallet approval overlay component for bulk send
 #[component]
-fn BulkSendHardwareApprovalOverlay(selected_count: usize, oncancel: EventHandler<()>) -> Element {
+fn BulkSendHardwareApprovalOverlay(selected_count: usize, previews: Vec<TransactionPreview>, oncancel: EventHandler<()>) -> Element {
     rsx! {
         div {
             class: "hardware-approval-overlay",
@@ -51,18 +55,22 @@ fn BulkSendHardwareApprovalOverlay(selected_count: usize, oncancel: EventHandler
                     class: "hardware-approval-text",
                     "Please check your hardware wallet and confirm the bulk transaction details."
                 }
+
+                if !previews.is_empty() {
+                    TransactionPreviewView { previews: previews.clone() }
+                }
                 
                 div {
                     class: "hardware-steps",
                     div {
                         class: "hardware-step",
                         div { class: "step-number", "1" }
-                        span { "Review all {selected_count} token transactions on your device" }
+                        span { "Check the transaction count shown on your device" }
                     }
                     div {
                         class: "hardware-step",
                         div { class: "step-number", "2" }
-                        span { "Press the button on your Unruggable to confirm each transaction" }
+                        span { "Press the button on your Unruggable once to approve them all" }
                     }
                 }
                 
@@ -84,6 +92,7 @@ pub fn BulkSendSuccessModal(
     was_hardware_wallet: bool,
     onclose: EventHandler<()>,
 ) -> Element {
+    let note_signature = signature.clone();
     // Explorer links for multiple explorers
     let solana_explorer_url = format!("https://explorer.solana.com/tx/{}", signature);
     let solscan_url = format!("https://solscan.io/tx/{}", signature);
@@ -140,6 +149,8 @@ pub fn BulkSendSuccessModal(
                         }
                     }
                     
+                    TransactionNoteInput { signature: note_signature }
+
                     div {
                         class: "explorer-links",
                         p { "View transaction in explorer:" }
@@ -210,6 +221,9 @@ pub fn BulkSendModal(
     
     // Hardware approval overlay state
     let mut show_hardware_approval = use_signal(|| false);
+    // What the hardware wallet is being asked to sign, filled in just before signing
+    let mut signing_preview = use_signal_sync(Vec::<TransactionPreview>::new);
+    let review = use_transaction_review();
     
     // Filter tokens to only selected ones using use_memo for reactivity
     let selected_tokens = use_memo(move || {
@@ -341,6 +355,8 @@ pub fn BulkSendModal(
         "No Wallet".to_string()
     };
 
+    let watch_only = cannot_sign_watch_only(wallet.as_ref(), hardware_wallet.is_some());
+
     rsx! {
         div {
             class: "modal-backdrop",  // Changed from "modal-overlay"
@@ -355,12 +371,15 @@ pub fn BulkSendModal(
                 if show_hardware_approval() {
                     BulkSendHardwareApprovalOverlay {
                         selected_count: selected_tokens().len(),
+                        previews: signing_preview(),
                         oncancel: move |_| {
                             show_hardware_approval.set(false);
                             sending.set(false);
                         }
                     }
                 }
+
+                TransactionReviewOverlay { review }
                 
                 // Modal header with close button - matching other modals
                 h2 { 
@@ -387,15 +406,14 @@ pub fn BulkSendModal(
                 div {
                     class: "wallet-field",
                     label { "Recipient Address:" }
-                    input {
-                        value: "{recipient()}",
-                        oninput: move |e| {
-                            recipient.set(e.value());
+                    RecipientInput {
+                        value: recipient(),
+                        oninput: move |address: String| {
+                            recipient.set(address);
                             // Reset balance check when address changes
                             recipient_balance.set(None);
                             error_message.set(None);
                         },
-                        placeholder: "Enter Solana address"
                     }
                     
                     // Show recipient balance if checked
@@ -512,6 +530,13 @@ pub fn BulkSendModal(
                     }
                 }
                 
+                if watch_only {
+                    div {
+                        class: "error-message",
+                        "{WATCH_ONLY_MESSAGE}"
+                    }
+                }
+
                 div { 
                     class: "modal-buttons",
                     button {
@@ -521,7 +546,7 @@ pub fn BulkSendModal(
                     }
                     button {
                         class: "modal-button primary",
-                        disabled: sending() || !all_amounts_valid() || recipient().trim().is_empty(),
+                        disabled: watch_only || sending() || !all_amounts_valid() || recipient().trim().is_empty(),
                         onclick: move |_| {
                             if !sending() {
                                 sending.set(true);
@@ -529,6 +554,7 @@ pub fn BulkSendModal(
                                 
                                 // Show hardware approval overlay if using hardware wallet
                                 if hardware_wallet.is_some() {
+                                    signing_preview.set(Vec::new());
                                     show_hardware_approval.set(true);
                                     was_hardware_transaction.set(true);
                                 } else {
@@ -568,13 +594,16 @@ pub fn BulkSendModal(
                                     // Determine signer type based on available wallet
                                     let result = if let Some(ref hw) = hardware_wallet_clone {
                                         // Use hardware wallet signer
-                                        let hw_signer = HardwareSigner::from_wallet(hw.clone());
+                                        let hw_signer = PreviewSigner::new(HardwareSigner::from_wallet(hw.clone()), move |previews| {
+                                            let mut signing_preview = signing_preview;
+                                            signing_preview.set(previews);
+                                        });
                                         client.send_bulk_tokens_with_signer(&hw_signer, &recipient_address, selected_for_send).await
                                     } else if let Some(wallet_info) = wallet_info {
                                         // Use software wallet signer
-                                        match Wallet::from_wallet_info(&wallet_info) {
-                                            Ok(wallet) => {
-                                                let signer = SignerType::from_wallet(wallet);
+                                        match SignerType::from_wallet_info(&wallet_info) {
+                                            Ok(signer) => {
+                                                let signer = review.review(signer);
                                                 client.send_bulk_tokens_with_signer(&signer, &recipient_address, selected_for_send).await
                                             }
                                             Err(e) => {
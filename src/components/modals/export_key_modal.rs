@@ -0,0 +1,139 @@
+use dioxus::prelude::*;
+use crate::wallet::{SecretExportFormat, Wallet, WalletInfo};
+#[cfg(not(feature = "web"))]
+use crate::storage::export_keypair_file;
+
+/// Reveal the current wallet's private key as base58 or as a Solana CLI `id.json` keypair
+#[component]
+pub fn ExportKeyModal(wallet: Option<WalletInfo>, onclose: EventHandler<()>) -> Element {
+    // "base58" or "json"
+    let mut export_format = use_signal(|| "base58".to_string());
+    let mut revealed = use_signal(|| false);
+    let mut error_message = use_signal(|| None as Option<String>);
+    let mut saved_path = use_signal(|| None as Option<String>);
+
+    let exported = if revealed() {
+        wallet.as_ref().map(|info| {
+            Wallet::from_wallet_info(info).map(|w| {
+                w.export_secret(if export_format() == "json" {
+                    SecretExportFormat::CliJson
+                } else {
+                    SecretExportFormat::Base58Keypair
+                })
+            })
+        })
+    } else {
+        None
+    };
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Export Private Key" }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                if let Some(info) = wallet.clone() {
+                    div { class: "wallet-field",
+                        label { "Wallet:" }
+                        div { class: "address-display", "{info.name} ({info.address})" }
+                    }
+
+                    div {
+                        class: "mode-toggle",
+                        button {
+                            class: if export_format() == "base58" { "toggle-button active" } else { "toggle-button" },
+                            onclick: move |_| export_format.set("base58".to_string()),
+                            "Base58"
+                        }
+                        button {
+                            class: if export_format() == "json" { "toggle-button active" } else { "toggle-button" },
+                            onclick: move |_| export_format.set("json".to_string()),
+                            "CLI JSON (id.json)"
+                        }
+                    }
+
+                    div { class: "wallet-field",
+                        div { class: "private-key-warning",
+                            "⚠️ Anyone with this key controls the wallet. Never share it!"
+                        }
+                        match exported {
+                            Some(Ok(key)) => rsx! {
+                                div { class: "private-key-display", "{key.as_str()}" }
+                                div {
+                                    class: "key-format-info",
+                                    if export_format() == "json" {
+                                        "64-byte keypair array - use with solana-keygen and `solana --keypair`"
+                                    } else {
+                                        "Solana Keypair (64 bytes) - Compatible with Phantom, Solflare and other wallets"
+                                    }
+                                }
+                            },
+                            Some(Err(e)) => rsx! {
+                                div { class: "error-message", "{e}" }
+                            },
+                            None => rsx! {
+                                button {
+                                    class: "show-key-button",
+                                    onclick: move |_| revealed.set(true),
+                                    "Show Private Key"
+                                }
+                            },
+                        }
+                    }
+
+                    if let Some(path) = saved_path() {
+                        div {
+                            class: "info-message",
+                            "Saved keypair file to {path}"
+                        }
+                    }
+                } else {
+                    div {
+                        class: "info-message",
+                        "No wallet selected"
+                    }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Close"
+                    }
+                    if cfg!(not(feature = "web")) && export_format() == "json" && revealed() {
+                        button {
+                            class: "modal-button primary",
+                            onclick: move |_| {
+                                #[cfg(not(feature = "web"))]
+                                {
+                                    if let Some(info) = wallet.as_ref() {
+                                        match export_keypair_file(info) {
+                                            Ok(path) => {
+                                                error_message.set(None);
+                                                saved_path.set(Some(path));
+                                            }
+                                            Err(e) => error_message.set(Some(e)),
+                                        }
+                                    }
+                                }
+                            },
+                            "Save id.json"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use dioxus::prelude::*;
+use crate::address_book::{short_address, AddressBook};
+use crate::history::{self, Direction, HistoryEntry};
+use crate::transaction_notes::TransactionNotes;
+use crate::wallet::WalletInfo;
+use std::rc::Rc;
+
+const HISTORY_LIMIT: usize = 20;
+
+/// Recent transactions of the current wallet. Counterparties show as contact or
+/// wallet names where known, and local transaction notes are shown alongside.
+#[component]
+pub fn HistoryModal(
+    address: String,
+    wallets: Vec<WalletInfo>,
+    custom_rpc: Option<String>,
+    onclose: EventHandler<()>,
+) -> Element {
+    let mut entries = use_signal(|| None as Option<Vec<HistoryEntry>>);
+    let mut error_message = use_signal(|| None as Option<String>);
+    // Read once per modal; contacts and notes can't change while it is open
+    let address_book = use_hook(|| Rc::new(AddressBook::load()));
+    let notes = use_hook(|| Rc::new(TransactionNotes::load()));
+
+    use_effect({
+        let address = address.clone();
+        move || {
+            let address = address.clone();
+            let custom_rpc = custom_rpc.clone();
+            spawn(async move {
+                match history::load_history(&address, HISTORY_LIMIT, custom_rpc.as_deref()).await {
+                    Ok(loaded) => entries.set(Some(loaded)),
+                    Err(e) => error_message.set(Some(e)),
+                }
+            });
+        }
+    });
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "History" }
+                div { class: "help-text", "{short_address(&address)}" }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                if let Some(list) = entries() {
+                    if list.is_empty() {
+                        div { class: "help-text", "No transactions yet." }
+                    }
+                    div { class: "candidate-list",
+                        for entry in list {
+                            a {
+                                key: "{entry.info.signature}",
+                                class: "candidate-row history-row",
+                                href: "https://solscan.io/tx/{entry.info.signature}",
+                                target: "_blank",
+                                rel: "noopener noreferrer",
+                                div { class: "candidate-info",
+                                    div { class: "candidate-path",
+                                        "{entry.info.time_ago} · {entry.info.status}"
+                                    }
+                                    if entry.counterparties.is_empty() {
+                                        div { class: "candidate-address", "{short_address(&entry.info.signature)}" }
+                                    }
+                                    for party in entry.counterparties.iter() {
+                                        div {
+                                            class: "candidate-address",
+                                            if party.direction == Direction::Sent { "→ " } else { "← " }
+                                            "{address_book.display_name(&party.address, &wallets)}"
+                                        }
+                                    }
+                                    if let Some(note) = notes.get(&entry.info.signature) {
+                                        div { class: "help-text", "📝 {note}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else if error_message().is_none() {
+                    div { class: "help-text", "Loading transactions..." }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Close"
+                    }
+                }
+            }
+        }
+    }
+}
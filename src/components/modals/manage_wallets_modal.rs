@@ -0,0 +1,243 @@
+use dioxus::prelude::*;
+use crate::wallet::WalletInfo;
+use crate::wallet_repository::{WalletRepository, WALLET_COLORS};
+
+fn short_address(address: &str) -> String {
+    if address.len() >= 8 {
+        format!("{}...{}", &address[..4], &address[address.len() - 4..])
+    } else {
+        address.to_string()
+    }
+}
+
+fn format_timestamp(timestamp: Option<i64>) -> String {
+    timestamp
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "never".to_string())
+}
+
+/// Rename, reorder, tag, color and delete stored wallets.
+/// Every change is saved immediately and the new list is passed to `onchange`.
+#[component]
+pub fn ManageWalletsModal(
+    wallets: Vec<WalletInfo>,
+    onclose: EventHandler<()>,
+    onchange: EventHandler<Vec<WalletInfo>>,
+) -> Element {
+    let mut editing = use_signal(|| None as Option<String>);
+    let mut edit_name = use_signal(|| "".to_string());
+    let mut edit_tags = use_signal(|| "".to_string());
+    let mut confirm_delete = use_signal(|| false);
+    let mut error_message = use_signal(|| None as Option<String>);
+
+    // Run one repository operation and publish the result
+    let mut apply = move |change: &dyn Fn(&mut WalletRepository) -> Result<(), String>| {
+        let mut repository = WalletRepository::load();
+        match change(&mut repository) {
+            Ok(()) => {
+                error_message.set(None);
+                onchange.call(repository.wallets().to_vec());
+                true
+            }
+            Err(e) => {
+                error_message.set(Some(e));
+                false
+            }
+        }
+    };
+
+    let last_index = wallets.len().saturating_sub(1);
+    let selected = editing().and_then(|address| wallets.iter().find(|w| w.address == address).cloned());
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Manage Wallets" }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                if let Some(wallet) = selected {
+                    div { class: "wallet-field",
+                        label { "Name:" }
+                        input {
+                            value: "{edit_name}",
+                            oninput: move |e| edit_name.set(e.value()),
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Tags (comma separated):" }
+                        input {
+                            value: "{edit_tags}",
+                            oninput: move |e| edit_tags.set(e.value()),
+                            placeholder: "trading, cold storage"
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Color:" }
+                        div { class: "color-swatches",
+                            for color in WALLET_COLORS.iter().copied() {
+                                button {
+                                    class: if wallet.metadata.color.as_deref() == Some(color) { "color-swatch active" } else { "color-swatch" },
+                                    style: "background-color: {color}",
+                                    onclick: {
+                                        let address = wallet.address.clone();
+                                        move |_| {
+                                            apply(&|repo| repo.set_color(&address, Some(color)));
+                                        }
+                                    },
+                                }
+                            }
+                            button {
+                                class: "color-swatch none",
+                                onclick: {
+                                    let address = wallet.address.clone();
+                                    move |_| {
+                                        apply(&|repo| repo.set_color(&address, None));
+                                    }
+                                },
+                                "✕"
+                            }
+                        }
+                    }
+                    div { class: "help-text",
+                        "Added {format_timestamp(wallet.metadata.created_at)} · Last used {format_timestamp(wallet.metadata.last_used_at)}"
+                    }
+
+                    if confirm_delete() {
+                        div { class: "private-key-warning",
+                            if wallet.is_watch_only() {
+                                "Stop tracking {wallet.name}?"
+                            } else {
+                                "Delete {wallet.name}? Its key is removed from this device. Export it first unless you have a backup."
+                            }
+                        }
+                    }
+
+                    div { class: "modal-buttons",
+                        if confirm_delete() {
+                            button {
+                                class: "modal-button cancel",
+                                onclick: move |_| confirm_delete.set(false),
+                                "Keep"
+                            }
+                            button {
+                                class: "modal-button danger",
+                                onclick: {
+                                    let address = wallet.address.clone();
+                                    move |_| {
+                                        if apply(&|repo| repo.delete(&address).map(|_| ())) {
+                                            confirm_delete.set(false);
+                                            editing.set(None);
+                                        }
+                                    }
+                                },
+                                "Delete"
+                            }
+                        } else {
+                            button {
+                                class: "modal-button danger",
+                                onclick: move |_| confirm_delete.set(true),
+                                "Delete"
+                            }
+                            button {
+                                class: "modal-button cancel",
+                                onclick: move |_| editing.set(None),
+                                "Back"
+                            }
+                            button {
+                                class: "modal-button primary",
+                                onclick: {
+                                    let address = wallet.address.clone();
+                                    move |_| {
+                                        let tags: Vec<String> = edit_tags().split(',').map(str::to_string).collect();
+                                        let name = edit_name();
+                                        if apply(&|repo| repo.update(&address, &name, &tags)) {
+                                            editing.set(None);
+                                        }
+                                    }
+                                },
+                                "Save"
+                            }
+                        }
+                    }
+                } else {
+                    div { class: "candidate-list",
+                        for (index, wallet) in wallets.iter().cloned().enumerate() {
+                            div {
+                                key: "{wallet.address}",
+                                class: "candidate-row",
+                                onclick: {
+                                    let wallet = wallet.clone();
+                                    move |_| {
+                                        edit_name.set(wallet.name.clone());
+                                        edit_tags.set(wallet.metadata.tags.join(", "));
+                                        confirm_delete.set(false);
+                                        editing.set(Some(wallet.address.clone()));
+                                    }
+                                },
+                                div {
+                                    class: "wallet-color-dot",
+                                    style: "background-color: {wallet.metadata.color.clone().unwrap_or_default()}",
+                                }
+                                div { class: "candidate-info",
+                                    div { class: "candidate-path",
+                                        if wallet.is_watch_only() { "👁 " }
+                                        "{wallet.name}"
+                                        for tag in wallet.metadata.tags.iter() {
+                                            span { class: "wallet-tag", "{tag}" }
+                                        }
+                                    }
+                                    div { class: "candidate-address", "{short_address(&wallet.address)}" }
+                                }
+                                button {
+                                    class: "reorder-button",
+                                    disabled: index == 0,
+                                    onclick: {
+                                        let address = wallet.address.clone();
+                                        move |e: MouseEvent| {
+                                            e.stop_propagation();
+                                            apply(&|repo| repo.move_to(&address, index.saturating_sub(1)));
+                                        }
+                                    },
+                                    "▲"
+                                }
+                                button {
+                                    class: "reorder-button",
+                                    disabled: index == last_index,
+                                    onclick: {
+                                        let address = wallet.address.clone();
+                                        move |e: MouseEvent| {
+                                            e.stop_propagation();
+                                            apply(&|repo| repo.move_to(&address, index + 1));
+                                        }
+                                    },
+                                    "▼"
+                                }
+                            }
+                        }
+                    }
+
+                    div { class: "modal-buttons",
+                        button {
+                            class: "modal-button cancel",
+                            onclick: move |_| onclose.call(()),
+                            "Done"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
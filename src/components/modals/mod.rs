@@ -9,6 +9,19 @@ pub mod stake_modal;
 pub mod background_modal;
 pub mod currency_modal;
 pub mod bulk_send_modal;
+pub mod unlock_modal;
+pub mod export_key_modal;
+pub mod watch_only_modal;
+pub mod vanity_modal;
+pub mod manage_wallets_modal;
+pub mod backup_modal;
+pub mod shamir_modal;
+pub mod address_book_modal;
+pub mod history_modal;
+pub mod sign_message_modal;
+pub mod offline_sign_modal;
+pub mod nonce_modal;
+pub mod signing_policy_modal;
 
 pub use wallet_modal::WalletModal;
 pub use rpc_modal::RpcModal;
@@ -20,4 +33,17 @@ pub use jito_modal::JitoModal;
 pub use stake_modal::StakeModal;
 pub use background_modal::BackgroundModal;
 pub use currency_modal::CurrencyModal;
-pub use bulk_send_modal::BulkSendModal;
\ No newline at end of file
+pub use bulk_send_modal::BulkSendModal;
+pub use unlock_modal::UnlockModal;
+pub use export_key_modal::ExportKeyModal;
+pub use watch_only_modal::WatchOnlyModal;
+pub use vanity_modal::VanityModal;
+pub use manage_wallets_modal::ManageWalletsModal;
+pub use backup_modal::BackupModal;
+pub use shamir_modal::ShamirModal;
+pub use address_book_modal::AddressBookModal;
+pub use sign_message_modal::SignMessageModal;
+pub use offline_sign_modal::OfflineSignModal;
+pub use nonce_modal::NonceModal;
+pub use signing_policy_modal::SigningPolicyModal;
+pub use history_modal::HistoryModal;
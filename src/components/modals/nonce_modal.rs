@@ -0,0 +1,263 @@
+use dioxus::prelude::*;
+use crate::hardware::HardwareWallet;
+use crate::nonce::{NonceAccountInfo, NonceClient};
+use crate::signing::hardware::HardwareSigner;
+use crate::signing::SignerType;
+use crate::wallet::WalletInfo;
+use std::sync::Arc;
+
+/// Create, inspect and manage durable nonce accounts for the current wallet.
+/// Transactions built on a nonce account don't expire until the nonce is advanced.
+#[component]
+pub fn NonceModal(
+    wallet: Option<WalletInfo>,
+    hardware_wallet: Option<Arc<HardwareWallet>>,
+    custom_rpc: Option<String>,
+    onclose: EventHandler<()>,
+) -> Element {
+    let mut address = use_signal(|| "".to_string());
+    let mut info = use_signal(|| None as Option<NonceAccountInfo>);
+    // "advance", "withdraw" or "authorize"
+    let mut action = use_signal(|| "advance".to_string());
+    let mut destination = use_signal(|| wallet.as_ref().map(|w| w.address.clone()).unwrap_or_default());
+    let mut amount = use_signal(|| "".to_string());
+    let mut new_authority = use_signal(|| "".to_string());
+    let mut busy = use_signal(|| false);
+    let mut last_signature = use_signal(|| None as Option<String>);
+    let mut error_message = use_signal(|| None as Option<String>);
+
+    let signer_for = {
+        let wallet = wallet.clone();
+        let hardware_wallet = hardware_wallet.clone();
+        move || -> Result<SignerType, String> {
+            match (&hardware_wallet, &wallet) {
+                (Some(hw), _) => Ok(SignerType::Hardware(HardwareSigner::from_wallet(hw.clone()))),
+                (None, Some(info)) => SignerType::from_wallet_info(info),
+                (None, None) => Err("No wallet selected".to_string()),
+            }
+        }
+    };
+
+    let load = {
+        let custom_rpc = custom_rpc.clone();
+        move |nonce_address: String| {
+            let custom_rpc = custom_rpc.clone();
+            spawn(async move {
+                let client = NonceClient::new(custom_rpc.as_deref());
+                match client.get_nonce_account(nonce_address.trim()).await {
+                    Ok(loaded) => {
+                        error_message.set(None);
+                        info.set(Some(loaded));
+                    }
+                    Err(e) => {
+                        info.set(None);
+                        error_message.set(Some(e.to_string()));
+                    }
+                }
+            });
+        }
+    };
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Nonce Accounts" }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                if let Some(signature) = last_signature() {
+                    div { class: "info-message", "✅ Transaction sent" }
+                    div { class: "address-display", "{signature}" }
+                }
+
+                div { class: "wallet-field",
+                    label { "Nonce account:" }
+                    input {
+                        value: "{address}",
+                        oninput: move |e| {
+                            address.set(e.value());
+                            info.set(None);
+                        },
+                        placeholder: "Nonce account address"
+                    }
+                }
+
+                if let Some(nonce) = info() {
+                    div { class: "wallet-field",
+                        label { "Authority:" }
+                        div { class: "address-display", "{nonce.authority}" }
+                    }
+                    div { class: "wallet-field",
+                        label { "Current nonce:" }
+                        div { class: "address-display", "{nonce.nonce}" }
+                    }
+                    div { class: "wallet-field",
+                        label { "Balance:" }
+                        div { class: "address-display", "{nonce.balance as f64 / 1_000_000_000.0} SOL" }
+                    }
+
+                    div { class: "wallet-field",
+                        label { "Action:" }
+                        select {
+                            value: "{action}",
+                            onchange: move |e| action.set(e.value()),
+                            option { value: "advance", "Advance nonce" }
+                            option { value: "withdraw", "Withdraw" }
+                            option { value: "authorize", "Change authority" }
+                        }
+                    }
+                    if action() == "withdraw" {
+                        div { class: "wallet-field",
+                            label { "To:" }
+                            input {
+                                value: "{destination}",
+                                oninput: move |e| destination.set(e.value()),
+                            }
+                        }
+                        div { class: "wallet-field",
+                            label { "Amount (SOL):" }
+                            input {
+                                r#type: "number",
+                                value: "{amount}",
+                                oninput: move |e| amount.set(e.value()),
+                            }
+                        }
+                        div { class: "help-text", "Withdrawing the whole balance closes the account." }
+                    } else if action() == "authorize" {
+                        div { class: "wallet-field",
+                            label { "New authority:" }
+                            input {
+                                value: "{new_authority}",
+                                oninput: move |e| new_authority.set(e.value()),
+                            }
+                        }
+                        div { class: "help-text", "Only the new authority can use or change the nonce afterwards." }
+                    } else {
+                        div { class: "help-text", "Advancing invalidates anything already signed against the current nonce." }
+                    }
+                } else {
+                    div { class: "help-text",
+                        "Create a nonce account to sign transactions that don't expire, for offline or slow hardware signing."
+                    }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Close"
+                    }
+                    if info().is_none() {
+                        button {
+                            class: "modal-button cancel",
+                            disabled: busy() || address().trim().is_empty(),
+                            onclick: {
+                                let load = load.clone();
+                                move |_| load(address())
+                            },
+                            "Load"
+                        }
+                        button {
+                            class: "modal-button primary",
+                            disabled: busy() || (wallet.is_none() && hardware_wallet.is_none()),
+                            onclick: {
+                                let signer_for = signer_for.clone();
+                                let custom_rpc = custom_rpc.clone();
+                                let load = load.clone();
+                                move |_| {
+                                    let signer = match signer_for() {
+                                        Ok(signer) => signer,
+                                        Err(e) => {
+                                            error_message.set(Some(e));
+                                            return;
+                                        }
+                                    };
+                                    let custom_rpc = custom_rpc.clone();
+                                    let load = load.clone();
+                                    busy.set(true);
+                                    error_message.set(None);
+                                    spawn(async move {
+                                        let client = NonceClient::new(custom_rpc.as_deref());
+                                        match client.create_nonce_account(&signer, None).await {
+                                            Ok((nonce_address, signature)) => {
+                                                last_signature.set(Some(signature));
+                                                address.set(nonce_address.to_string());
+                                                load(nonce_address.to_string());
+                                            }
+                                            Err(e) => error_message.set(Some(e.to_string())),
+                                        }
+                                        busy.set(false);
+                                    });
+                                }
+                            },
+                            if busy() { "Creating..." } else { "Create New" }
+                        }
+                    } else {
+                        button {
+                            class: "modal-button primary",
+                            disabled: busy(),
+                            onclick: {
+                                let signer_for = signer_for.clone();
+                                let custom_rpc = custom_rpc.clone();
+                                let load = load.clone();
+                                move |_| {
+                                    let signer = match signer_for() {
+                                        Ok(signer) => signer,
+                                        Err(e) => {
+                                            error_message.set(Some(e));
+                                            return;
+                                        }
+                                    };
+                                    let lamports = if action() == "withdraw" {
+                                        match amount().trim().parse::<f64>() {
+                                            Ok(sol) if sol > 0.0 => (sol * 1_000_000_000.0) as u64,
+                                            _ => {
+                                                error_message.set(Some("Enter a valid amount".to_string()));
+                                                return;
+                                            }
+                                        }
+                                    } else {
+                                        0
+                                    };
+                                    let custom_rpc = custom_rpc.clone();
+                                    let load = load.clone();
+                                    let nonce_address = address().trim().to_string();
+                                    busy.set(true);
+                                    error_message.set(None);
+                                    spawn(async move {
+                                        let client = NonceClient::new(custom_rpc.as_deref());
+                                        let result = match action().as_str() {
+                                            "withdraw" => client.withdraw(&signer, &nonce_address, destination().trim(), lamports).await,
+                                            "authorize" => client.set_authority(&signer, &nonce_address, new_authority().trim()).await,
+                                            _ => client.advance_nonce(&signer, &nonce_address).await,
+                                        };
+                                        match result {
+                                            Ok(signature) => {
+                                                last_signature.set(Some(signature));
+                                                load(nonce_address);
+                                            }
+                                            Err(e) => error_message.set(Some(e.to_string())),
+                                        }
+                                        busy.set(false);
+                                    });
+                                }
+                            },
+                            if busy() { "Sending..." } else { "Submit" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use dioxus::prelude::*;
+use crate::components::common::Token;
+use crate::components::transaction_preview::TransactionPreviewView;
+use crate::components::modals::receive_modal::generate_qr_code_svg;
+use crate::hardware::HardwareWallet;
+use crate::offline::{self, MultipartDecoder, SignatureReply};
+use crate::preview::TransactionPreview;
+use crate::qr;
+use crate::signing::hardware::HardwareSigner;
+use crate::signing::partial::PartialTransaction;
+use crate::signing::SignerType;
+use crate::transaction::TransactionClient;
+use crate::wallet::WalletInfo;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+/// How long each frame of a multipart QR stays on screen
+const QR_FRAME_INTERVAL: Duration = Duration::from_millis(900);
+
+/// Air-gapped signing. "Prepare" runs on the online device: build a transfer for the
+/// current (usually watch-only) wallet, show it as QR/text, then attach the returned
+/// signature and broadcast. "Sign" runs on the offline device holding the key.
+#[component]
+pub fn OfflineSignModal(
+    wallet: Option<WalletInfo>,
+    hardware_wallet: Option<Arc<HardwareWallet>>,
+    tokens: Vec<Token>,
+    custom_rpc: Option<String>,
+    onclose: EventHandler<()>,
+) -> Element {
+    // "prepare" or "sign"
+    let mut mode = use_signal(|| "prepare".to_string());
+    let mut recipient = use_signal(|| "".to_string());
+    let mut amount = use_signal(|| "".to_string());
+    let mut token_mint = use_signal(|| SOL_MINT.to_string());
+    let mut nonce_account = use_signal(|| "".to_string());
+    // Transaction waiting for signatures, on either side
+    let mut request = use_signal(|| None as Option<PartialTransaction>);
+    // Text to show as QR: the request when preparing, the signature reply when signing
+    let mut shown_payload = use_signal(|| None as Option<String>);
+    let mut reply_input = use_signal(|| "".to_string());
+    let mut request_input = use_signal(|| "".to_string());
+    let mut decoder = use_signal(MultipartDecoder::new);
+    let mut frame = use_signal(|| 0usize);
+    let mut busy = use_signal(|| false);
+    let mut sent_signature = use_signal(|| None as Option<String>);
+    let mut error_message = use_signal(|| None as Option<String>);
+
+    // Cycle through the parts of a multipart QR so the other device can scan them in turn
+    use_future(move || async move {
+        loop {
+            tokio::time::sleep(QR_FRAME_INTERVAL).await;
+            frame += 1;
+        }
+    });
+
+    let mut switch_mode = move |new_mode: &str| {
+        mode.set(new_mode.to_string());
+        request.set(None);
+        shown_payload.set(None);
+        decoder.write().reset();
+        error_message.set(None);
+    };
+
+    let frames = shown_payload().map(|payload| offline::split_qr_parts(&payload)).unwrap_or_default();
+    let frame_index = frame() % frames.len().max(1);
+    let current_frame = frames.get(frame_index).cloned();
+    let frame_number = frame_index + 1;
+    let frame_count = frames.len();
+    let preview = request().map(|r| TransactionPreview::new(r.message()));
+    let missing_signers = request().map(|r| r.missing_signers()).unwrap_or_default();
+    let (parts_scanned, parts_total) = decoder.read().progress();
+
+    // Feed scanned or pasted text to the decoder; loads the request once complete
+    let mut accept_request_text = move |text: String| {
+        let result = decoder.write().add(&text);
+        match result.and_then(|payload| payload.map(|p| offline::decode_request(&p)).transpose()) {
+            Ok(Some(decoded)) => {
+                decoder.write().reset();
+                error_message.set(None);
+                request.set(Some(decoded));
+            }
+            Ok(None) => error_message.set(None),
+            Err(e) => error_message.set(Some(e.to_string())),
+        }
+    };
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Offline Signing" }
+
+                div {
+                    class: "mode-toggle",
+                    button {
+                        class: if mode() == "prepare" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| switch_mode("prepare"),
+                        "Prepare (online)"
+                    }
+                    button {
+                        class: if mode() == "sign" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| switch_mode("sign"),
+                        "Sign (offline)"
+                    }
+                }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                if let Some(signature) = sent_signature() {
+                    div { class: "info-message", "✅ Transaction sent" }
+                    div { class: "address-display", "{signature}" }
+                }
+
+                if let Some(frame_text) = current_frame {
+                    div { class: "wallet-field",
+                        label {
+                            if mode() == "prepare" { "Scan on the offline device:" } else { "Scan on the online device:" }
+                        }
+                        div {
+                            class: "qr-code-container",
+                            div {
+                                class: "qr-code",
+                                dangerous_inner_html: "{generate_qr_code_svg(&frame_text)}"
+                            }
+                        }
+                        if frame_count > 1 {
+                            div { class: "help-text", "Part {frame_number} of {frame_count}, keep scanning until all parts are read" }
+                        }
+                        div { class: "private-key-display", "{shown_payload().unwrap_or_default()}" }
+                    }
+                }
+
+                if let Some(preview) = preview {
+                    div { class: "wallet-field",
+                        label { "This transaction will:" }
+                        TransactionPreviewView { previews: vec![preview] }
+                        if !missing_signers.is_empty() {
+                            div { class: "help-text",
+                                "Waiting for signatures from: "
+                                for key in missing_signers.iter() {
+                                    span { class: "wallet-tag", "{key}" }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if mode() == "prepare" {
+                    if request().is_none() {
+                        div { class: "wallet-field",
+                            label { "Recipient:" }
+                            input {
+                                value: "{recipient}",
+                                oninput: move |e| recipient.set(e.value()),
+                                placeholder: "Solana address"
+                            }
+                        }
+                        div { class: "wallet-field",
+                            label { "Token:" }
+                            select {
+                                value: "{token_mint}",
+                                onchange: move |e| token_mint.set(e.value()),
+                                option { value: SOL_MINT, selected: token_mint() == SOL_MINT, "SOL" }
+                                for token in tokens.iter().filter(|t| t.mint != SOL_MINT) {
+                                    option {
+                                        value: "{token.mint}",
+                                        selected: token.mint == token_mint(),
+                                        "{token.symbol}"
+                                    }
+                                }
+                            }
+                        }
+                        div { class: "wallet-field",
+                            label { "Amount:" }
+                            input {
+                                r#type: "number",
+                                value: "{amount}",
+                                oninput: move |e| amount.set(e.value()),
+                            }
+                        }
+                        div { class: "wallet-field",
+                            label { "Durable nonce account (optional):" }
+                            input {
+                                value: "{nonce_account}",
+                                oninput: move |e| nonce_account.set(e.value()),
+                                placeholder: "Nonce account address"
+                            }
+                        }
+                        div { class: "help-text",
+                            if nonce_account().trim().is_empty() {
+                                "Without a nonce account the transaction expires in about a minute, so sign and return it quickly."
+                            } else {
+                                "The transaction stays valid until the nonce account is advanced."
+                            }
+                        }
+                    } else {
+                        div { class: "wallet-field",
+                            label { "Signature from the offline device:" }
+                            textarea {
+                                value: "{reply_input}",
+                                oninput: move |e| reply_input.set(e.value()),
+                                placeholder: "unruggable-sig:...",
+                                rows: 3,
+                            }
+                        }
+                        div { class: "wallet-field",
+                            label { "Or scan the signature QR code image:" }
+                            input {
+                                r#type: "file",
+                                accept: "image/png,image/jpeg",
+                                onchange: move |evt| {
+                                    spawn(async move {
+                                        let Some(file_engine) = evt.files() else { return };
+                                        let Some(file_name) = file_engine.files().into_iter().next() else { return };
+                                        let Some(bytes) = file_engine.read_file(&file_name).await else {
+                                            error_message.set(Some(format!("Could not read {}", file_name)));
+                                            return;
+                                        };
+                                        match qr::decode_qr_image(&bytes) {
+                                            Ok(payload) => reply_input.set(payload.trim().to_string()),
+                                            Err(e) => error_message.set(Some(e)),
+                                        }
+                                    });
+                                }
+                            }
+                        }
+                    }
+                } else if request().is_none() {
+                    div { class: "wallet-field",
+                        label { "Signing request:" }
+                        textarea {
+                            value: "{request_input}",
+                            oninput: move |e| request_input.set(e.value()),
+                            placeholder: "unruggable-tx:...",
+                            rows: 4,
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Or scan the request QR code images:" }
+                        input {
+                            r#type: "file",
+                            accept: "image/png,image/jpeg",
+                            multiple: true,
+                            onchange: move |evt| {
+                                spawn(async move {
+                                    let Some(file_engine) = evt.files() else { return };
+                                    for file_name in file_engine.files() {
+                                        let Some(bytes) = file_engine.read_file(&file_name).await else {
+                                            error_message.set(Some(format!("Could not read {}", file_name)));
+                                            return;
+                                        };
+                                        match qr::decode_qr_image(&bytes) {
+                                            Ok(payload) => accept_request_text(payload),
+                                            Err(e) => error_message.set(Some(e)),
+                                        }
+                                    }
+                                });
+                            }
+                        }
+                        if parts_total > 1 {
+                            div { class: "help-text", "Scanned {parts_scanned} of {parts_total} parts" }
+                        }
+                    }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Close"
+                    }
+                    if mode() == "prepare" && request().is_none() {
+                        button {
+                            class: "modal-button primary",
+                            disabled: busy() || wallet.is_none() || recipient().trim().is_empty() || amount().trim().is_empty(),
+                            onclick: {
+                                let wallet = wallet.clone();
+                                let custom_rpc = custom_rpc.clone();
+                                move |_| {
+                                    let Some(info) = wallet.clone() else { return };
+                                    let Ok(amount_value) = amount().trim().parse::<f64>() else {
+                                        error_message.set(Some("Enter a valid amount".to_string()));
+                                        return;
+                                    };
+                                    let custom_rpc = custom_rpc.clone();
+                                    let nonce = match nonce_account().trim() {
+                                        "" => None,
+                                        address => match Pubkey::from_str(address) {
+                                            Ok(pubkey) => Some(pubkey),
+                                            Err(_) => {
+                                                error_message.set(Some("Invalid nonce account address".to_string()));
+                                                return;
+                                            }
+                                        },
+                                    };
+                                    busy.set(true);
+                                    error_message.set(None);
+                                    sent_signature.set(None);
+                                    spawn(async move {
+                                        let mut client = TransactionClient::new(custom_rpc.as_deref());
+                                        if let Some(nonce) = nonce {
+                                            client = client.with_durable_nonce(nonce);
+                                        }
+                                        let to = recipient().trim().to_string();
+                                        let result = if token_mint() == SOL_MINT {
+                                            client.build_sol_transfer(&info.address, &to, amount_value).await
+                                        } else {
+                                            client.build_spl_transfer(&info.address, &to, amount_value, &token_mint()).await
+                                        };
+                                        match result {
+                                            Ok(transaction) => {
+                                                frame.set(0);
+                                                shown_payload.set(Some(offline::encode_request(&transaction)));
+                                                request.set(Some(transaction));
+                                            }
+                                            Err(e) => error_message.set(Some(e.to_string())),
+                                        }
+                                        busy.set(false);
+                                    });
+                                }
+                            },
+                            if busy() { "Building..." } else { "Create Request" }
+                        }
+                    } else if mode() == "prepare" {
+                        button {
+                            class: "modal-button primary",
+                            disabled: busy() || reply_input().trim().is_empty(),
+                            onclick: {
+                                let custom_rpc = custom_rpc.clone();
+                                move |_| {
+                                    let Some(mut transaction) = request() else { return };
+                                    let attached = SignatureReply::from_text(&reply_input())
+                                        .and_then(|reply| offline::attach_signature(&mut transaction, &reply));
+                                    if let Err(e) = attached {
+                                        error_message.set(Some(e.to_string()));
+                                        return;
+                                    }
+                                    request.set(Some(transaction.clone()));
+                                    if !transaction.is_complete() {
+                                        // More signers to go: hand out the updated request
+                                        shown_payload.set(Some(offline::encode_request(&transaction)));
+                                        reply_input.set(String::new());
+                                        return;
+                                    }
+
+                                    let custom_rpc = custom_rpc.clone();
+                                    busy.set(true);
+                                    error_message.set(None);
+                                    spawn(async move {
+                                        let client = TransactionClient::new(custom_rpc.as_deref());
+                                        match client.send_partial_transaction(transaction).await {
+                                            Ok(signature) => {
+                                                sent_signature.set(Some(signature));
+                                                request.set(None);
+                                                shown_payload.set(None);
+                                                reply_input.set(String::new());
+                                            }
+                                            Err(e) => error_message.set(Some(e.to_string())),
+                                        }
+                                        busy.set(false);
+                                    });
+                                }
+                            },
+                            if busy() { "Sending..." } else { "Attach & Broadcast" }
+                        }
+                    } else if request().is_none() {
+                        button {
+                            class: "modal-button primary",
+                            disabled: request_input().trim().is_empty(),
+                            onclick: move |_| accept_request_text(request_input()),
+                            "Review"
+                        }
+                    } else if shown_payload().is_none() {
+                        button {
+                            class: "modal-button primary",
+                            disabled: busy() || (wallet.is_none() && hardware_wallet.is_none()),
+                            onclick: {
+                                let wallet = wallet.clone();
+                                let hardware_wallet = hardware_wallet.clone();
+                                move |_| {
+                                    let signer = match (&hardware_wallet, &wallet) {
+                                        (Some(hw), _) => SignerType::Hardware(HardwareSigner::from_wallet(hw.clone())),
+                                        (None, Some(info)) => match SignerType::from_wallet_info(info) {
+                                            Ok(signer) => signer,
+                                            Err(e) => {
+                                                error_message.set(Some(e));
+                                                return;
+                                            }
+                                        },
+                                        (None, None) => return,
+                                    };
+                                    let Some(mut transaction) = request() else { return };
+
+                                    busy.set(true);
+                                    error_message.set(None);
+                                    spawn(async move {
+                                        match offline::sign_request(&mut transaction, &signer).await {
+                                            Ok(reply) => {
+                                                frame.set(0);
+                                                shown_payload.set(Some(reply.to_text()));
+                                                request.set(Some(transaction));
+                                            }
+                                            Err(e) => error_message.set(Some(e.to_string())),
+                                        }
+                                        busy.set(false);
+                                    });
+                                }
+                            },
+                            if busy() { "Signing..." } else { "Sign" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -181,7 +181,7 @@ fn handle_copy(address: String, mut copying: Signal<bool>, mut copied: Signal<bo
 }
 
 // Helper function to generate QR code as SVG
-fn generate_qr_code_svg(data: &str) -> String {
+pub(crate) fn generate_qr_code_svg(data: &str) -> String {
     match QrCode::new(data) {
         Ok(qr_code) => {
             // Generate SVG with proper styling
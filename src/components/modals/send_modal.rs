@@ -1,14 +1,19 @@
 use dioxus::prelude::*;
-use crate::wallet::{Wallet, WalletInfo};
+use crate::wallet::{cannot_sign_watch_only, WalletInfo, WATCH_ONLY_MESSAGE};
 use crate::hardware::HardwareWallet;
 use crate::transaction::TransactionClient;
 use crate::signing::hardware::HardwareSigner;
+use crate::signing::SignerType;
 use crate::rpc;
+use crate::components::recipient_input::RecipientInput;
+use crate::components::transaction_note_input::TransactionNoteInput;
+use crate::components::transaction_preview::{use_transaction_review, TransactionPreviewView, TransactionReviewOverlay};
+use crate::preview::{PreviewSigner, TransactionPreview};
 use std::sync::Arc;
 
 /// Hardware wallet approval overlay component shown during transaction signing
 #[component]
-fn HardwareApprovalOverlay(oncancel: EventHandler<()>) -> Element {
+fn HardwareApprovalOverlay(previews: Vec<TransactionPreview>, oncancel: EventHandler<()>) -> Element {
     rsx! {
         div {
             class: "hardware-approval-overlay",
@@ -41,6 +46,10 @@ fn HardwareApprovalOverlay(oncancel: EventHandler<()>) -> Element {
                     class: "hardware-approval-text",
                     "Please check your hardware wallet and confirm the transaction details."
                 }
+
+                if !previews.is_empty() {
+                    TransactionPreviewView { previews: previews.clone() }
+                }
                 
                 div {
                     class: "hardware-steps",
@@ -68,6 +77,7 @@ pub fn TransactionSuccessModal(
     was_hardware_wallet: bool,
     onclose: EventHandler<()>,
 ) -> Element {
+    let note_signature = signature.clone();
     // Explorer links for multiple explorers
     let solana_explorer_url = format!("https://explorer.solana.com/tx/{}", signature);
     let solscan_url = format!("https://solscan.io/tx/{}", signature);
@@ -126,6 +136,8 @@ pub fn TransactionSuccessModal(
                         }
                     }
                     
+                    TransactionNoteInput { signature: note_signature }
+
                     div {
                         class: "explorer-links",
                         p { "View transaction in explorer:" }
@@ -201,6 +213,9 @@ pub fn SendModalWithHardware(
     
     // Add state for hardware wallet approval overlay - always declared
     let mut show_hardware_approval = use_signal(|| false);
+    // What the hardware wallet is being asked to sign, filled in just before signing
+    let mut signing_preview = use_signal_sync(Vec::<TransactionPreview>::new);
+    let review = use_transaction_review();
 
     // Use all effect hooks unconditionally
     let custom_rpc_for_effect = custom_rpc.clone();
@@ -271,6 +286,8 @@ pub fn SendModalWithHardware(
         "No Wallet".to_string()
     };
 
+    let watch_only = cannot_sign_watch_only(wallet.as_ref(), hardware_wallet.is_some());
+
     rsx! {
         div {
             class: "modal-backdrop",
@@ -284,6 +301,7 @@ pub fn SendModalWithHardware(
                 // Hardware approval overlay - shown when waiting for hardware confirmation
                 if show_hardware_approval() {
                     HardwareApprovalOverlay {
+                        previews: signing_preview(),
                         oncancel: move |_| {
                             show_hardware_approval.set(false);
                             sending.set(false);
@@ -291,6 +309,8 @@ pub fn SendModalWithHardware(
                     }
                 }
 
+                TransactionReviewOverlay { review }
+
                 h2 { class: "modal-title",
                     if hardware_wallet.is_some() {
                         "Send SOL (Hardware Wallet)"
@@ -322,10 +342,10 @@ pub fn SendModalWithHardware(
                 div {
                     class: "wallet-field",
                     label { "Recipient Address:" }
-                    input {
-                        value: "{recipient}",
-                        oninput: move |e| recipient.set(e.value()),
-                        placeholder: "Enter Solana address"
+                    RecipientInput {
+                        value: recipient(),
+                        oninput: move |address: String| recipient.set(address),
+                        token_mint: Some("So11111111111111111111111111111111111111112".to_string()),
                     }
                     // Show recipient balance if available
                     if checking_balance() {
@@ -361,6 +381,13 @@ pub fn SendModalWithHardware(
                     }
                 }
 
+                if watch_only {
+                    div {
+                        class: "error-message",
+                        "{WATCH_ONLY_MESSAGE}"
+                    }
+                }
+
                 div { class: "modal-buttons",
                     button {
                         class: "modal-button cancel",
@@ -375,6 +402,7 @@ pub fn SendModalWithHardware(
 
                             // Show hardware approval overlay if using hardware wallet
                             if hardware_wallet.is_some() {
+                                signing_preview.set(Vec::new());
                                 show_hardware_approval.set(true);
                                 was_hardware_transaction.set(true);
                             } else {
@@ -423,7 +451,10 @@ pub fn SendModalWithHardware(
 
                                 // Use hardware wallet if available, otherwise use software wallet
                                 if let Some(hw) = hardware_wallet_clone {
-                                    let hw_signer = HardwareSigner::from_wallet(hw.clone());
+                                    let hw_signer = PreviewSigner::new(HardwareSigner::from_wallet(hw.clone()), move |previews| {
+                                        let mut signing_preview = signing_preview;
+                                        signing_preview.set(previews);
+                                    });
                                     match client.send_sol_with_signer(&hw_signer, &recipient_address, amount_value).await {
                                         Ok(signature) => {
                                             println!("Transaction sent with hardware wallet: {}", signature);
@@ -453,11 +484,12 @@ pub fn SendModalWithHardware(
                                         }
                                     }
                                 } else if let Some(wallet_info) = wallet_info {
-                                    // Load wallet from wallet info
-                                    match Wallet::from_wallet_info(&wallet_info) {
-                                        Ok(wallet) => {
+                                    // Load the signer for this wallet (software or Mobile Wallet Adapter)
+                                    match SignerType::from_wallet_info(&wallet_info) {
+                                        Ok(signer) => {
+                                            let signer = review.review(signer);
                                             // Send transaction with amount in SOL
-                                            match client.send_sol(&wallet, &recipient_address, amount_value).await {
+                                            match client.send_sol_with_signer(&signer, &recipient_address, amount_value).await {
                                                 Ok(signature) => {
                                                     println!("Transaction sent: {}", signature);
                                                     
@@ -484,7 +516,7 @@ pub fn SendModalWithHardware(
                                 }
                             });
                         },
-                        disabled: sending() || recipient().is_empty() || amount().is_empty(),
+                        disabled: watch_only || sending() || recipient().is_empty() || amount().is_empty(),
                         if sending() && !show_hardware_approval() { "Sending..." } else { "Send" }
                     }
                 }
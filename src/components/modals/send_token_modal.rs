@@ -1,10 +1,15 @@
 // src/components/modals/send_token_modal.rs
 use dioxus::prelude::*;
-use crate::wallet::{Wallet, WalletInfo};
+use crate::wallet::{cannot_sign_watch_only, WalletInfo, WATCH_ONLY_MESSAGE};
 use crate::hardware::HardwareWallet;
 use crate::transaction::TransactionClient;
 use crate::signing::hardware::HardwareSigner;
+use crate::signing::SignerType;
 use crate::rpc;
+use crate::components::recipient_input::RecipientInput;
+use crate::components::transaction_note_input::TransactionNoteInput;
+use crate::components::transaction_preview::{use_transaction_review, TransactionPreviewView, TransactionReviewOverlay};
+use crate::preview::{PreviewSigner, TransactionPreview};
 use std::sync::Arc;
 
 // Import HardwareWalletEvent from send_modal instead of defining it again
@@ -18,6 +23,7 @@ pub fn TokenTransactionSuccessModal(
     was_hardware_wallet: bool,
     onclose: EventHandler<()>,
 ) -> Element {
+    let note_signature = signature.clone();
     // Explorer links for multiple explorers
     let solana_explorer_url = format!("https://explorer.solana.com/tx/{}", signature);
     let solscan_url = format!("https://solscan.io/tx/{}", signature);
@@ -76,6 +82,8 @@ pub fn TokenTransactionSuccessModal(
                         }
                     }
                     
+                    TransactionNoteInput { signature: note_signature }
+
                     div {
                         class: "explorer-links",
                         p { "View transaction in explorer:" }
@@ -121,7 +129,7 @@ pub fn TokenTransactionSuccessModal(
 
 /// Hardware wallet approval overlay component shown during token transaction signing
 #[component]
-fn TokenHardwareApprovalOverlay(token_symbol: String, oncancel: EventHandler<()>) -> Element {
+fn TokenHardwareApprovalOverlay(token_symbol: String, previews: Vec<TransactionPreview>, oncancel: EventHandler<()>) -> Element {
     rsx! {
         div {
             class: "hardware-approval-overlay",
@@ -154,6 +162,10 @@ fn TokenHardwareApprovalOverlay(token_symbol: String, oncancel: EventHandler<()>
                     class: "hardware-approval-text",
                     "Please check your hardware wallet and confirm the {token_symbol} transaction details."
                 }
+
+                if !previews.is_empty() {
+                    TransactionPreviewView { previews: previews.clone() }
+                }
                 
                 div {
                     class: "hardware-steps",
@@ -207,6 +219,9 @@ pub fn SendTokenModal(
     
     // Add state for hardware wallet approval overlay - always declared
     let mut show_hardware_approval = use_signal(|| false);
+    // What the hardware wallet is being asked to sign, filled in just before signing
+    let mut signing_preview = use_signal_sync(Vec::<TransactionPreview>::new);
+    let review = use_transaction_review();
 
     // Use decimals or default to 6 for most SPL tokens
     let decimals = token_decimals.unwrap_or(6);
@@ -280,6 +295,9 @@ pub fn SendTokenModal(
         "No Wallet".to_string()
     };
 
+    let watch_only = cannot_sign_watch_only(wallet.as_ref(), hardware_wallet.is_some());
+    let recipient_token_mint = token_mint.clone();
+
     rsx! {
         div {
             class: "modal-backdrop",
@@ -294,6 +312,7 @@ pub fn SendTokenModal(
                 if show_hardware_approval() {
                     TokenHardwareApprovalOverlay {
                         token_symbol: token_symbol.clone(),
+                        previews: signing_preview(),
                         oncancel: move |_| {
                             show_hardware_approval.set(false);
                             sending.set(false);
@@ -301,6 +320,8 @@ pub fn SendTokenModal(
                     }
                 }
 
+                TransactionReviewOverlay { review }
+
                 h2 { 
                     class: "modal-title", 
                     "Send {token_symbol}"
@@ -332,10 +353,10 @@ pub fn SendTokenModal(
                 div {
                     class: "wallet-field",
                     label { "Recipient Address:" }
-                    input {
-                        value: "{recipient}",
-                        oninput: move |e| recipient.set(e.value()),
-                        placeholder: "Enter Solana address"
+                    RecipientInput {
+                        value: recipient(),
+                        oninput: move |address: String| recipient.set(address),
+                        token_mint: Some(recipient_token_mint),
                     }
                     // Show recipient balance if available
                     if checking_balance() {
@@ -372,6 +393,13 @@ pub fn SendTokenModal(
                     }
                 }
 
+                if watch_only {
+                    div {
+                        class: "error-message",
+                        "{WATCH_ONLY_MESSAGE}"
+                    }
+                }
+
                 div { class: "modal-buttons",
                     button {
                         class: "modal-button cancel",
@@ -386,6 +414,7 @@ pub fn SendTokenModal(
 
                             // Show hardware approval overlay if using hardware wallet
                             if hardware_wallet.is_some() {
+                                signing_preview.set(Vec::new());
                                 show_hardware_approval.set(true);
                                 was_hardware_transaction.set(true);
                             } else {
@@ -435,7 +464,10 @@ pub fn SendTokenModal(
 
                                 // Use hardware wallet if available, otherwise use software wallet
                                 if let Some(hw) = hardware_wallet_clone {
-                                    let hw_signer = HardwareSigner::from_wallet(hw.clone());
+                                    let hw_signer = PreviewSigner::new(HardwareSigner::from_wallet(hw.clone()), move |previews| {
+                                        let mut signing_preview = signing_preview;
+                                        signing_preview.set(previews);
+                                    });
                                     match client.send_spl_token_with_signer(&hw_signer, &recipient_address, amount_value, &token_mint_clone).await {
                                         Ok(signature) => {
                                             println!("Token transaction sent with hardware wallet: {}", signature);
@@ -464,11 +496,12 @@ pub fn SendTokenModal(
                                         }
                                     }
                                 } else if let Some(wallet_info) = wallet_info {
-                                    // Load wallet from wallet info
-                                    match Wallet::from_wallet_info(&wallet_info) {
-                                        Ok(wallet) => {
+                                    // Load the signer for this wallet (software or Mobile Wallet Adapter)
+                                    match SignerType::from_wallet_info(&wallet_info) {
+                                        Ok(signer) => {
+                                            let signer = review.review(signer);
                                             // Send SPL token transaction
-                                            match client.send_spl_token(&wallet, &recipient_address, amount_value, &token_mint_clone).await {
+                                            match client.send_spl_token_with_signer(&signer, &recipient_address, amount_value, &token_mint_clone).await {
                                                 Ok(signature) => {
                                                     println!("Token transaction sent: {}", signature);
                                                     
@@ -495,7 +528,7 @@ pub fn SendTokenModal(
                                 }
                             });
                         },
-                        disabled: sending() || recipient().is_empty() || amount().is_empty(),
+                        disabled: watch_only || sending() || recipient().is_empty() || amount().is_empty(),
                         if sending() && !show_hardware_approval() { 
                             "Sending {token_symbol}..." 
                         } else { 
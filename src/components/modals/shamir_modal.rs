@@ -0,0 +1,289 @@
+use dioxus::prelude::*;
+use crate::components::modals::receive_modal::generate_qr_code_svg;
+use crate::qr;
+use crate::shamir::{self, KeyShare, ShareKind};
+use crate::wallet::{SeedSource, Wallet, WalletInfo};
+
+/// Split the current wallet's key or recovery phrase into M-of-N Shamir shares,
+/// or rebuild a wallet from shares
+#[component]
+pub fn ShamirModal(
+    wallet: Option<WalletInfo>,
+    onclose: EventHandler<()>,
+    onsave: EventHandler<WalletInfo>,
+) -> Element {
+    // "split" or "recover"
+    let mut mode = use_signal(|| "split".to_string());
+    let mut threshold = use_signal(|| "2".to_string());
+    let mut share_count = use_signal(|| "3".to_string());
+    let mut shares = use_signal(Vec::<KeyShare>::new);
+    let mut shown_share = use_signal(|| 0usize);
+    let mut share_input = use_signal(|| "".to_string());
+    let mut wallet_name = use_signal(|| "".to_string());
+    // Recovery phrase to split instead of the key, and its BIP39 passphrase (also asked on recovery)
+    let mut recovery_phrase = use_signal(|| "".to_string());
+    let mut passphrase = use_signal(|| "".to_string());
+    let mut error_message = use_signal(|| None as Option<String>);
+
+    let current_share = shares.read().get(shown_share()).cloned();
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Shamir Backup" }
+
+                div {
+                    class: "mode-toggle",
+                    button {
+                        class: if mode() == "split" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| {
+                            mode.set("split".to_string());
+                            error_message.set(None);
+                        },
+                        "Split Key"
+                    }
+                    button {
+                        class: if mode() == "recover" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| {
+                            mode.set("recover".to_string());
+                            shares.set(Vec::new());
+                            error_message.set(None);
+                        },
+                        "Recover"
+                    }
+                }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                if mode() == "split" {
+                    if let Some(share) = current_share {
+                        div { class: "private-key-warning",
+                            if share.kind == ShareKind::SeedEntropy {
+                                "⚠️ Give each share to a different person or place. Any {share.threshold} of them rebuild the recovery phrase."
+                            } else {
+                                "⚠️ Give each share to a different person or place. Any {share.threshold} of them rebuild the key."
+                            }
+                        }
+                        div { class: "wallet-field",
+                            label { "Share {share.index} of {shares.read().len()}:" }
+                            div {
+                                class: "qr-code-container",
+                                div {
+                                    class: "qr-code",
+                                    dangerous_inner_html: "{generate_qr_code_svg(&share.to_text())}"
+                                }
+                            }
+                            div { class: "private-key-display", "{share.to_text().as_str()}" }
+                        }
+                    } else if let Some(info) = wallet.clone() {
+                        div { class: "wallet-field",
+                            label { "Wallet:" }
+                            div { class: "address-display", "{info.name} ({info.address})" }
+                        }
+                        div { class: "wallet-field",
+                            label { "Shares needed to recover:" }
+                            input {
+                                r#type: "number",
+                                min: "2",
+                                value: "{threshold}",
+                                oninput: move |e| threshold.set(e.value()),
+                            }
+                        }
+                        div { class: "wallet-field",
+                            label { "Total shares:" }
+                            input {
+                                r#type: "number",
+                                min: "2",
+                                max: "255",
+                                value: "{share_count}",
+                                oninput: move |e| share_count.set(e.value()),
+                            }
+                        }
+                        if matches!(info.seed_source, SeedSource::Mnemonic { .. }) {
+                            div { class: "wallet-field",
+                                label { "Recovery Phrase (optional):" }
+                                textarea {
+                                    value: "{recovery_phrase}",
+                                    oninput: move |e| recovery_phrase.set(e.value()),
+                                    placeholder: "Enter it to split the phrase instead of the key"
+                                }
+                            }
+                            div { class: "wallet-field",
+                                label { "Passphrase (optional):" }
+                                input {
+                                    r#type: "password",
+                                    value: "{passphrase}",
+                                    oninput: move |e| passphrase.set(e.value()),
+                                    placeholder: "BIP39 passphrase"
+                                }
+                            }
+                            div { class: "help-text",
+                                "Shares of the phrase also restore its other accounts. The passphrase is not part of the shares."
+                            }
+                        }
+                        div { class: "help-text",
+                            "Fewer shares than the threshold reveal nothing about the key."
+                        }
+                    } else {
+                        div {
+                            class: "info-message",
+                            "No wallet selected"
+                        }
+                    }
+                } else {
+                    div { class: "wallet-field",
+                        label { "Wallet Name:" }
+                        input {
+                            value: "{wallet_name}",
+                            oninput: move |e| wallet_name.set(e.value()),
+                            placeholder: "Recovered Wallet"
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Passphrase (recovery phrase shares only):" }
+                        input {
+                            r#type: "password",
+                            value: "{passphrase}",
+                            oninput: move |e| passphrase.set(e.value()),
+                            placeholder: "BIP39 passphrase, if the phrase had one"
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Shares (one per line):" }
+                        textarea {
+                            value: "{share_input}",
+                            oninput: move |e| share_input.set(e.value()),
+                            placeholder: "unruggable-share:...",
+                            rows: 5,
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Or scan share QR code images:" }
+                        input {
+                            r#type: "file",
+                            accept: "image/png,image/jpeg",
+                            multiple: true,
+                            onchange: move |evt| {
+                                spawn(async move {
+                                    let Some(file_engine) = evt.files() else { return };
+                                    for file_name in file_engine.files() {
+                                        let Some(bytes) = file_engine.read_file(&file_name).await else {
+                                            error_message.set(Some(format!("Could not read {}", file_name)));
+                                            return;
+                                        };
+                                        match qr::decode_qr_image(&bytes) {
+                                            Ok(payload) => {
+                                                error_message.set(None);
+                                                let mut text = share_input();
+                                                if !text.is_empty() && !text.ends_with('\n') {
+                                                    text.push('\n');
+                                                }
+                                                text.push_str(payload.trim());
+                                                share_input.set(text);
+                                            }
+                                            Err(e) => error_message.set(Some(e)),
+                                        }
+                                    }
+                                });
+                            }
+                        }
+                    }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Close"
+                    }
+                    if mode() == "split" && !shares.read().is_empty() {
+                        button {
+                            class: "modal-button cancel",
+                            disabled: shown_share() == 0,
+                            onclick: move |_| shown_share.set(shown_share().saturating_sub(1)),
+                            "Previous"
+                        }
+                        button {
+                            class: "modal-button primary",
+                            disabled: shown_share() + 1 >= shares.read().len(),
+                            onclick: move |_| shown_share.set(shown_share() + 1),
+                            "Next"
+                        }
+                    } else if mode() == "split" {
+                        button {
+                            class: "modal-button primary",
+                            disabled: wallet.is_none(),
+                            onclick: {
+                                let wallet = wallet.clone();
+                                move |_| {
+                                    let (Ok(m), Ok(n)) = (threshold().trim().parse::<u8>(), share_count().trim().parse::<u8>()) else {
+                                        error_message.set(Some("Enter whole numbers between 2 and 255".to_string()));
+                                        return;
+                                    };
+                                    let Some(info) = wallet.as_ref() else { return };
+                                    let phrase = recovery_phrase();
+                                    let result = Wallet::from_wallet_info(info).and_then(|w| {
+                                        let split = if phrase.trim().is_empty() {
+                                            shamir::split_wallet(&w, m, n)
+                                        } else {
+                                            shamir::split_recovery_phrase(&w, &phrase, &passphrase(), m, n)
+                                        };
+                                        split.map_err(String::from)
+                                    });
+                                    match result {
+                                        Ok(new_shares) => {
+                                            error_message.set(None);
+                                            shown_share.set(0);
+                                            recovery_phrase.set(String::new());
+                                            passphrase.set(String::new());
+                                            shares.set(new_shares);
+                                        }
+                                        Err(e) => error_message.set(Some(e)),
+                                    }
+                                }
+                            },
+                            "Create Shares"
+                        }
+                    } else {
+                        button {
+                            class: "modal-button primary",
+                            disabled: share_input().trim().is_empty(),
+                            onclick: move |_| {
+                                let parsed: Result<Vec<KeyShare>, _> = share_input()
+                                    .lines()
+                                    .filter(|line| !line.trim().is_empty())
+                                    .map(KeyShare::from_text)
+                                    .collect();
+                                let name = if wallet_name().trim().is_empty() {
+                                    "Recovered Wallet".to_string()
+                                } else {
+                                    wallet_name()
+                                };
+                                let result = parsed
+                                    .and_then(|parsed| shamir::recover_wallet(&parsed, &passphrase(), name))
+                                    .map_err(String::from)
+                                    .and_then(|recovered| recovered.to_wallet_info());
+                                match result {
+                                    Ok(wallet_info) => onsave.call(wallet_info),
+                                    Err(e) => error_message.set(Some(e)),
+                                }
+                            },
+                            "Recover Wallet"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use dioxus::prelude::*;
+use crate::hardware::HardwareWallet;
+use crate::signing::hardware::HardwareSigner;
+use crate::signing::offchain::{self, OffchainMessage};
+use crate::signing::siws::SiwsMessage;
+use crate::signing::{SignerType, TransactionSigner};
+use crate::wallet::WalletInfo;
+use std::sync::Arc;
+
+/// Sign a message to prove address ownership, or verify someone else's signed message.
+/// Messages use the Solana off-chain message format, so they can never be a transaction.
+/// Sign-in mode signs a pasted Sign-In With Solana message for the current wallet.
+#[component]
+pub fn SignMessageModal(
+    wallet: Option<WalletInfo>,
+    hardware_wallet: Option<Arc<HardwareWallet>>,
+    onclose: EventHandler<()>,
+) -> Element {
+    // "sign", "signin" or "verify"
+    let mut mode = use_signal(|| "sign".to_string());
+    let mut message = use_signal(|| "".to_string());
+    let mut signature = use_signal(|| "".to_string());
+    let mut verify_address = use_signal(|| wallet.as_ref().map(|w| w.address.clone()).unwrap_or_default());
+    let mut signed = use_signal(|| None as Option<(String, String)>);
+    let mut verified = use_signal(|| None as Option<bool>);
+    let mut signing = use_signal(|| false);
+    let mut error_message = use_signal(|| None as Option<String>);
+
+    let mut switch_mode = move |new_mode: &str| {
+        mode.set(new_mode.to_string());
+        signed.set(None);
+        verified.set(None);
+        error_message.set(None);
+    };
+
+    // Problem with the typed message, or None when it can be signed
+    let message_problem = if message().is_empty() {
+        None
+    } else if mode() == "signin" {
+        SiwsMessage::parse(message().trim_end()).err().map(|e| e.to_string())
+    } else {
+        OffchainMessage::new(&message()).err().map(|e| e.to_string())
+    };
+    let sign_in_request = if mode() == "signin" { SiwsMessage::parse(message().trim_end()).ok() } else { None };
+    let too_long_for_device = hardware_wallet.is_some()
+        && mode() == "sign"
+        && OffchainMessage::new(&message()).is_ok_and(|m| !m.fits_ledger());
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Sign Message" }
+
+                div {
+                    class: "mode-toggle",
+                    button {
+                        class: if mode() == "sign" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| switch_mode("sign"),
+                        "Sign"
+                    }
+                    button {
+                        class: if mode() == "signin" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| switch_mode("signin"),
+                        "Sign-In"
+                    }
+                    button {
+                        class: if mode() == "verify" { "toggle-button active" } else { "toggle-button" },
+                        onclick: move |_| switch_mode("verify"),
+                        "Verify"
+                    }
+                }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                div { class: "wallet-field",
+                    label { "Message:" }
+                    textarea {
+                        value: "{message}",
+                        oninput: move |e| {
+                            message.set(e.value());
+                            signed.set(None);
+                            verified.set(None);
+                        },
+                        placeholder: if mode() == "signin" {
+                            "Paste the sign-in message from the site"
+                        } else {
+                            "I, the owner of this address, agree to..."
+                        },
+                        rows: 4,
+                    }
+                    if let Some(problem) = message_problem.clone() {
+                        div { class: "help-text", "{problem}" }
+                    }
+                    if too_long_for_device {
+                        div { class: "help-text", "This message is too long for a hardware wallet to display" }
+                    }
+                    if let Some(request) = sign_in_request {
+                        div { class: "help-text",
+                            "Signs you in to {request.domain}"
+                            if let Some(expires) = request.expiration_time {
+                                " until {expires.format(\"%Y-%m-%d %H:%M\")} UTC"
+                            }
+                        }
+                    }
+                }
+
+                if mode() != "verify" {
+                    if let Some((address, signature)) = signed() {
+                        div { class: "wallet-field",
+                            label { "Signed by:" }
+                            div { class: "address-display", "{address}" }
+                        }
+                        div { class: "wallet-field",
+                            label { "Signature:" }
+                            div { class: "address-display", "{signature}" }
+                        }
+                        div { class: "help-text",
+                            if mode() == "signin" {
+                                "Give the signature back to the site to finish signing in."
+                            } else {
+                                "Share the message, signature and address. Anyone can check them with Verify."
+                            }
+                        }
+                    }
+                } else {
+                    div { class: "wallet-field",
+                        label { "Signature:" }
+                        input {
+                            value: "{signature}",
+                            oninput: move |e| {
+                                signature.set(e.value());
+                                verified.set(None);
+                            },
+                            placeholder: "Base58 signature"
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Address:" }
+                        input {
+                            value: "{verify_address}",
+                            oninput: move |e| {
+                                verify_address.set(e.value());
+                                verified.set(None);
+                            },
+                        }
+                    }
+                    match verified() {
+                        Some(true) => rsx! { div { class: "info-message", "✅ Valid signature from this address" } },
+                        Some(false) => rsx! { div { class: "error-message", "❌ Signature does not match this message and address" } },
+                        None => rsx! {},
+                    }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Close"
+                    }
+                    if mode() != "verify" {
+                        button {
+                            class: "modal-button primary",
+                            disabled: signing()
+                                || message().is_empty()
+                                || message_problem.is_some()
+                                || (wallet.is_none() && hardware_wallet.is_none())
+                                || (mode() == "signin" && wallet.is_none()),
+                            onclick: {
+                                let wallet = wallet.clone();
+                                let hardware_wallet = hardware_wallet.clone();
+                                move |_| {
+                                    let signer = match (&hardware_wallet, &wallet) {
+                                        (Some(hw), _) => SignerType::Hardware(HardwareSigner::from_wallet(hw.clone())),
+                                        (None, Some(info)) => match SignerType::from_wallet_info(info) {
+                                            Ok(signer) => signer,
+                                            Err(e) => {
+                                                error_message.set(Some(e));
+                                                return;
+                                            }
+                                        },
+                                        (None, None) => return,
+                                    };
+                                    let text = message();
+                                    // The sign-in message must name the wallet that is signing
+                                    let sign_in_as = if mode() == "signin" { wallet.clone() } else { None };
+
+                                    signing.set(true);
+                                    error_message.set(None);
+                                    spawn(async move {
+                                        let result = async {
+                                            if let Some(info) = sign_in_as {
+                                                let request = SiwsMessage::parse(text.trim_end())?;
+                                                let signed = request.sign(&info, &signer).await?;
+                                                return Ok((signed.address, signed.signature));
+                                            }
+                                            let offchain_message = OffchainMessage::new(&text)?;
+                                            let address = signer.get_public_key().await?;
+                                            let signature = signer.sign_offchain_message(&offchain_message).await?;
+                                            Ok::<_, Box<dyn std::error::Error>>((address, bs58::encode(signature).into_string()))
+                                        }
+                                        .await;
+                                        match result {
+                                            Ok(result) => signed.set(Some(result)),
+                                            Err(e) => error_message.set(Some(e.to_string())),
+                                        }
+                                        signing.set(false);
+                                    });
+                                }
+                            },
+                            if signing() {
+                                "Signing..."
+                            } else if mode() == "signin" {
+                                "Sign In"
+                            } else {
+                                "Sign Message"
+                            }
+                        }
+                    } else {
+                        button {
+                            class: "modal-button primary",
+                            disabled: message().is_empty() || signature().trim().is_empty(),
+                            onclick: move |_| {
+                                match offchain::verify_text(&message(), &signature(), &verify_address()) {
+                                    Ok(valid) => {
+                                        error_message.set(None);
+                                        verified.set(Some(valid));
+                                    }
+                                    Err(e) => error_message.set(Some(e.to_string())),
+                                }
+                            },
+                            "Verify"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use dioxus::prelude::*;
+use crate::signing::policy::{self, OverrideRecord, PolicyChange, PolicyError, SigningPolicy};
+
+/// View and edit this device's signing policy, and approve an override for the
+/// last transaction it refused. Both need the wallet passphrase, and every policy
+/// change and approval is kept in the policy log.
+#[component]
+pub fn SigningPolicyModal(onclose: EventHandler<()>) -> Element {
+    let engine = policy::shared_engine();
+    let mut policy_text = use_signal(|| {
+        serde_json::to_string_pretty(policy::shared_engine().lock().unwrap().policy()).unwrap_or_default()
+    });
+    let mut refusal = use_signal(|| policy::shared_engine().lock().unwrap().last_refusal().cloned() as Option<PolicyError>);
+    let mut overrides = use_signal(|| policy::shared_engine().lock().unwrap().overrides().to_vec() as Vec<OverrideRecord>);
+    let mut changes = use_signal(|| policy::shared_engine().lock().unwrap().changes().to_vec() as Vec<PolicyChange>);
+    let mut passphrase = use_signal(|| "".to_string());
+    let mut approved_by = use_signal(|| "".to_string());
+    let mut reason = use_signal(|| "".to_string());
+    let mut info_message = use_signal(|| None as Option<String>);
+    let mut error_message = use_signal(|| None as Option<String>);
+    let mut load_errors = use_signal(|| policy::shared_engine().lock().unwrap().load_errors());
+
+    let save_policy = {
+        let engine = engine.clone();
+        move |_: MouseEvent| {
+            let mut engine = engine.lock().unwrap();
+            let now = chrono::Utc::now().timestamp();
+            let parsed = serde_json::from_str::<SigningPolicy>(&policy_text())
+                .map_err(|e| format!("Invalid policy: {}", e))
+                .and_then(|new_policy| engine.set_policy(new_policy, &passphrase(), now));
+            match parsed {
+                Ok(()) => {
+                    passphrase.set(String::new());
+                    changes.set(engine.changes().to_vec());
+                    error_message.set(None);
+                    load_errors.set(Vec::new());
+                    info_message.set(Some("✅ Policy saved".to_string()));
+                }
+                Err(e) => error_message.set(Some(e)),
+            }
+        }
+    };
+
+    let approve = {
+        let engine = engine.clone();
+        move |_: MouseEvent| {
+            let Some(error) = refusal() else { return };
+            let mut engine = engine.lock().unwrap();
+            let now = chrono::Utc::now().timestamp();
+            match engine.approve_override(&error, &approved_by(), &reason(), &passphrase(), now) {
+                Ok(()) => {
+                    passphrase.set(String::new());
+                    refusal.set(None);
+                    overrides.set(engine.overrides().to_vec());
+                    error_message.set(None);
+                    info_message.set(Some(format!(
+                        "✅ Override approved. Retry the transaction within {} minutes to sign it.",
+                        policy::OVERRIDE_TTL_SECS / 60
+                    )));
+                }
+                Err(e) => error_message.set(Some(e)),
+            }
+        }
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let override_lines: Vec<String> = overrides()
+        .iter()
+        .rev()
+        .map(|record| {
+            let status = match record.used_at {
+                Some(_) => "",
+                None if now > record.expires_at => " – expired",
+                None => " – not used yet",
+            };
+            format!("{}: {} ({}){}", record.approved_by, record.reason, record.violations.join("; "), status)
+        })
+        .collect();
+    let change_lines: Vec<String> = changes()
+        .iter()
+        .rev()
+        .map(|change| {
+            let when = chrono::DateTime::from_timestamp(change.changed_at, 0)
+                .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            format!("{}: {}", when, serde_json::to_string(&change.policy).unwrap_or_default())
+        })
+        .collect();
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Signing Policy" }
+
+                for error in load_errors().iter() {
+                    div { class: "error-message", "⛔ Nothing is signed until a policy is saved. {error}" }
+                }
+
+                if let Some(error) = error_message() {
+                    div { class: "error-message", "{error}" }
+                }
+                if let Some(info) = info_message() {
+                    div { class: "info-message", "{info}" }
+                }
+
+                div { class: "wallet-field",
+                    label { "Wallet passphrase:" }
+                    input {
+                        r#type: "password",
+                        value: "{passphrase}",
+                        oninput: move |e| passphrase.set(e.value()),
+                        placeholder: "Needed to save the policy or approve an override"
+                    }
+                }
+
+                if let Some(error) = refusal() {
+                    div { class: "wallet-field",
+                        label { "Last refused transaction:" }
+                        for violation in error.violations.iter() {
+                            div { class: "help-text", "⛔ {violation}" }
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Approved by:" }
+                        input {
+                            value: "{approved_by}",
+                            oninput: move |e| approved_by.set(e.value()),
+                            placeholder: "Name of the person allowing it"
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Reason:" }
+                        input {
+                            value: "{reason}",
+                            oninput: move |e| reason.set(e.value()),
+                            placeholder: "Why this transaction is fine"
+                        }
+                    }
+                    button {
+                        class: "modal-button primary",
+                        disabled: approved_by().trim().is_empty() || reason().trim().is_empty() || passphrase().is_empty(),
+                        onclick: approve,
+                        "Approve Override"
+                    }
+                }
+
+                div { class: "wallet-field",
+                    label { "Policy:" }
+                    textarea {
+                        value: "{policy_text}",
+                        oninput: move |e| policy_text.set(e.value()),
+                        rows: "12",
+                    }
+                    div { class: "help-text",
+                        "Limits are in base units (lamports for SOL, mint "
+                        "So11111111111111111111111111111111111111112). Blocked instructions: "
+                        "SetAuthority, Approve, CloseAccount, Assign. "
+                        "Jito tip accounts are always allowed as recipients."
+                    }
+                }
+
+                if !change_lines.is_empty() {
+                    div { class: "wallet-field",
+                        label { "Policy changes:" }
+                        for line in change_lines.iter() {
+                            div { class: "help-text", "{line}" }
+                        }
+                    }
+                }
+
+                if !override_lines.is_empty() {
+                    div { class: "wallet-field",
+                        label { "Override log:" }
+                        for line in override_lines.iter() {
+                            div { class: "help-text", "{line}" }
+                        }
+                    }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Close"
+                    }
+                    button {
+                        class: "modal-button primary",
+                        disabled: passphrase().is_empty(),
+                        onclick: save_policy,
+                        "Save Policy"
+                    }
+                }
+            }
+        }
+    }
+}
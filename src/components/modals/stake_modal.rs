@@ -1,11 +1,14 @@
 use dioxus::prelude::*;
-use crate::wallet::WalletInfo;
+use crate::wallet::{cannot_sign_watch_only, WalletInfo, WATCH_ONLY_MESSAGE};
 use crate::hardware::HardwareWallet;
 use crate::validators::{ValidatorInfo, get_recommended_validators};
-use crate::staking::{self, DetailedStakeAccount, StakeAccountState};
+use crate::staking::{self, DetailedStakeAccount, StakeAccountState, StakingError};
 use std::sync::Arc;
 use crate::signing::hardware::HardwareSigner;
-use crate::staking::create_stake_account;
+use crate::signing::SignerType;
+use crate::components::transaction_note_input::TransactionNoteInput;
+use crate::components::transaction_preview::{use_transaction_review, TransactionPreviewView, TransactionReviewOverlay};
+use crate::preview::{PreviewSigner, TransactionPreview};
 
 #[derive(PartialEq, Clone)]
 enum ModalMode {
@@ -15,7 +18,7 @@ enum ModalMode {
 
 /// Hardware wallet approval overlay component for staking transactions
 #[component]
-fn HardwareApprovalOverlay(oncancel: EventHandler<()>) -> Element {
+fn HardwareApprovalOverlay(previews: Vec<TransactionPreview>, oncancel: EventHandler<()>) -> Element {
     rsx! {
         div {
             class: "hardware-approval-overlay",
@@ -48,6 +51,10 @@ fn HardwareApprovalOverlay(oncancel: EventHandler<()>) -> Element {
                     class: "hardware-approval-text",
                     "Please check your hardware wallet and confirm the staking transaction details."
                 }
+
+                if !previews.is_empty() {
+                    TransactionPreviewView { previews: previews.clone() }
+                }
                 
                 div {
                     class: "hardware-steps",
@@ -82,6 +89,7 @@ fn StakeSuccessModal(
     was_hardware_wallet: bool,
     onclose: EventHandler<()>,
 ) -> Element {
+    let note_signature = signature.clone();
     // Explorer links for multiple explorers
     let solana_explorer_url = format!("https://explorer.solana.com/tx/{}", signature);
     let solscan_url = format!("https://solscan.io/tx/{}", signature);
@@ -176,6 +184,8 @@ fn StakeSuccessModal(
                         }
                     }
                     
+                    TransactionNoteInput { signature: note_signature }
+
                     div {
                         class: "explorer-links",
                         p { "View transaction in explorer:" }
@@ -248,6 +258,9 @@ pub fn StakeModal(
     // Hardware wallet prompting states
     let mut show_hardware_approval = use_signal(|| false);
     let mut was_hardware_transaction = use_signal(|| false);
+    // What the hardware wallet is being asked to sign, filled in just before signing
+    let mut signing_preview = use_signal_sync(Vec::<TransactionPreview>::new);
+    let review = use_transaction_review();
 
     // Load validators on component mount
     use_effect(move || {
@@ -370,6 +383,8 @@ pub fn StakeModal(
         };
     }
 
+    let watch_only = cannot_sign_watch_only(wallet.as_ref(), hardware_wallet.is_some());
+
     rsx! {
         div {
             class: "modal-backdrop",
@@ -386,6 +401,7 @@ pub fn StakeModal(
                 // Hardware approval overlay - shown when waiting for hardware confirmation
                 if show_hardware_approval() {
                     HardwareApprovalOverlay {
+                        previews: signing_preview(),
                         oncancel: move |_| {
                             show_hardware_approval.set(false);
                             staking.set(false);
@@ -393,6 +409,8 @@ pub fn StakeModal(
                     }
                 }
 
+                TransactionReviewOverlay { review }
+
                 // Header with toggle
                 div {
                     class: "modal-header-with-toggle",
@@ -730,6 +748,13 @@ pub fn StakeModal(
                     }
                 }
 
+                if watch_only {
+                    div {
+                        class: "error-message",
+                        "{WATCH_ONLY_MESSAGE}"
+                    }
+                }
+
                 div { 
                     class: "modal-buttons",
                     button {
@@ -741,7 +766,7 @@ pub fn StakeModal(
                     if mode() == ModalMode::Stake {
                         button {
                             class: "modal-button primary",
-                            disabled: staking() || amount().is_empty() || amount().parse::<f64>().unwrap_or(0.0) < 0.01 || selected_validator().is_none(),
+                            disabled: watch_only || staking() || amount().is_empty() || amount().parse::<f64>().unwrap_or(0.0) < 0.01 || selected_validator().is_none(),
                             onclick: move |_| {
                                 error_message.set(None);
                                 
@@ -767,6 +792,7 @@ pub fn StakeModal(
 
                                 // Show hardware approval overlay if using hardware wallet
                                 if hardware_wallet.is_some() {
+                                    signing_preview.set(Vec::new());
                                     show_hardware_approval.set(true);
                                     was_hardware_transaction.set(true);
                                 } else {
@@ -781,13 +807,29 @@ pub fn StakeModal(
                                 let validator_vote_account = validator.vote_account.clone();
                             
                                 spawn(async move {
-                                    match create_stake_account(
-                                        wallet_clone.as_ref(),
-                                        hardware_wallet_clone,
-                                        &validator_vote_account,
-                                        stake_amount,
-                                        custom_rpc_clone.as_deref(),
-                                    ).await {
+                                    let result = match hardware_wallet_clone {
+                                        Some(hw) => {
+                                            let hw_signer = PreviewSigner::new(HardwareSigner::from_wallet(hw), move |previews| {
+                                                let mut signing_preview = signing_preview;
+                                                signing_preview.set(previews);
+                                            });
+                                            staking::StakingClient::new(custom_rpc_clone.as_deref())
+                                                .create_stake_account_with_jito(&hw_signer, &validator_vote_account, stake_amount)
+                                                .await
+                                        }
+                                        // Software or Mobile Wallet Adapter signer, depending on the wallet
+                                        None => match wallet_clone.as_ref().map(SignerType::from_wallet_info) {
+                                            Some(Ok(signer)) => {
+                                                let signer = review.review(signer);
+                                                staking::StakingClient::new(custom_rpc_clone.as_deref())
+                                                    .create_stake_account_with_jito(&signer, &validator_vote_account, stake_amount)
+                                                    .await
+                                            }
+                                            Some(Err(e)) => Err(StakingError::WalletError(format!("Failed to create wallet: {}", e))),
+                                            None => Err(StakingError::WalletError("No wallet or hardware wallet provided".to_string())),
+                                        },
+                                    };
+                                    match result {
                                         Ok(stake_info) => {
                                             println!("Successfully created stake account: {:?}", stake_info);
                                             staking.set(false);
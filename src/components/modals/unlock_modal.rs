@@ -0,0 +1,110 @@
+use dioxus::prelude::*;
+use crate::wallet::WalletInfo;
+use crate::storage::{keystore_initialized, unlock_wallets};
+
+/// Passphrase prompt shown before any wallet secret can be used.
+/// On first launch (or first launch after upgrading from plaintext storage)
+/// it asks the user to choose a passphrase instead. If the wallet file can't be
+/// read it only reports that, so a new passphrase is never set over it.
+#[component]
+pub fn UnlockModal(onunlock: EventHandler<Vec<WalletInfo>>) -> Element {
+    let mut keystore_state = use_signal(keystore_initialized);
+    let first_time = move || matches!(keystore_state(), Ok(false));
+    let mut passphrase = use_signal(|| "".to_string());
+    let mut confirm_passphrase = use_signal(|| "".to_string());
+    let mut error_message = use_signal(|| None as Option<String>);
+    let mut unlocking = use_signal(|| false);
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title",
+                    if first_time() { "Set Wallet Passphrase" } else { "Unlock Wallet" }
+                }
+
+                if let Err(error) = keystore_state() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                    div {
+                        class: "info-message",
+                        "Nothing has been changed. Restore the file or a backup, then try again."
+                    }
+                    div { class: "modal-buttons",
+                        button {
+                            class: "modal-button primary",
+                            onclick: move |_| keystore_state.set(keystore_initialized()),
+                            "Try Again"
+                        }
+                    }
+                } else {
+                    if let Some(error) = error_message() {
+                        div {
+                            class: "error-message",
+                            "{error}"
+                        }
+                    }
+
+                    div {
+                        class: "wallet-field",
+                        label { "Passphrase:" }
+                        input {
+                            r#type: "password",
+                            value: "{passphrase}",
+                            oninput: move |e| passphrase.set(e.value()),
+                            placeholder: "Enter passphrase"
+                        }
+                    }
+
+                    if first_time() {
+                        div {
+                            class: "wallet-field",
+                            label { "Confirm Passphrase:" }
+                            input {
+                                r#type: "password",
+                                value: "{confirm_passphrase}",
+                                oninput: move |e| confirm_passphrase.set(e.value()),
+                                placeholder: "Repeat passphrase"
+                            }
+                        }
+                        div {
+                            class: "info-message",
+                            "Your private keys are encrypted with this passphrase. It cannot be recovered if you forget it."
+                        }
+                    }
+
+                    div { class: "modal-buttons",
+                        button {
+                            class: "modal-button primary",
+                            disabled: unlocking(),
+                            onclick: move |_| {
+                                if first_time() && passphrase() != confirm_passphrase() {
+                                    error_message.set(Some("Passphrases do not match".to_string()));
+                                    return;
+                                }
+                                unlocking.set(true);
+                                error_message.set(None);
+                                match unlock_wallets(&passphrase()) {
+                                    Ok(wallets) => {
+                                        passphrase.set(String::new());
+                                        confirm_passphrase.set(String::new());
+                                        onunlock.call(wallets);
+                                    }
+                                    Err(e) => error_message.set(Some(e)),
+                                }
+                                unlocking.set(false);
+                            },
+                            if unlocking() { "Unlocking..." } else if first_time() { "Set Passphrase" } else { "Unlock" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use dioxus::prelude::*;
+use crate::wallet::{Wallet, WalletInfo};
+use crate::vanity::{VanityGrinder, VanityPattern, VanityProgress};
+use std::rc::Rc;
+
+fn format_count(value: f64) -> String {
+    if value >= 1_000_000_000.0 {
+        format!("{:.1}B", value / 1_000_000_000.0)
+    } else if value >= 1_000_000.0 {
+        format!("{:.1}M", value / 1_000_000.0)
+    } else if value >= 1_000.0 {
+        format!("{:.1}K", value / 1_000.0)
+    } else {
+        format!("{:.0}", value)
+    }
+}
+
+fn format_duration(seconds: f64) -> String {
+    if seconds < 60.0 {
+        format!("{:.0}s", seconds)
+    } else if seconds < 3600.0 {
+        format!("{:.0}m", seconds / 60.0)
+    } else if seconds < 86400.0 {
+        format!("{:.1}h", seconds / 3600.0)
+    } else {
+        format!("{:.1} days", seconds / 86400.0)
+    }
+}
+
+/// Grind a keypair whose address starts and/or ends with chosen characters
+#[component]
+pub fn VanityModal(onclose: EventHandler<()>, onsave: EventHandler<WalletInfo>) -> Element {
+    let mut wallet_name = use_signal(|| "".to_string());
+    let mut prefix = use_signal(|| "".to_string());
+    let mut suffix = use_signal(|| "".to_string());
+    let mut case_sensitive = use_signal(|| true);
+    let mut error_message = use_signal(|| None as Option<String>);
+    let mut grinder = use_signal(|| None as Option<Rc<VanityGrinder>>);
+    let mut progress = use_signal(|| None as Option<VanityProgress>);
+    let mut found_wallet = use_signal(|| None as Option<Rc<Wallet>>);
+
+    // Live pattern check so invalid characters are flagged while typing
+    let pattern = VanityPattern::new(&prefix(), &suffix(), case_sensitive());
+    let expected = pattern.as_ref().map(|p| p.expected_attempts()).ok();
+    let searching = grinder().is_some();
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Vanity Address" }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                if let Some(wallet) = found_wallet() {
+                    div { class: "wallet-field",
+                        label { "Found Address:" }
+                        div { class: "address-display", "{wallet.get_public_key()}" }
+                    }
+                    if let Some(p) = progress() {
+                        div {
+                            class: "info-message",
+                            "Found after {format_count(p.attempts as f64)} attempts in {format_duration(p.elapsed.as_secs_f64())}"
+                        }
+                    }
+                } else {
+                    div { class: "wallet-field",
+                        label { "Wallet Name:" }
+                        input {
+                            value: "{wallet_name}",
+                            oninput: move |e| wallet_name.set(e.value()),
+                            placeholder: "Vanity Wallet",
+                            disabled: searching,
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Prefix:" }
+                        input {
+                            value: "{prefix}",
+                            oninput: move |e| prefix.set(e.value()),
+                            placeholder: "e.g. Sun",
+                            disabled: searching,
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label { "Suffix:" }
+                        input {
+                            value: "{suffix}",
+                            oninput: move |e| suffix.set(e.value()),
+                            placeholder: "optional",
+                            disabled: searching,
+                        }
+                    }
+                    div { class: "wallet-field",
+                        label {
+                            input {
+                                r#type: "checkbox",
+                                checked: case_sensitive(),
+                                disabled: searching,
+                                onchange: move |e| case_sensitive.set(e.checked()),
+                            }
+                            " Case sensitive"
+                        }
+                    }
+
+                    match (&pattern, expected) {
+                        (Err(e), _) if !prefix().is_empty() || !suffix().is_empty() => rsx! {
+                            div { class: "help-text", "{e}" }
+                        },
+                        (Ok(_), Some(expected)) => rsx! {
+                            div {
+                                class: "help-text",
+                                "Expected attempts: ~{format_count(expected)}. Each extra character makes it about 58x harder."
+                            }
+                        },
+                        _ => rsx! {},
+                    }
+
+                    if let Some(p) = progress() {
+                        if searching {
+                            div {
+                                class: "info-message",
+                                "Tried {format_count(p.attempts as f64)} keys ({format_count(p.attempts_per_second())}/s)"
+                                if let Some(remaining) = p.estimated_seconds_remaining() {
+                                    ", about {format_duration(remaining)} left"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| {
+                            if let Some(g) = grinder() {
+                                g.cancel();
+                                grinder.set(None);
+                            } else {
+                                onclose.call(());
+                            }
+                        },
+                        if searching { "Stop" } else { "Cancel" }
+                    }
+                    if let Some(wallet) = found_wallet() {
+                        button {
+                            class: "modal-button primary",
+                            onclick: move |_| {
+                                match wallet.to_wallet_info() {
+                                    Ok(wallet_info) => onsave.call(wallet_info),
+                                    Err(e) => error_message.set(Some(e)),
+                                }
+                            },
+                            "Save Wallet"
+                        }
+                    } else {
+                        button {
+                            class: "modal-button primary",
+                            disabled: searching || pattern.is_err(),
+                            onclick: move |_| {
+                                let pattern = match VanityPattern::new(&prefix(), &suffix(), case_sensitive()) {
+                                    Ok(pattern) => pattern,
+                                    Err(e) => {
+                                        error_message.set(Some(e));
+                                        return;
+                                    }
+                                };
+                                let name = if wallet_name().is_empty() {
+                                    "Vanity Wallet".to_string()
+                                } else {
+                                    wallet_name()
+                                };
+                                error_message.set(None);
+                                grinder.set(Some(Rc::new(VanityGrinder::start(pattern, name))));
+
+                                spawn(async move {
+                                    loop {
+                                        tokio::time::sleep(std::time::Duration::from_millis(250)).await;
+                                        let Some(running) = grinder() else { break };
+                                        progress.set(Some(running.progress()));
+                                        if running.is_finished() {
+                                            if let Some(wallet) = running.take_result() {
+                                                found_wallet.set(Some(Rc::new(wallet)));
+                                            }
+                                            grinder.set(None);
+                                            break;
+                                        }
+                                    }
+                                });
+                            },
+                            if searching { "Searching..." } else { "Start" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,41 @@
 use dioxus::prelude::*;
-use crate::wallet::{Wallet, WalletInfo};
-use crate::storage::import_wallet_from_key;
+use crate::wallet::{SecretExportFormat, Wallet, WalletInfo};
+use crate::storage::{
+    import_wallet_from_key,
+    import_wallet_from_phrase,
+    scan_phrase_import,
+    import_selected_accounts,
+};
+use crate::derivation::{self, DerivationScheme};
+use crate::discovery::{DiscoveredAccount, DEFAULT_GAP_LIMIT};
+use std::collections::HashSet;
+use std::rc::Rc;
+use zeroize::Zeroizing;
 
 #[component]
-pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler<WalletInfo>) -> Element {
+pub fn WalletModal(
+    mode: String,
+    custom_rpc: Option<String>,
+    onclose: EventHandler<()>,
+    onsave: EventHandler<Vec<WalletInfo>>,
+) -> Element {
     let mut wallet_name = use_signal(|| "".to_string());
     let mut import_key = use_signal(|| "".to_string());
     let mut show_generated_key = use_signal(|| false);
-    let mut generated_wallet = use_signal(|| None as Option<Wallet>);
+    let mut generated_wallet = use_signal(|| None as Option<Rc<Wallet>>);
     let mut error_message = use_signal(|| None as Option<String>);
+    // "key" for raw private keys, "phrase" for BIP39 recovery phrases
+    let mut key_method = use_signal(|| "key".to_string());
+    let mut word_count = use_signal(|| 12usize);
+    let mut passphrase = use_signal(|| "".to_string());
+    let mut import_phrase = use_signal(|| "".to_string());
+    let mut account_index = use_signal(|| "0".to_string());
+    let mut generated_phrase = use_signal(|| None as Option<Rc<Zeroizing<String>>>);
+    let mut discover_accounts = use_signal(|| true);
+    let mut gap_limit = use_signal(|| DEFAULT_GAP_LIMIT.to_string());
+    let mut scanning = use_signal(|| false);
+    let mut candidates = use_signal(|| Vec::<DiscoveredAccount>::new());
+    let mut selected_candidates = use_signal(|| HashSet::<String>::new());
     
     rsx! {
         div {
@@ -31,6 +58,28 @@ pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler
                     }
                 }
                 
+                if generated_wallet().is_none() {
+                    div {
+                        class: "mode-toggle",
+                        button {
+                            class: if key_method() == "key" { "toggle-button active" } else { "toggle-button" },
+                            onclick: move |_| {
+                                key_method.set("key".to_string());
+                                error_message.set(None);
+                            },
+                            if mode == "create" { "Random Key" } else { "Private Key" }
+                        }
+                        button {
+                            class: if key_method() == "phrase" { "toggle-button active" } else { "toggle-button" },
+                            onclick: move |_| {
+                                key_method.set("phrase".to_string());
+                                error_message.set(None);
+                            },
+                            "Recovery Phrase"
+                        }
+                    }
+                }
+                
                 if mode == "create" {
                     if let Some(wallet) = generated_wallet() {
                         // Show generated wallet details
@@ -48,37 +97,71 @@ pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler
                                 label { "Public Address:" }
                                 div { class: "address-display", "{wallet.get_public_key()}" }
                             }
-                            div { class: "wallet-field",
-                                label { "Private Key:" }
-                                div { class: "private-key-warning",
-                                    "⚠️ Keep this safe! Never share it with anyone!"
-                                }
-                                if show_generated_key() {
-                                    div { class: "private-key-display", 
-                                        "{wallet.get_private_key()}"
-                                    }
-                                    div { 
-                                        class: "key-format-info",
-                                        "Solana Keypair (64 bytes) - Compatible with Solana CLI and other wallets"
+                            if let Some(phrase) = generated_phrase() {
+                                div { class: "wallet-field",
+                                    label { "Recovery Phrase:" }
+                                    div { class: "private-key-warning",
+                                        "⚠️ Write these words down in order. Anyone with them controls your funds!"
                                     }
-                                    
-                                    // Optionally show just the private key too
-                                    div { 
-                                        class: "private-key-section",
-                                        label { "Private Key Only (32 bytes):" }
-                                        div { class: "private-key-display", 
-                                            "{wallet.get_private_key_only()}"
+                                    if show_generated_key() {
+                                        div { class: "private-key-display",
+                                            "{phrase.as_str()}"
+                                        }
+                                        div {
+                                            class: "key-format-info",
+                                            "BIP39 phrase, account 0 at m/44'/501'/0'/0' - Compatible with Phantom, Solflare and the Solana CLI"
+                                        }
+                                        if !passphrase().is_empty() {
+                                            div {
+                                                class: "copy-hint",
+                                                "You also need your passphrase to recover this wallet."
+                                            }
+                                        }
+                                        div {
+                                            class: "copy-hint",
+                                            "Make sure to back up this phrase before saving!"
+                                        }
+                                    } else {
+                                        button {
+                                            class: "show-key-button",
+                                            onclick: move |_| show_generated_key.set(true),
+                                            "Show Recovery Phrase"
                                         }
                                     }
-                                    div { 
-                                        class: "copy-hint",
-                                        "Make sure to copy this key before saving!"
+                                }
+                            } else {
+                                div { class: "wallet-field",
+                                    label { "Private Key:" }
+                                    div { class: "private-key-warning",
+                                        "⚠️ Keep this safe! Never share it with anyone!"
                                     }
-                                } else {
-                                    button {
-                                        class: "show-key-button",
-                                        onclick: move |_| show_generated_key.set(true),
-                                        "Show Private Key"
+                                    if show_generated_key() {
+                                        div { class: "private-key-display", 
+                                            "{wallet.export_secret(SecretExportFormat::Base58Keypair).as_str()}"
+                                        }
+                                        div { 
+                                            class: "key-format-info",
+                                            "Solana Keypair (64 bytes) - Compatible with Solana CLI and other wallets"
+                                        }
+                                        
+                                        // Optionally show just the private key too
+                                        div { 
+                                            class: "private-key-section",
+                                            label { "Private Key Only (32 bytes):" }
+                                            div { class: "private-key-display", 
+                                                "{wallet.export_secret(SecretExportFormat::Base58PrivateKey).as_str()}"
+                                            }
+                                        }
+                                        div { 
+                                            class: "copy-hint",
+                                            "Make sure to copy this key before saving!"
+                                        }
+                                    } else {
+                                        button {
+                                            class: "show-key-button",
+                                            onclick: move |_| show_generated_key.set(true),
+                                            "Show Private Key"
+                                        }
                                     }
                                 }
                             }
@@ -93,11 +176,160 @@ pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler
                                 placeholder: "My Wallet"
                             }
                         }
+                        if key_method() == "phrase" {
+                            div {
+                                class: "wallet-field",
+                                label { "Phrase Length:" }
+                                select {
+                                    value: "{word_count}",
+                                    onchange: move |e| word_count.set(e.value().parse().unwrap_or(12)),
+                                    option { value: "12", "12 words" }
+                                    option { value: "24", "24 words" }
+                                }
+                            }
+                            div {
+                                class: "wallet-field",
+                                label { "Passphrase (optional):" }
+                                input {
+                                    r#type: "password",
+                                    value: "{passphrase}",
+                                    oninput: move |e| passphrase.set(e.value()),
+                                    placeholder: "BIP39 passphrase"
+                                }
+                                div {
+                                    class: "help-text",
+                                    "A passphrase creates a different wallet. Losing it means losing access."
+                                }
+                            }
+                        }
                         div {
                             class: "info-message",
                             "Click 'Generate Wallet' to create a new wallet"
                         }
                     }
+                } else if key_method() == "phrase" && !candidates().is_empty() {
+                    div {
+                        class: "info-message",
+                        "Select the accounts to add. Accounts with activity are preselected."
+                    }
+                    div {
+                        class: "candidate-list",
+                        for candidate in candidates() {
+                            {
+                                let address = candidate.address();
+                                let short = format!("{}...{}", &address[..4], &address[address.len() - 4..]);
+                                let checked = selected_candidates().contains(&address);
+                                let toggle_address = address.clone();
+                                rsx! {
+                                    label {
+                                        key: "{address}",
+                                        class: "candidate-row",
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: checked,
+                                            onchange: move |e| {
+                                                if e.checked() {
+                                                    selected_candidates.write().insert(toggle_address.clone());
+                                                } else {
+                                                    selected_candidates.write().remove(&toggle_address);
+                                                }
+                                            }
+                                        }
+                                        div {
+                                            class: "candidate-info",
+                                            div { class: "candidate-path", "{candidate.scheme.label()} · {candidate.path()}" }
+                                            div { class: "candidate-address", "{short}" }
+                                        }
+                                        div {
+                                            class: "candidate-balance",
+                                            if candidate.activity.is_used() {
+                                                "{candidate.activity.balance:.4} SOL"
+                                                if candidate.activity.token_accounts > 0 {
+                                                    " · {candidate.activity.token_accounts} tokens"
+                                                }
+                                            } else {
+                                                "No activity"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else if key_method() == "phrase" {
+                    div {
+                        class: "wallet-field",
+                        label { "Wallet Name:" }
+                        input {
+                            value: "{wallet_name}",
+                            oninput: move |e| wallet_name.set(e.value()),
+                            placeholder: "Imported Wallet"
+                        }
+                    }
+                    div {
+                        class: "wallet-field",
+                        label { "Recovery Phrase:" }
+                        textarea {
+                            value: "{import_phrase}",
+                            oninput: move |e| import_phrase.set(e.value()),
+                            placeholder: "Enter your 12 or 24 word recovery phrase"
+                        }
+                    }
+                    div {
+                        class: "wallet-field",
+                        label { "Passphrase (optional):" }
+                        input {
+                            r#type: "password",
+                            value: "{passphrase}",
+                            oninput: move |e| passphrase.set(e.value()),
+                            placeholder: "BIP39 passphrase"
+                        }
+                    }
+                    div {
+                        class: "wallet-field",
+                        label {
+                            input {
+                                r#type: "checkbox",
+                                checked: discover_accounts(),
+                                onchange: move |e| discover_accounts.set(e.checked()),
+                            }
+                            " Scan all derivation paths for used accounts"
+                        }
+                    }
+                    if discover_accounts() {
+                        div {
+                            class: "wallet-field",
+                            label { "Gap Limit:" }
+                            input {
+                                r#type: "number",
+                                min: "1",
+                                value: "{gap_limit}",
+                                oninput: move |e| gap_limit.set(e.value()),
+                            }
+                            div {
+                                class: "help-text",
+                                {format!(
+                                    "Checks {} and stops after this many unused accounts in a row.",
+                                    DerivationScheme::ALL.iter().map(|s| s.pattern()).collect::<Vec<_>>().join(", ")
+                                )}
+                            }
+                        }
+                    } else {
+                        div {
+                            class: "wallet-field",
+                            label { "Account Index:" }
+                            input {
+                                r#type: "number",
+                                min: "0",
+                                value: "{account_index}",
+                                oninput: move |e| account_index.set(e.value()),
+                            }
+                            div {
+                                class: "help-text",
+                                "Derives m/44'/501'/{account_index}'/0'. Use 0 for your first account."
+                            }
+                        }
+                    }
                 } else {
                     // Import mode
                     div {
@@ -115,11 +347,30 @@ pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler
                         textarea {
                             value: "{import_key}",
                             oninput: move |e| import_key.set(e.value()),
-                            placeholder: "Enter your base58 encoded private key or Solana keypair"
+                            placeholder: "Paste a private key or the contents of a Solana CLI id.json"
                         }
                         div {
                             class: "help-text",
-                            "Supports both 32-byte private keys and 64-byte Solana keypairs"
+                            "Accepts base58, base64, hex, a comma-separated byte list or a CLI JSON keypair (32-byte key or 64-byte keypair)"
+                        }
+                        input {
+                            r#type: "file",
+                            accept: ".json,application/json",
+                            onchange: move |evt| {
+                                spawn(async move {
+                                    if let Some(file_engine) = evt.files() {
+                                        if let Some(file_name) = file_engine.files().first() {
+                                            match file_engine.read_file_to_string(file_name).await {
+                                                Some(contents) => {
+                                                    error_message.set(None);
+                                                    import_key.set(contents);
+                                                }
+                                                None => error_message.set(Some(format!("Could not read {}", file_name))),
+                                            }
+                                        }
+                                    }
+                                });
+                            }
                         }
                     }
                 }
@@ -135,14 +386,27 @@ pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler
                             button {
                                 class: "modal-button primary",
                                 onclick: move |_| {
-                                    let new_wallet = Wallet::new(
-                                        if wallet_name().is_empty() { 
-                                            "New Wallet".to_string() 
-                                        } else { 
-                                            wallet_name() 
-                                        }
-                                    );
-                                    generated_wallet.set(Some(new_wallet));
+                                    let name = if wallet_name().is_empty() { 
+                                        "New Wallet".to_string() 
+                                    } else { 
+                                        wallet_name() 
+                                    };
+                                    if key_method() == "phrase" {
+                                        let result = derivation::generate_mnemonic(word_count())
+                                            .and_then(|phrase| {
+                                                let wallet = Wallet::from_mnemonic(&phrase, &passphrase(), 0, name)?;
+                                                Ok((phrase, wallet))
+                                            });
+                                        match result {
+                                            Ok((phrase, new_wallet)) => {
+                                                generated_phrase.set(Some(Rc::new(Zeroizing::new(phrase))));
+                                                generated_wallet.set(Some(Rc::new(new_wallet)));
+                                            }
+                                            Err(e) => error_message.set(Some(e)),
+                                        }
+                                    } else {
+                                        generated_wallet.set(Some(Rc::new(Wallet::new(name))));
+                                    }
                                 },
                                 "Generate Wallet"
                             }
@@ -151,17 +415,23 @@ pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler
                                 class: "modal-button primary",
                                 onclick: move |_| {
                                     if let Some(wallet) = generated_wallet() {
-                                        let mut wallet_info = wallet.to_wallet_info();
-                                        wallet_info.name = if wallet_name().is_empty() {
-                                            wallet.name.clone()
-                                        } else {
-                                            wallet_name()
-                                        };
-                                        onsave.call(wallet_info);
+                                        match wallet.to_wallet_info() {
+                                            Ok(mut wallet_info) => {
+                                                wallet_info.name = if wallet_name().is_empty() {
+                                                    wallet.name.clone()
+                                                } else {
+                                                    wallet_name()
+                                                };
+                                                onsave.call(vec![wallet_info]);
+                                            }
+                                            Err(e) => error_message.set(Some(e)),
+                                        }
                                     }
                                 },
                                 disabled: !show_generated_key(),
-                                if !show_generated_key() {
+                                if !show_generated_key() && generated_phrase().is_some() {
+                                    "Show Recovery Phrase First"
+                                } else if !show_generated_key() {
                                     "Show Private Key First"
                                 } else {
                                     "Save Wallet"
@@ -171,10 +441,80 @@ pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler
                     } else {
                         button {
                             class: "modal-button primary",
+                            disabled: scanning(),
                             onclick: move |_| {
-                                if !import_key().is_empty() {
+                                if key_method() == "phrase" && !candidates().is_empty() {
+                                    let selected: Vec<DiscoveredAccount> = candidates()
+                                        .into_iter()
+                                        .filter(|c| selected_candidates().contains(&c.address()))
+                                        .collect();
+                                    if selected.is_empty() {
+                                        error_message.set(Some("Select at least one account".to_string()));
+                                        return;
+                                    }
+                                    match import_selected_accounts(&selected) {
+                                        Ok(wallet_infos) => onsave.call(wallet_infos),
+                                        Err(e) => error_message.set(Some(e)),
+                                    }
+                                } else if key_method() == "phrase" {
+                                    if import_phrase().trim().is_empty() {
+                                        error_message.set(Some("Please enter a recovery phrase".to_string()));
+                                        return;
+                                    }
+                                    if discover_accounts() {
+                                        let gap = match gap_limit().trim().parse::<u32>() {
+                                            Ok(gap) if gap > 0 => gap,
+                                            _ => {
+                                                error_message.set(Some("Gap limit must be a positive number".to_string()));
+                                                return;
+                                            }
+                                        };
+                                        scanning.set(true);
+                                        error_message.set(None);
+                                        let rpc_url = custom_rpc.clone();
+                                        spawn(async move {
+                                            let result = scan_phrase_import(
+                                                &import_phrase(),
+                                                &passphrase(),
+                                                gap,
+                                                &wallet_name(),
+                                                rpc_url.as_deref(),
+                                            ).await;
+                                            scanning.set(false);
+                                            match result {
+                                                Ok(found) => {
+                                                    let mut preselected: HashSet<String> = found
+                                                        .iter()
+                                                        .filter(|c| c.activity.is_used())
+                                                        .map(|c| c.address())
+                                                        .collect();
+                                                    if preselected.is_empty() {
+                                                        if let Some(first) = found.first() {
+                                                            preselected.insert(first.address());
+                                                        }
+                                                    }
+                                                    selected_candidates.set(preselected);
+                                                    candidates.set(found);
+                                                }
+                                                Err(e) => error_message.set(Some(format!("Account discovery failed: {}", e))),
+                                            }
+                                        });
+                                        return;
+                                    }
+                                    let index = match account_index().trim().parse::<u32>() {
+                                        Ok(index) => index,
+                                        Err(_) => {
+                                            error_message.set(Some("Account index must be a whole number".to_string()));
+                                            return;
+                                        }
+                                    };
+                                    match import_wallet_from_phrase(&import_phrase(), &passphrase(), index, wallet_name()) {
+                                        Ok(wallet_info) => onsave.call(vec![wallet_info]),
+                                        Err(e) => error_message.set(Some(e)),
+                                    }
+                                } else if !import_key().is_empty() {
                                     match import_wallet_from_key(&import_key(), wallet_name()) {
-                                        Ok(wallet_info) => onsave.call(wallet_info),
+                                        Ok(wallet_info) => onsave.call(vec![wallet_info]),
                                         Err(e) => {
                                             error_message.set(Some(e));
                                         }
@@ -183,7 +523,13 @@ pub fn WalletModal(mode: String, onclose: EventHandler<()>, onsave: EventHandler
                                     error_message.set(Some("Please enter a private key".to_string()));
                                 }
                             },
-                            "Import"
+                            if scanning() {
+                                "Scanning accounts..."
+                            } else if !candidates().is_empty() {
+                                "Add Selected ({selected_candidates().len()})"
+                            } else {
+                                "Import"
+                            }
                         }
                     }
                 }
@@ -0,0 +1,100 @@
+use dioxus::prelude::*;
+use crate::wallet::WalletInfo;
+use crate::storage::create_watch_only_wallet;
+use crate::qr;
+
+/// Track an address without its private key, by pasting it or loading a QR code image
+#[component]
+pub fn WatchOnlyModal(onclose: EventHandler<()>, onsave: EventHandler<WalletInfo>) -> Element {
+    let mut wallet_name = use_signal(|| "".to_string());
+    let mut address = use_signal(|| "".to_string());
+    let mut error_message = use_signal(|| None as Option<String>);
+
+    rsx! {
+        div {
+            class: "modal-backdrop",
+            onclick: move |_| onclose.call(()),
+
+            div {
+                class: "modal-content",
+                onclick: move |e| e.stop_propagation(),
+
+                h2 { class: "modal-title", "Add Watch-Only Wallet" }
+
+                if let Some(error) = error_message() {
+                    div {
+                        class: "error-message",
+                        "{error}"
+                    }
+                }
+
+                div {
+                    class: "wallet-field",
+                    label { "Wallet Name:" }
+                    input {
+                        value: "{wallet_name}",
+                        oninput: move |e| wallet_name.set(e.value()),
+                        placeholder: "Treasury"
+                    }
+                }
+                div {
+                    class: "wallet-field",
+                    label { "Address:" }
+                    textarea {
+                        value: "{address}",
+                        oninput: move |e| address.set(e.value()),
+                        placeholder: "Paste a Solana address or solana: payment link"
+                    }
+                }
+                div {
+                    class: "wallet-field",
+                    label { "Or scan a QR code image:" }
+                    input {
+                        r#type: "file",
+                        accept: "image/png,image/jpeg",
+                        onchange: move |evt| {
+                            spawn(async move {
+                                let Some(file_engine) = evt.files() else { return };
+                                let Some(file_name) = file_engine.files().first().cloned() else { return };
+                                let Some(bytes) = file_engine.read_file(&file_name).await else {
+                                    error_message.set(Some(format!("Could not read {}", file_name)));
+                                    return;
+                                };
+                                match qr::decode_qr_image(&bytes).and_then(|payload| qr::parse_address_payload(&payload)) {
+                                    Ok(scanned) => {
+                                        error_message.set(None);
+                                        address.set(scanned);
+                                    }
+                                    Err(e) => error_message.set(Some(e)),
+                                }
+                            });
+                        }
+                    }
+                }
+                div {
+                    class: "info-message",
+                    "Balances, tokens and history load normally, but this wallet cannot send or stake."
+                }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| onclose.call(()),
+                        "Cancel"
+                    }
+                    button {
+                        class: "modal-button primary",
+                        disabled: address().trim().is_empty(),
+                        onclick: move |_| {
+                            match create_watch_only_wallet(&address(), wallet_name()) {
+                                Ok(wallet_info) => onsave.call(wallet_info),
+                                Err(e) => error_message.set(Some(e)),
+                            }
+                        },
+                        "Add Wallet"
+                    }
+                }
+            }
+        }
+    }
+}
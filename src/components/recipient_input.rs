@@ -0,0 +1,80 @@
+// src/components/recipient_input.rs
+use dioxus::prelude::*;
+use crate::address_book::{short_address, AddressBook, SuggestionSource};
+use crate::wallet_repository::WalletRepository;
+use std::rc::Rc;
+
+/// Recipient address field that autocompletes from contacts and the user's own wallets.
+/// When `token_mint` is given, warns if the chosen contact usually receives another token.
+#[component]
+pub fn RecipientInput(
+    value: String,
+    oninput: EventHandler<String>,
+    #[props(default)] token_mint: Option<String>,
+    #[props(default)] disabled: bool,
+) -> Element {
+    // Read once per modal; contacts can't change while a send form is open
+    let sources = use_hook(|| Rc::new((AddressBook::load(), WalletRepository::load().wallets().to_vec())));
+    let mut show_suggestions = use_signal(|| false);
+
+    let (address_book, own_wallets) = sources.as_ref();
+    let suggestions = if show_suggestions() {
+        address_book.suggestions(&value, own_wallets)
+    } else {
+        Vec::new()
+    };
+    let label = address_book.label_for(value.trim(), own_wallets);
+    let token_warning = match (address_book.get(value.trim()), token_mint.as_deref()) {
+        (Some(contact), Some(mint)) => contact
+            .default_token
+            .as_deref()
+            .filter(|default| *default != mint)
+            .map(|_| format!("{} usually receives a different token", contact.name)),
+        _ => None,
+    };
+
+    rsx! {
+        input {
+            value: "{value}",
+            disabled: disabled,
+            oninput: move |e| {
+                show_suggestions.set(true);
+                oninput.call(e.value());
+            },
+            onfocus: move |_| show_suggestions.set(true),
+            onblur: move |_| show_suggestions.set(false),
+            placeholder: "Enter Solana address or contact name"
+        }
+
+        if !suggestions.is_empty() {
+            div { class: "address-suggestions",
+                for suggestion in suggestions {
+                    div {
+                        key: "{suggestion.address}",
+                        class: "address-suggestion",
+                        // mousedown fires before the input's blur hides the list
+                        onmousedown: {
+                            let address = suggestion.address.clone();
+                            move |_| {
+                                show_suggestions.set(false);
+                                oninput.call(address.clone());
+                            }
+                        },
+                        span { class: "suggestion-icon",
+                            if suggestion.source == SuggestionSource::Contact { "📇" } else { "👛" }
+                        }
+                        span { class: "suggestion-name", "{suggestion.name}" }
+                        span { class: "suggestion-address", "{short_address(&suggestion.address)}" }
+                    }
+                }
+            }
+        }
+
+        if let Some(name) = label {
+            div { class: "recipient-label", "→ {name}" }
+        }
+        if let Some(warning) = token_warning {
+            div { class: "recipient-balance checking", "⚠️ {warning}" }
+        }
+    }
+}
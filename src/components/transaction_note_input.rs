@@ -0,0 +1,44 @@
+// src/components/transaction_note_input.rs
+use dioxus::prelude::*;
+use crate::transaction_notes::TransactionNotes;
+
+/// Private note field for a sent transaction, saved on this device only
+#[component]
+pub fn TransactionNoteInput(signature: String) -> Element {
+    let mut note = use_signal({
+        let signature = signature.clone();
+        move || TransactionNotes::load().get(&signature).unwrap_or_default().to_string()
+    });
+    let mut saved = use_signal(|| false);
+    let mut error_message = use_signal(|| None as Option<String>);
+
+    rsx! {
+        div { class: "wallet-field",
+            label { "Note (only on this device):" }
+            input {
+                value: "{note}",
+                oninput: move |e| {
+                    saved.set(false);
+                    note.set(e.value());
+                },
+                placeholder: "e.g. March rent"
+            }
+            div { class: "modal-buttons",
+                button {
+                    class: "modal-button secondary",
+                    onclick: move |_| match TransactionNotes::load().set(&signature, &note()) {
+                        Ok(()) => {
+                            error_message.set(None);
+                            saved.set(true);
+                        }
+                        Err(e) => error_message.set(Some(e)),
+                    },
+                    if saved() { "Saved ✓" } else { "Save Note" }
+                }
+            }
+            if let Some(error) = error_message() {
+                div { class: "error-message", "{error}" }
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use dioxus::prelude::*;
+use crate::preview::{PreviewSigner, TransactionPreview};
+use crate::signing::mwa::MwaSigner;
+use crate::signing::offchain::OffchainMessage;
+use crate::signing::{SignerType, TransactionSigner};
+use async_trait::async_trait;
+use std::error::Error;
+use std::future::Future;
+
+/// What the signer is being asked to approve, one line per instruction.
+/// Risky and unknown instructions are highlighted.
+#[component]
+pub fn TransactionPreviewView(previews: Vec<TransactionPreview>) -> Element {
+    let count = previews.len();
+    // (heading, [(line, risky)], uses lookup tables) per transaction
+    let sections: Vec<(Option<String>, Vec<(String, bool)>, bool)> = previews
+        .iter()
+        .enumerate()
+        .map(|(index, preview)| {
+            let heading = (count > 1).then(|| format!("Transaction {} of {}", index + 1, count));
+            let lines = preview
+                .instructions
+                .iter()
+                .map(|instruction| (instruction.to_string(), instruction.is_risky()))
+                .collect();
+            (heading, lines, preview.uses_lookup_tables)
+        })
+        .collect();
+
+    rsx! {
+        div {
+            class: "transaction-preview",
+            for (heading, lines, uses_lookup_tables) in sections.into_iter() {
+                if let Some(heading) = heading {
+                    div { class: "transaction-preview-heading", "{heading}" }
+                }
+                for (line, risky) in lines.into_iter() {
+                    div {
+                        class: if risky { "transaction-preview-line risky" } else { "transaction-preview-line" },
+                        "{line}"
+                    }
+                }
+                if uses_lookup_tables {
+                    div {
+                        class: "transaction-preview-line risky",
+                        "⚠️ Some accounts come from lookup tables and can't be shown here"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Approval state for signers without a confirmation step of their own, such as
+/// software and Mobile Wallet Adapter wallets. Wrap the signer with `review` and
+/// render a `TransactionReviewOverlay` for it.
+#[derive(Clone, Copy, PartialEq)]
+pub struct TransactionReview {
+    previews: SyncSignal<Vec<TransactionPreview>>,
+    answer: Signal<Option<async_channel::Sender<bool>>>,
+    /// The wallet app signer while it has a request open, so the user can cancel it
+    wallet_app: SyncSignal<Option<MwaSigner>>,
+}
+
+pub fn use_transaction_review() -> TransactionReview {
+    TransactionReview {
+        previews: use_signal_sync(Vec::new),
+        answer: use_signal(|| None),
+        wallet_app: use_signal_sync(|| None),
+    }
+}
+
+impl TransactionReview {
+    /// Make `signer` wait for the user to approve each transaction it is asked to sign
+    pub fn review(mut self, signer: SignerType) -> PreviewSigner<WalletAppRequest> {
+        let (answer, approvals) = async_channel::bounded(1);
+        self.answer.set(Some(answer));
+        let previews = self.previews;
+        let signer = WalletAppRequest { inner: signer, open: self.wallet_app };
+        PreviewSigner::with_approval(signer, move |shown| {
+            let mut previews = previews;
+            previews.set(shown);
+        }, approvals)
+    }
+
+    fn respond(mut self, approved: bool) {
+        if let Some(answer) = self.answer.peek().as_ref() {
+            let _ = answer.try_send(approved);
+        }
+        self.previews.set(Vec::new());
+    }
+}
+
+/// A reviewed signer that shows when a Mobile Wallet Adapter request is waiting on the wallet app
+pub struct WalletAppRequest {
+    inner: SignerType,
+    open: SyncSignal<Option<MwaSigner>>,
+}
+
+impl WalletAppRequest {
+    async fn track<T>(&self, request: impl Future<Output = T>) -> T {
+        let mut open = self.open;
+        if let SignerType::Mwa(signer) = &self.inner {
+            open.set(Some(signer.clone()));
+        }
+        let result = request.await;
+        open.set(None);
+        result
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for WalletAppRequest {
+    async fn get_public_key(&self) -> Result<String, Box<dyn Error>> {
+        self.track(self.inner.get_public_key()).await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.track(self.inner.sign_message(message)).await
+    }
+
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        self.track(self.inner.sign_messages(messages)).await
+    }
+
+    async fn sign_offchain_message(&self, message: &OffchainMessage) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.track(self.inner.sign_offchain_message(message)).await
+    }
+
+    fn get_name(&self) -> String {
+        self.inner.get_name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+}
+
+/// Asks the user to approve a transaction while a signer wrapped by
+/// `TransactionReview::review` waits, then offers to cancel while the wallet app
+/// has the request; renders nothing otherwise
+#[component]
+pub fn TransactionReviewOverlay(review: TransactionReview) -> Element {
+    let previews = review.previews.read().clone();
+    if previews.is_empty() {
+        let Some(wallet_app) = review.wallet_app.read().clone() else {
+            return rsx! {};
+        };
+        return rsx! {
+            div {
+                class: "hardware-approval-overlay",
+
+                div {
+                    class: "hardware-approval-content",
+
+                    h3 { class: "hardware-approval-title", "Waiting for Wallet App" }
+                    p {
+                        class: "hardware-approval-text",
+                        "Approve or reject the request in your wallet app."
+                    }
+
+                    div { class: "modal-buttons",
+                        button {
+                            class: "modal-button cancel",
+                            onclick: move |_| wallet_app.cancel(),
+                            "Cancel"
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    rsx! {
+        div {
+            class: "hardware-approval-overlay",
+
+            div {
+                class: "hardware-approval-content",
+
+                h3 { class: "hardware-approval-title", "Review Transaction" }
+                p {
+                    class: "hardware-approval-text",
+                    "Check what this transaction does before it is signed."
+                }
+
+                TransactionPreviewView { previews }
+
+                div { class: "modal-buttons",
+                    button {
+                        class: "modal-button cancel",
+                        onclick: move |_| review.respond(false),
+                        "Reject"
+                    }
+                    button {
+                        class: "modal-button primary",
+                        onclick: move |_| review.respond(true),
+                        "Approve"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,17 +1,19 @@
 use dioxus::prelude::*;
 use crate::wallet::{Wallet, WalletInfo};
+use crate::wallet_repository::WalletRepository;
 use crate::storage::{
-    load_wallets_from_storage, 
-    save_wallet_to_storage, 
     load_rpc_from_storage,
     save_rpc_to_storage,
     clear_rpc_storage,
     load_jito_settings_from_storage,
     save_jito_settings_to_storage,
+    load_background_from_storage,
+    save_background_to_storage,
     JitoSettings
 };
 use crate::currency::{
     SELECTED_CURRENCY, 
+    load_currency_from_storage,
     EXCHANGE_RATES,
     initialize_currency_system,
     update_exchange_rates_loop,
@@ -27,7 +29,7 @@ use crate::currency_utils::{
     get_current_currency_code
 };
 use crate::components::modals::currency_modal::CurrencyModal;
-use crate::components::modals::{WalletModal, RpcModal, SendModalWithHardware, SendTokenModal, HardwareWalletModal, ReceiveModal, JitoModal, StakeModal, BulkSendModal};
+use crate::components::modals::{WalletModal, RpcModal, SendModalWithHardware, SendTokenModal, HardwareWalletModal, ReceiveModal, JitoModal, StakeModal, BulkSendModal, UnlockModal, ExportKeyModal, WatchOnlyModal, VanityModal, ManageWalletsModal, BackupModal, ShamirModal, AddressBookModal, SignMessageModal, OfflineSignModal, NonceModal, SigningPolicyModal, HistoryModal};
 use crate::components::modals::send_modal::HardwareWalletEvent;
 use crate::components::common::Token;
 use crate::rpc;
@@ -122,6 +124,64 @@ async fn fetch_token_prices(
     prices_loading.set(false);
 }
 
+/// Display name of the in-memory Mobile Wallet Adapter entry. It is never persisted and is
+/// found by its `SeedSource`, so a user wallet with the same name is left alone.
+const MWA_WALLET_NAME: &str = "MWA Wallet";
+
+/// Show the repository's wallets, keeping the in-memory MWA entry.
+/// Selects `select` if given, otherwise keeps the current selection when it still exists.
+/// Reads through `peek` so that calling this from an effect doesn't subscribe it to the
+/// signals it writes.
+fn show_persisted_wallets(
+    mut wallets: Signal<Vec<WalletInfo>>,
+    mut current_wallet_index: Signal<usize>,
+    persisted: &[WalletInfo],
+    select: Option<&str>,
+) {
+    let selected_address = select.map(str::to_string).or_else(|| {
+        wallets
+            .peek()
+            .get(*current_wallet_index.peek())
+            .map(|w| w.address.clone())
+    });
+
+    let mut list = persisted.to_vec();
+    list.extend(wallets.peek().iter().filter(|w| w.is_mobile_wallet_adapter()).cloned());
+    let index = selected_address
+        .and_then(|address| list.iter().position(|w| w.address == address))
+        .unwrap_or(0);
+
+    wallets.set(list);
+    current_wallet_index.set(index);
+}
+
+/// The saved background theme, or the first preset
+fn saved_background() -> BackgroundTheme {
+    let presets = BackgroundTheme::get_presets();
+    load_background_from_storage()
+        .and_then(|name| presets.iter().find(|theme| theme.name == name).cloned())
+        .unwrap_or_else(|| presets[0].clone())
+}
+
+/// Add wallets through the repository and select the first one that was added
+fn add_wallets(
+    wallets: Signal<Vec<WalletInfo>>,
+    current_wallet_index: Signal<usize>,
+    new_wallets: Vec<WalletInfo>,
+) {
+    let mut repository = WalletRepository::load();
+    let mut first_added = None;
+    for wallet_info in new_wallets {
+        match repository.add(wallet_info) {
+            Ok(added) => {
+                first_added.get_or_insert(added.address);
+            }
+            Err(e) => log::error!("❌ Failed to add wallet: {}", e),
+        }
+    }
+    show_persisted_wallets(wallets, current_wallet_index, repository.wallets(), first_added.as_deref());
+}
+
 // Hardcoded verified tokens including USDC, USDT, JTO, JUP, JLP, and BONK
 fn get_verified_tokens() -> HashMap<String, JupiterToken> {
     let mut map = HashMap::new();
@@ -206,7 +266,9 @@ fn get_verified_tokens() -> HashMap<String, JupiterToken> {
 pub fn WalletView() -> Element {
     // Wallet management
     let mut wallets = use_signal(|| Vec::<WalletInfo>::new());
-    let mut current_wallet_index = use_signal(|| 0);
+    let mut session_unlocked = use_signal(|| crate::keystore::is_unlocked());
+    let mut current_wallet_index = use_signal(|| 0usize);
+    let mut wallet_load_error = use_signal(|| None as Option<String>);
     let mut show_dropdown = use_signal(|| false);
     let mut show_wallet_modal = use_signal(|| false);
     let mut modal_mode = use_signal(|| "create".to_string());
@@ -215,6 +277,17 @@ pub fn WalletView() -> Element {
     let mut show_receive_modal = use_signal(|| false);
     let mut show_history_modal = use_signal(|| false);
     let mut show_stake_modal = use_signal(|| false);
+    let mut show_export_modal = use_signal(|| false);
+    let mut show_watch_only_modal = use_signal(|| false);
+    let mut show_vanity_modal = use_signal(|| false);
+    let mut show_manage_modal = use_signal(|| false);
+    let mut show_backup_modal = use_signal(|| false);
+    let mut show_shamir_modal = use_signal(|| false);
+    let mut show_address_book_modal = use_signal(|| false);
+    let mut show_sign_message_modal = use_signal(|| false);
+    let mut show_offline_sign_modal = use_signal(|| false);
+    let mut show_nonce_modal = use_signal(|| false);
+    let mut show_signing_policy_modal = use_signal(|| false);
 
     // Hardware wallet state
     let mut hardware_wallet = use_signal(|| None as Option<Arc<HardwareWallet>>);
@@ -252,7 +325,7 @@ pub fn WalletView() -> Element {
     let verified_tokens = use_memo(move || get_verified_tokens());
 
     // Background Selections
-    let mut selected_background = use_signal(|| BackgroundTheme::get_presets()[0].clone());
+    let mut selected_background = use_signal(|| saved_background());
     let mut show_background_modal = use_signal(|| false);
 
     //Currency
@@ -303,17 +376,25 @@ pub fn WalletView() -> Element {
         rng.gen_range(0.0..7.0)
     }
 
-    // Load wallets from storage on component mount
+    // Load wallets from storage once the keystore is unlocked
     use_effect(move || {
-        let stored_wallets = load_wallets_from_storage();
-        if stored_wallets.is_empty() {
+        if !session_unlocked() {
+            return;
+        }
+        let mut repository = WalletRepository::load();
+        if !repository.load_errors().is_empty() {
+            // Never put a generated wallet where the user's unreadable wallets were
+            wallet_load_error.set(Some(repository.load_errors().join("\n")));
+        } else if repository.wallets().iter().all(|w| w.is_watch_only()) {
             let new_wallet = Wallet::new("Main Wallet".to_string());
-            let wallet_info = new_wallet.to_wallet_info();
-            save_wallet_to_storage(&wallet_info);
-            wallets.set(vec![wallet_info]);
-        } else {
-            wallets.set(stored_wallets);
+            let added = new_wallet
+                .to_wallet_info()
+                .and_then(|wallet_info| repository.add(wallet_info));
+            if let Err(e) = added {
+                log::error!("❌ Failed to create main wallet: {}", e);
+            }
         }
+        show_persisted_wallets(wallets, current_wallet_index, repository.wallets(), None);
     });
 
     // Monitor hardware wallet presence - check every 2 seconds
@@ -383,15 +464,14 @@ pub fn WalletView() -> Element {
                     log::info!("🔗 MWA Connected - Setting as active wallet: {}", pubkey);
                     
                     // Create a temporary WalletInfo for the MWA wallet
-                    let mwa_wallet_info = crate::wallet::WalletInfo {
-                        name: "MWA Wallet".to_string(),
-                        address: pubkey.to_string(),
-                        encrypted_key: "".to_string(),
-                    };
+                    let mwa_wallet_info = crate::wallet::WalletInfo::mobile_wallet_adapter(
+                        MWA_WALLET_NAME.to_string(),
+                        pubkey.to_string(),
+                    );
                     
                     // Check if MWA wallet already exists in the list
                     let mut wallets_list = wallets.read().clone();
-                    let mwa_index = wallets_list.iter().position(|w| w.name == "MWA Wallet");
+                    let mwa_index = wallets_list.iter().position(|w| w.is_mobile_wallet_adapter());
                     
                     if let Some(index) = mwa_index {
                         // Only update if the address actually changed
@@ -418,7 +498,7 @@ pub fn WalletView() -> Element {
                     
                     // When MWA disconnects, remove the MWA wallet from the list
                     let mut wallets_list = wallets.read().clone();
-                    if let Some(mwa_index) = wallets_list.iter().position(|w| w.name == "MWA Wallet") {
+                    if let Some(mwa_index) = wallets_list.iter().position(|w| w.is_mobile_wallet_adapter()) {
                         // Store values we need before modifying the list
                         let current_index = current_wallet_index();
                         let was_using_mwa = current_index == mwa_index;
@@ -719,6 +799,14 @@ pub fn WalletView() -> Element {
 
     let short_address = format!("{}...{}", start, end);    
 
+    if !session_unlocked() {
+        return rsx! {
+            UnlockModal {
+                onunlock: move |_| session_unlocked.set(true)
+            }
+        };
+    }
+
     rsx! {
         div {
             class: "wallet-container-dynamic",
@@ -1017,13 +1105,25 @@ pub fn WalletView() -> Element {
                                 },
                                 onclick: move |_| {
                                     current_wallet_index.set(index);
+                                    let selected = wallets.read().get(index).cloned();
+                                    if let Some(selected) = selected.filter(|w| !w.is_mobile_wallet_adapter()) {
+                                        let mut repository = WalletRepository::load();
+                                        match repository.touch(&selected.address) {
+                                            Ok(()) => show_persisted_wallets(wallets, current_wallet_index, repository.wallets(), Some(&selected.address)),
+                                            Err(e) => log::warn!("⚠️ Could not record wallet use: {}", e),
+                                        }
+                                    }
                                     show_dropdown.set(false);
                                     hardware_connected.set(false);
                                     hardware_pubkey.set(None);
                                 },
                                 div {
                                     class: "dropdown-icon",
-                                    "💗"
+                                    style: match &wallet.metadata.color {
+                                        Some(color) => format!("color: {}", color),
+                                        None => String::new(),
+                                    },
+                                    if wallet.is_watch_only() { "👁" } else { "💗" }
                                 }
                                 div {
                                     class: "wallet-info",
@@ -1073,6 +1173,123 @@ pub fn WalletView() -> Element {
                             }
                             "Import Wallet"
                         }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_watch_only_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "👁"
+                            }
+                            "Add Watch-Only Wallet"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_vanity_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "✨"
+                            }
+                            "Vanity Address"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_manage_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "⚙"
+                            }
+                            "Manage Wallets"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_address_book_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "📇"
+                            }
+                            "Address Book"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_sign_message_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "✍"
+                            }
+                            "Sign Message"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_offline_sign_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "📷"
+                            }
+                            "Offline Signing"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_nonce_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "⏳"
+                            }
+                            "Nonce Accounts"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_signing_policy_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "🛡"
+                            }
+                            "Signing Policy"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_backup_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "💾"
+                            }
+                            "Backup & Restore"
+                        }
                         
                         if hardware_device_present() && !hardware_connected() {
                             button {
@@ -1117,6 +1334,32 @@ pub fn WalletView() -> Element {
                             "JITO Settings"
                         }
 
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_export_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "🔑"
+                            }
+                            "Export Private Key"
+                        }
+
+                        button {
+                            class: "dropdown-item",
+                            onclick: move |_| {
+                                show_shamir_modal.set(true);
+                                show_dropdown.set(false);
+                            },
+                            div {
+                                class: "dropdown-icon action-icon",
+                                "🧩"
+                            }
+                            "Shamir Backup"
+                        }
+
                         button {
                             class: "dropdown-item",
                             onclick: move |_| {
@@ -1136,11 +1379,10 @@ pub fn WalletView() -> Element {
             if show_wallet_modal() {
                 WalletModal {
                     mode: modal_mode(),
+                    custom_rpc: custom_rpc(),
                     onclose: move |_| show_wallet_modal.set(false),
-                    onsave: move |wallet_info| {
-                        save_wallet_to_storage(&wallet_info);
-                        wallets.write().push(wallet_info);
-                        current_wallet_index.set(wallets.read().len() - 1);
+                    onsave: move |new_wallets: Vec<WalletInfo>| {
+                        add_wallets(wallets, current_wallet_index, new_wallets);
                         show_wallet_modal.set(false);
                     }
                 }
@@ -1352,11 +1594,126 @@ pub fn WalletView() -> Element {
                 }
             }
 
+            if show_watch_only_modal() {
+                WatchOnlyModal {
+                    onclose: move |_| show_watch_only_modal.set(false),
+                    onsave: move |wallet_info: WalletInfo| {
+                        add_wallets(wallets, current_wallet_index, vec![wallet_info]);
+                        show_watch_only_modal.set(false);
+                    }
+                }
+            }
+
+            if show_vanity_modal() {
+                VanityModal {
+                    onclose: move |_| show_vanity_modal.set(false),
+                    onsave: move |wallet_info: WalletInfo| {
+                        add_wallets(wallets, current_wallet_index, vec![wallet_info]);
+                        show_vanity_modal.set(false);
+                    }
+                }
+            }
+
+            if show_manage_modal() {
+                ManageWalletsModal {
+                    wallets: wallets.read().iter().filter(|w| !w.is_mobile_wallet_adapter()).cloned().collect::<Vec<_>>(),
+                    onclose: move |_| show_manage_modal.set(false),
+                    onchange: move |persisted: Vec<WalletInfo>| {
+                        show_persisted_wallets(wallets, current_wallet_index, &persisted, None);
+                    }
+                }
+            }
+
+            if show_backup_modal() {
+                BackupModal {
+                    onclose: move |_| show_backup_modal.set(false),
+                    onrestore: move |_| {
+                        let repository = WalletRepository::load();
+                        show_persisted_wallets(wallets, current_wallet_index, repository.wallets(), None);
+                        custom_rpc.set(load_rpc_from_storage());
+                        rpc_input.set(custom_rpc().unwrap_or_default());
+                        jito_settings.set(load_jito_settings_from_storage());
+                        if let Some(currency) = load_currency_from_storage() {
+                            *SELECTED_CURRENCY.write() = currency;
+                        }
+                        selected_background.set(saved_background());
+                    }
+                }
+            }
+
+            if show_export_modal() {
+                ExportKeyModal {
+                    wallet: current_wallet.clone(),
+                    onclose: move |_| show_export_modal.set(false),
+                }
+            }
+
+            if show_history_modal() && full_address != "No Wallet" {
+                HistoryModal {
+                    address: full_address.clone(),
+                    wallets: wallets(),
+                    custom_rpc: custom_rpc(),
+                    onclose: move |_| show_history_modal.set(false),
+                }
+            }
+
+            if show_address_book_modal() {
+                AddressBookModal {
+                    tokens: tokens(),
+                    onclose: move |_| show_address_book_modal.set(false),
+                }
+            }
+
+            if show_sign_message_modal() {
+                SignMessageModal {
+                    wallet: current_wallet.clone(),
+                    hardware_wallet: hardware_wallet(),
+                    onclose: move |_| show_sign_message_modal.set(false),
+                }
+            }
+
+            if show_offline_sign_modal() {
+                OfflineSignModal {
+                    wallet: current_wallet.clone(),
+                    hardware_wallet: hardware_wallet(),
+                    tokens: tokens(),
+                    custom_rpc: custom_rpc(),
+                    onclose: move |_| show_offline_sign_modal.set(false),
+                }
+            }
+
+            if show_nonce_modal() {
+                NonceModal {
+                    wallet: current_wallet.clone(),
+                    hardware_wallet: hardware_wallet(),
+                    custom_rpc: custom_rpc(),
+                    onclose: move |_| show_nonce_modal.set(false),
+                }
+            }
+
+            if show_signing_policy_modal() {
+                SigningPolicyModal {
+                    onclose: move |_| show_signing_policy_modal.set(false),
+                }
+            }
+
+            if show_shamir_modal() {
+                ShamirModal {
+                    wallet: current_wallet.clone(),
+                    onclose: move |_| show_shamir_modal.set(false),
+                    onsave: move |wallet_info: WalletInfo| {
+                        add_wallets(wallets, current_wallet_index, vec![wallet_info]);
+                        show_shamir_modal.set(false);
+                    }
+                }
+            }
+
             if show_background_modal() {
                 BackgroundModal {
                     current_background: selected_background(),
                     onclose: move |_| show_background_modal.set(false),
                     onselect: move |theme: BackgroundTheme| {
+                        save_background_to_storage(&theme.name);
                         selected_background.set(theme);
                         show_background_modal.set(false);
                     }
@@ -1482,6 +1839,18 @@ pub fn WalletView() -> Element {
                     //        "{balance:.4} SOL"
                     //    }
                     //}
+                    if let Some(error) = wallet_load_error() {
+                        div {
+                            class: "wallet-load-error",
+                            p { "Some saved wallets could not be loaded, so they are not shown. Nothing was deleted; restore from a backup or re-import them." }
+                            p { "{error}" }
+                            button {
+                                class: "modal-button cancel",
+                                onclick: move |_| wallet_load_error.set(None),
+                                "Dismiss"
+                            }
+                        }
+                    }
                     // Display price refresh error if any
                     if let Some(error) = price_error() {
                         div {
@@ -1635,18 +2004,18 @@ pub fn WalletView() -> Element {
                     //        "Swap"
                     //    }
                     //}
-                    //button {
-                    //    class: "action-button",
-                    //    onclick: move |_| show_history_modal.set(true),
-                    //    div {
-                    //        class: "action-icon history-icon",
-                    //        "📜"
-                    //    }
-                    //    span {
-                    //        class: "action-label",
-                    //        "History"
-                    //    }
-                    //}
+                    button {
+                        class: "action-button",
+                        onclick: move |_| show_history_modal.set(true),
+                        div {
+                            class: "action-icon history-icon",
+                            "📜"
+                        }
+                        span {
+                            class: "action-label",
+                            "History"
+                        }
+                    }
                 }
             }
             
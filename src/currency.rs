@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error;
 use reqwest::Client;
+use crate::schema::Document;
+use crate::storage;
 
 /// Global currency state using Dioxus GlobalSignal
 pub static SELECTED_CURRENCY: GlobalSignal<String> = Signal::global(|| "USD".to_string());
@@ -247,24 +249,23 @@ pub async fn initialize_currency_system() {
     }
 }
 
+#[cfg(not(feature = "web"))]
+const CURRENCY_FILE: &str = "storage/currency.txt";
+
 /// Save currency preference to storage
 pub fn save_currency_to_storage(currency: &str) {
     #[cfg(feature = "web")]
     {
-        use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        storage.set_item("selected_currency", currency).unwrap();
+        if let Err(e) = storage::write_web_document("selected_currency", Document::Currency, &currency) {
+            println!("❌ {}", e);
+        }
     }
     
     #[cfg(not(feature = "web"))]
     {
-        if let Ok(_) = std::fs::create_dir_all("storage") {
-            let currency_file = "storage/currency.txt";
-            match std::fs::write(currency_file, currency) {
-                Ok(_) => println!("✅ Currency saved to: {}", currency_file),
-                Err(e) => println!("❌ Failed to write currency to {}: {}", currency_file, e),
-            }
+        match storage::write_document(CURRENCY_FILE, Document::Currency, &currency) {
+            Ok(_) => println!("✅ Currency saved to: {}", CURRENCY_FILE),
+            Err(e) => println!("❌ {}", e),
         }
     }
 }
@@ -273,19 +274,12 @@ pub fn save_currency_to_storage(currency: &str) {
 pub fn load_currency_from_storage() -> Option<String> {
     #[cfg(feature = "web")]
     {
-        use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        storage.get_item("selected_currency").unwrap()
+        storage::read_web_document("selected_currency", Document::Currency)
     }
     
     #[cfg(not(feature = "web"))]
     {
-        let currency_file = "storage/currency.txt";
-        match std::fs::read_to_string(currency_file) {
-            Ok(data) => Some(data.trim().to_string()),
-            Err(_) => None,
-        }
+        storage::read_document(CURRENCY_FILE, Document::Currency)
     }
 }
 
@@ -0,0 +1,255 @@
+// src/derivation.rs
+use bip39::{Language, Mnemonic};
+use hmac::{Hmac, Mac};
+use rand::{rngs::OsRng, RngCore};
+use sha2::Sha512;
+use std::fmt;
+use zeroize::{Zeroize, Zeroizing};
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// SLIP-0044 coin type registered for Solana
+pub const SOLANA_COIN_TYPE: u32 = 501;
+
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// A fully hardened BIP32-style derivation path (SLIP-0010 only allows hardened ed25519 children)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Standard Solana account path `m/44'/501'/account'/0'` used by Phantom, Solflare and the CLI
+    pub fn solana_account(account: u32) -> Self {
+        Self(vec![44, SOLANA_COIN_TYPE, account, 0])
+    }
+
+    /// Parse a path such as `m/44'/501'/0'/0'`. Every segment must be hardened.
+    pub fn parse(path: &str) -> Result<Self, String> {
+        let mut segments = path.trim().split('/');
+        if segments.next() != Some("m") {
+            return Err(format!("Derivation path must start with 'm': {}", path));
+        }
+
+        let mut indexes = Vec::new();
+        for segment in segments {
+            let index = segment
+                .strip_suffix('\'')
+                .or_else(|| segment.strip_suffix('h'))
+                .ok_or_else(|| format!("Segment '{}' must be hardened (ed25519 only supports hardened derivation)", segment))?;
+            let index: u32 = index
+                .parse()
+                .map_err(|_| format!("Invalid path segment: {}", segment))?;
+            if index >= HARDENED_OFFSET {
+                return Err(format!("Path segment out of range: {}", segment));
+            }
+            indexes.push(index);
+        }
+
+        Ok(Self(indexes))
+    }
+
+    /// Unhardened segment values, in order
+    pub fn indexes(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// The account number of a standard `m/44'/501'/account'/0'` path
+    pub fn solana_account_index(&self) -> Option<u32> {
+        match self.0.as_slice() {
+            [44, SOLANA_COIN_TYPE, account, 0] => Some(*account),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Derivation layouts used by popular Solana wallets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DerivationScheme {
+    /// `m/44'/501'/i'/0'` - Phantom, Solflare, Backpack, Solana CLI
+    Standard,
+    /// `m/44'/501'/i'` - Ledger Live and older Solflare
+    Ledger,
+    /// `m/44'/501'/0'/0'/i'` - some early multi-account wallets
+    Nested,
+    /// `m/44'/501'` - single root account (Trust Wallet and early Sollet)
+    Root,
+}
+
+impl DerivationScheme {
+    pub const ALL: [DerivationScheme; 4] = [
+        DerivationScheme::Standard,
+        DerivationScheme::Ledger,
+        DerivationScheme::Nested,
+        DerivationScheme::Root,
+    ];
+
+    /// Path for account `index`. `Root` has a single account and ignores the index.
+    pub fn path(&self, index: u32) -> DerivationPath {
+        match self {
+            DerivationScheme::Standard => DerivationPath::solana_account(index),
+            DerivationScheme::Ledger => DerivationPath(vec![44, SOLANA_COIN_TYPE, index]),
+            DerivationScheme::Nested => DerivationPath(vec![44, SOLANA_COIN_TYPE, 0, 0, index]),
+            DerivationScheme::Root => DerivationPath(vec![44, SOLANA_COIN_TYPE]),
+        }
+    }
+
+    /// Whether the scheme has more than one account
+    pub fn is_indexed(&self) -> bool {
+        !matches!(self, DerivationScheme::Root)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DerivationScheme::Standard => "Standard",
+            DerivationScheme::Ledger => "Ledger Live",
+            DerivationScheme::Nested => "Nested",
+            DerivationScheme::Root => "Root",
+        }
+    }
+
+    /// Display pattern, e.g. `m/44'/501'/i'/0'`
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            DerivationScheme::Standard => "m/44'/501'/i'/0'",
+            DerivationScheme::Ledger => "m/44'/501'/i'",
+            DerivationScheme::Nested => "m/44'/501'/0'/0'/i'",
+            DerivationScheme::Root => "m/44'/501'",
+        }
+    }
+}
+
+/// Generate a new English BIP39 mnemonic with 12 or 24 words
+pub fn generate_mnemonic(word_count: usize) -> Result<String, String> {
+    let entropy_len = match word_count {
+        12 => 16,
+        24 => 32,
+        other => return Err(format!("Unsupported mnemonic length: {} words (use 12 or 24)", other)),
+    };
+
+    let mut entropy = Zeroizing::new(vec![0u8; entropy_len]);
+    OsRng.fill_bytes(&mut entropy);
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, &entropy)
+        .map_err(|e| format!("Failed to generate mnemonic: {}", e))?;
+    Ok(mnemonic.to_string())
+}
+
+/// Check that a phrase is a valid English BIP39 mnemonic (word list and checksum)
+pub fn validate_mnemonic(phrase: &str) -> Result<(), String> {
+    parse_mnemonic(phrase).map(|_| ())
+}
+
+/// Turn a mnemonic and optional BIP39 passphrase into the 64-byte seed
+pub fn mnemonic_to_seed(phrase: &str, passphrase: &str) -> Result<Zeroizing<[u8; 64]>, String> {
+    Ok(Zeroizing::new(parse_mnemonic(phrase)?.to_seed(passphrase)))
+}
+
+/// The BIP39 entropy a phrase encodes: 16 bytes for 12 words up to 32 for 24
+pub fn mnemonic_entropy(phrase: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+    Ok(Zeroizing::new(parse_mnemonic(phrase)?.to_entropy()))
+}
+
+/// Turn BIP39 entropy and optional passphrase into the 64-byte seed, as if from the phrase
+pub fn entropy_to_seed(entropy: &[u8], passphrase: &str) -> Result<Zeroizing<[u8; 64]>, String> {
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, entropy)
+        .map_err(|e| format!("Invalid recovery phrase entropy: {}", e))?;
+    Ok(Zeroizing::new(mnemonic.to_seed(passphrase)))
+}
+
+/// SLIP-0010 ed25519 derivation of the 32-byte secret key at `path`
+pub fn derive_ed25519_key(seed: &[u8], path: &DerivationPath) -> Zeroizing<[u8; 32]> {
+    let (mut key, mut chain_code) = hmac_split(ED25519_SEED_KEY, &[seed]);
+
+    for index in path.indexes() {
+        let hardened = (index | HARDENED_OFFSET).to_be_bytes();
+        let (child_key, child_chain_code) = hmac_split(chain_code.as_ref(), &[&[0u8], key.as_ref(), &hardened]);
+        key = child_key;
+        chain_code = child_chain_code;
+    }
+
+    key
+}
+
+fn parse_mnemonic(phrase: &str) -> Result<Mnemonic, String> {
+    let normalized = phrase.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    Mnemonic::parse_in_normalized(Language::English, &normalized)
+        .map_err(|e| format!("Invalid recovery phrase: {}", e))
+}
+
+fn hmac_split(key: &[u8], parts: &[&[u8]]) -> (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>) {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    let mut output = mac.finalize().into_bytes();
+
+    let mut left = Zeroizing::new([0u8; 32]);
+    let mut right = Zeroizing::new([0u8; 32]);
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    output.as_mut_slice().zeroize();
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slip10_vectors() {
+        // SLIP-0010 test vector 1 for ed25519
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+
+        let master = derive_ed25519_key(&seed, &DerivationPath::parse("m").unwrap());
+        assert_eq!(hex::encode(master), "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7");
+
+        let child = derive_ed25519_key(&seed, &DerivationPath::parse("m/0'/1'").unwrap());
+        assert_eq!(hex::encode(child), "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2");
+    }
+
+    #[test]
+    fn test_path_parsing() {
+        let path = DerivationPath::parse("m/44'/501'/3'/0'").unwrap();
+        assert_eq!(path, DerivationPath::solana_account(3));
+        assert_eq!(path.to_string(), "m/44'/501'/3'/0'");
+        assert_eq!(path.solana_account_index(), Some(3));
+        assert_eq!(DerivationScheme::Ledger.path(3).solana_account_index(), None);
+
+        assert!(DerivationPath::parse("m/44'/501/0'").is_err());
+        assert!(DerivationPath::parse("44'/501'").is_err());
+    }
+
+    #[test]
+    fn test_scheme_paths() {
+        assert_eq!(DerivationScheme::Standard.path(2).to_string(), "m/44'/501'/2'/0'");
+        assert_eq!(DerivationScheme::Ledger.path(2).to_string(), "m/44'/501'/2'");
+        assert_eq!(DerivationScheme::Nested.path(2).to_string(), "m/44'/501'/0'/0'/2'");
+        assert_eq!(DerivationScheme::Root.path(2).to_string(), "m/44'/501'");
+        assert!(!DerivationScheme::Root.is_indexed());
+    }
+
+    #[test]
+    fn test_generate_mnemonic() {
+        assert_eq!(generate_mnemonic(12).unwrap().split(' ').count(), 12);
+        assert_eq!(generate_mnemonic(24).unwrap().split(' ').count(), 24);
+        assert!(generate_mnemonic(15).is_err());
+
+        let phrase = generate_mnemonic(12).unwrap();
+        assert!(validate_mnemonic(&phrase).is_ok());
+        assert!(validate_mnemonic("abandon abandon abandon").is_err());
+
+        let entropy = mnemonic_entropy(&phrase).unwrap();
+        assert_eq!(entropy.len(), 16);
+        assert_eq!(entropy_to_seed(&entropy, "pass").unwrap(), mnemonic_to_seed(&phrase, "pass").unwrap());
+    }
+}
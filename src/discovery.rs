@@ -0,0 +1,238 @@
+// src/discovery.rs
+use crate::derivation::{self, DerivationPath, DerivationScheme};
+use crate::rpc::{self, TokenAccountFilter};
+use crate::wallet::Wallet;
+use std::sync::Arc;
+
+/// Unused accounts in a row after which scanning stops (same default as most BIP44 wallets)
+pub const DEFAULT_GAP_LIMIT: u32 = 5;
+
+/// Hard cap so a misconfigured gap limit can't hammer the RPC forever
+pub const MAX_SCANNED_ACCOUNTS: u32 = 100;
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGyPMqHSJv9oZSRRT6m3bDw5uF";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EGFLa1wTxVTmyFCvH2r5BjEd";
+
+/// On-chain footprint of a single derived address
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountActivity {
+    pub balance: f64,
+    pub token_accounts: usize,
+    pub has_history: bool,
+}
+
+impl AccountActivity {
+    pub fn is_used(&self) -> bool {
+        self.balance > 0.0 || self.token_accounts > 0 || self.has_history
+    }
+}
+
+/// A derived account found during discovery, offered to the user as an import candidate.
+/// The wallet is shared so candidate lists can be cloned without copying keys.
+#[derive(Debug, Clone)]
+pub struct DiscoveredAccount {
+    pub scheme: DerivationScheme,
+    pub account_index: u32,
+    pub wallet: Arc<Wallet>,
+    pub activity: AccountActivity,
+}
+
+impl DiscoveredAccount {
+    pub fn address(&self) -> String {
+        self.wallet.get_public_key()
+    }
+
+    pub fn path(&self) -> DerivationPath {
+        self.scheme.path(self.account_index)
+    }
+}
+
+/// Tracks the run of unused indexes and decides when discovery is finished
+#[derive(Debug, Clone)]
+pub struct GapScanner {
+    gap_limit: u32,
+    next_index: u32,
+    unused_in_a_row: u32,
+}
+
+impl GapScanner {
+    pub fn new(gap_limit: u32) -> Self {
+        Self {
+            gap_limit: gap_limit.max(1),
+            next_index: 0,
+            unused_in_a_row: 0,
+        }
+    }
+
+    /// Next index to check, or `None` once the gap limit (or hard cap) is reached
+    pub fn next_index(&self) -> Option<u32> {
+        if self.unused_in_a_row >= self.gap_limit || self.next_index >= MAX_SCANNED_ACCOUNTS {
+            None
+        } else {
+            Some(self.next_index)
+        }
+    }
+
+    /// Record whether the index returned by `next_index` was used
+    pub fn record(&mut self, used: bool) {
+        self.unused_in_a_row = if used { 0 } else { self.unused_in_a_row + 1 };
+        self.next_index += 1;
+    }
+}
+
+/// Query balance, SPL token accounts and signature history for an address
+pub async fn check_activity(address: &str, rpc_url: Option<&str>) -> Result<AccountActivity, String> {
+    let balance = rpc::get_balance(address, rpc_url).await?;
+
+    let mut token_accounts = 0;
+    for program_id in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+        let accounts = rpc::get_token_accounts_by_owner(
+            address,
+            Some(TokenAccountFilter::ProgramId(program_id.to_string())),
+            rpc_url,
+        )
+        .await?;
+        token_accounts += accounts.len();
+    }
+
+    // Only need to know whether any signature exists
+    let has_history = !rpc::get_transaction_history(address, 1, rpc_url).await?.is_empty();
+
+    Ok(AccountActivity {
+        balance,
+        token_accounts,
+        has_history,
+    })
+}
+
+/// Scan one derivation scheme from index 0 until `gap_limit` unused accounts in a row.
+///
+/// Account 0 of the standard scheme is always returned, even if unused,
+/// so importing a fresh phrase still yields a wallet.
+async fn scan_scheme(
+    seed: &[u8],
+    scheme: DerivationScheme,
+    gap_limit: u32,
+    name: &str,
+    rpc_url: Option<&str>,
+) -> Result<Vec<DiscoveredAccount>, String> {
+    // The root scheme only has one account
+    let mut scanner = GapScanner::new(if scheme.is_indexed() { gap_limit } else { 1 });
+    let mut discovered = Vec::new();
+
+    while let Some(index) = scanner.next_index() {
+        let path = scheme.path(index);
+        let account_index = scheme.is_indexed().then_some(index);
+        let wallet = Wallet::from_seed(seed, &path, account_index, account_name(name, scheme, index));
+        let activity = check_activity(&wallet.get_public_key(), rpc_url).await?;
+        let used = activity.is_used();
+        log::info!(
+            "🔎 {} {} used={} balance={} token_accounts={}",
+            path,
+            wallet.get_public_key(),
+            used,
+            activity.balance,
+            activity.token_accounts
+        );
+
+        if used || (scheme == DerivationScheme::Standard && index == 0) {
+            discovered.push(DiscoveredAccount {
+                scheme,
+                account_index: index,
+                wallet: Arc::new(wallet),
+                activity,
+            });
+        }
+        scanner.record(used);
+
+        if !scheme.is_indexed() {
+            break;
+        }
+    }
+
+    Ok(discovered)
+}
+
+/// Scan every known derivation scheme for a phrase and return the candidate accounts.
+/// Addresses reachable through more than one scheme are listed once.
+pub async fn discover_accounts(
+    phrase: &str,
+    passphrase: &str,
+    gap_limit: u32,
+    name: &str,
+    rpc_url: Option<&str>,
+) -> Result<Vec<DiscoveredAccount>, String> {
+    let seed = derivation::mnemonic_to_seed(phrase, passphrase)?;
+    let mut candidates: Vec<DiscoveredAccount> = Vec::new();
+
+    for scheme in DerivationScheme::ALL {
+        for account in scan_scheme(&seed[..], scheme, gap_limit, name, rpc_url).await? {
+            if !candidates.iter().any(|c| c.address() == account.address()) {
+                candidates.push(account);
+            }
+        }
+    }
+
+    Ok(candidates)
+}
+
+fn account_name(base: &str, scheme: DerivationScheme, index: u32) -> String {
+    let base = if base.is_empty() { "Imported Wallet" } else { base };
+    match scheme {
+        DerivationScheme::Standard if index == 0 => base.to_string(),
+        DerivationScheme::Standard => format!("{} ({})", base, index + 1),
+        DerivationScheme::Root => format!("{} ({})", base, scheme.label()),
+        _ => format!("{} ({} {})", base, scheme.label(), index + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(gap_limit: u32, used: &[u32]) -> Vec<u32> {
+        let mut scanner = GapScanner::new(gap_limit);
+        let mut checked = Vec::new();
+        while let Some(index) = scanner.next_index() {
+            checked.push(index);
+            scanner.record(used.contains(&index));
+        }
+        checked
+    }
+
+    #[test]
+    fn test_gap_scanner_stops_after_gap() {
+        // Nothing used: only the first `gap_limit` indexes are checked
+        assert_eq!(scan(3, &[]), vec![0, 1, 2]);
+
+        // A used account resets the gap counter
+        assert_eq!(scan(2, &[0, 2]), vec![0, 1, 2, 3, 4]);
+        assert_eq!(scan(3, &[2]), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(scan(3, &[3]), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_gap_scanner_limits() {
+        // A gap limit of zero still checks account 0
+        assert_eq!(scan(0, &[]), vec![0]);
+
+        let everything: Vec<u32> = (0..MAX_SCANNED_ACCOUNTS).collect();
+        assert_eq!(scan(5, &everything).len() as u32, MAX_SCANNED_ACCOUNTS);
+    }
+
+    #[test]
+    fn test_account_activity() {
+        assert!(!AccountActivity::default().is_used());
+        assert!(AccountActivity { has_history: true, ..Default::default() }.is_used());
+        assert!(AccountActivity { token_accounts: 1, ..Default::default() }.is_used());
+    }
+
+    #[test]
+    fn test_account_name() {
+        assert_eq!(account_name("Team", DerivationScheme::Standard, 0), "Team");
+        assert_eq!(account_name("Team", DerivationScheme::Standard, 2), "Team (3)");
+        assert_eq!(account_name("", DerivationScheme::Standard, 1), "Imported Wallet (2)");
+        assert_eq!(account_name("Team", DerivationScheme::Ledger, 0), "Team (Ledger Live 1)");
+        assert_eq!(account_name("Team", DerivationScheme::Root, 0), "Team (Root)");
+    }
+}
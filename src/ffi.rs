@@ -1,6 +1,6 @@
 use jni::sys::jobject;
 use jni::{
-    objects::{GlobalRef, JClass, JObject, JString, JValue},
+    objects::{GlobalRef, JClass, JObject, JObjectArray, JString, JValue},
     JNIEnv, JavaVM,
 };
 use once_cell::sync::OnceCell;
@@ -91,6 +91,37 @@ pub extern "system" fn Java_dev_dioxus_main_Ipc_sendSignedTransaction(
     crate::send_msg_from_ffi(msg);
 }
 
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "system" fn Java_dev_dioxus_main_Ipc_sendSignedTransactions(
+    mut env: JNIEnv,
+    _class: JClass,
+    signedTransactions: JObjectArray,
+) {
+    let read_all = |env: &mut JNIEnv| -> jni::errors::Result<Vec<String>> {
+        let len = env.get_array_length(&signedTransactions)?;
+        let mut txs = Vec::with_capacity(len as usize);
+        for i in 0..len {
+            let element = env.get_object_array_element(&signedTransactions, i)?;
+            txs.push(env.get_string(&JString::from(element))?.into());
+        }
+        Ok(txs)
+    };
+    let txs = match read_all(&mut env) {
+        Ok(txs) => txs,
+        Err(e) => {
+            log::error!("Failed to get transaction strings from JNI: {:?}", e);
+            return;
+        }
+    };
+    log::info!(
+        "Received {} signed transactions from Kotlin, sending to channel",
+        txs.len()
+    );
+    let msg = MsgFromKotlin::SignedTransactions(txs);
+    crate::send_msg_from_ffi(msg);
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "system" fn Java_dev_dioxus_main_Ipc_sendSignedMessage(
@@ -113,6 +144,36 @@ pub extern "system" fn Java_dev_dioxus_main_Ipc_sendSignedMessage(
     crate::send_msg_from_ffi(msg);
 }
 
+/// Called by Kotlin when the wallet app rejects a request or it fails, so the request
+/// waiting on it ends now instead of at its timeout.
+///
+/// ```kotlin
+/// companion object {
+///   @JvmStatic external fun sendRequestFailed(reason: String)
+/// }
+/// ```
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "system" fn Java_dev_dioxus_main_Ipc_sendRequestFailed(
+    mut env: JNIEnv,
+    _class: JClass,
+    reason: JString,
+) {
+    let reason_str: String = match env.get_string(&reason) {
+        Ok(s) => s.into(),
+        Err(e) => {
+            log::error!("Failed to get failure reason from JNI: {:?}", e);
+            String::new()
+        }
+    };
+    log::info!(
+        "Received request failure from Kotlin, sending to channel: {}",
+        reason_str
+    );
+    let msg = MsgFromKotlin::RequestFailed(reason_str);
+    crate::send_msg_from_ffi(msg);
+}
+
 #[no_mangle]
 #[allow(non_snake_case)]
 pub extern "system" fn Java_dev_dioxus_main_WryActivity_cacheActivityInstance(
@@ -208,6 +269,46 @@ fn do_sign_transaction(
     Ok(rust_string)
 }
 
+fn do_sign_transactions(
+    env: &mut JNIEnv,
+    activity_jobject: jobject,
+    transactions: &[Vec<u8>],
+) -> jni::errors::Result<String> {
+    const CLASS_NAME: &str = "dev/dioxus/main/DioxusUtils";
+    const METHOD_NAME: &str = "signTransactions";
+    // JNI signature for: static String signTransactions(androidx.activity.ComponentActivity activity, byte[][] transactions)
+    const METHOD_SIG: &str = "(Landroidx/activity/ComponentActivity;[[B)Ljava/lang/String;";
+
+    // Find the class
+    let class = env.find_class(CLASS_NAME)?;
+
+    // Convert raw jobject to JObject
+    let activity_obj = unsafe { JObject::from_raw(activity_jobject) };
+
+    // Convert the transactions to a java byte[][], keeping their order
+    let transactions_array = env.new_object_array(transactions.len() as i32, "[B", JObject::null())?;
+    for (i, transaction) in transactions.iter().enumerate() {
+        let transaction_jbyte_array = env.byte_array_from_slice(transaction)?;
+        env.set_object_array_element(&transactions_array, i as i32, transaction_jbyte_array)?;
+    }
+
+    // Prepare arguments
+    let transactions_jobject: JObject = transactions_array.into();
+    let jvalue_args = [
+        JValue::from(&activity_obj),
+        JValue::from(&transactions_jobject),
+    ];
+
+    // Call static method
+    let result_jvalue = env.call_static_method(class, METHOD_NAME, METHOD_SIG, &jvalue_args)?;
+
+    // Process result
+    let jstring_obj = result_jvalue.l()?;
+    let rust_string: String = env.get_string(&JString::from(jstring_obj))?.into();
+
+    Ok(rust_string)
+}
+
 fn do_sign_message(
     env: &mut JNIEnv,
     activity_jobject: jobject,
@@ -296,6 +397,34 @@ pub fn initiate_sign_transaction_from_dioxus(transaction: &[u8]) -> String {
     })
 }
 
+/// Function callable from Dioxus to sign several transactions with one MWA request
+/// NOTE: This function must be invoked from the main dioxus thread,
+/// that means we cannot call this function from inside a dioxus::spawn
+pub fn initiate_sign_transactions_from_dioxus(transactions: &[Vec<u8>]) -> String {
+    let activity_global_ref = match WRY_ACTIVITY.get() {
+        Some(glob_ref) => glob_ref,
+        None => {
+            let err_msg = "Error: WryActivity reference not available. MWA signing cannot be initiated. Ensure WryActivity.create() has been called.";
+            log::error!("{}", err_msg);
+            return String::from(err_msg);
+        }
+    };
+    with_env(|env| {
+        let activity_jobject_local_ref = activity_global_ref.as_obj();
+        let raw_activity_jobject: jobject = activity_jobject_local_ref.as_raw();
+        match do_sign_transactions(env, raw_activity_jobject, transactions) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!(
+                    "JNI error in initiate_sign_transactions_from_dioxus: {:?}",
+                    e
+                );
+                format!("JNI call to signTransactions failed: {:?}", e)
+            }
+        }
+    })
+}
+
 /// Function callable from Dioxus to initiate MWA message signing
 /// NOTE: This function must be invoked from the main dioxus thread,
 /// that means we cannot call this function from inside a dioxus::spawn
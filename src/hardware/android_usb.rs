@@ -7,7 +7,11 @@ use jni::JNIEnv;
 #[cfg(target_os = "android")]
 use dioxus::mobile::wry::prelude::dispatch;
 #[cfg(target_os = "android")]
-use crate::hardware::protocol::{Command, Response, format_esp32_command, parse_esp32_response};
+use crate::hardware::protocol::MAX_RESPONSE_LEN;
+#[cfg(target_os = "android")]
+use crate::hardware::transport::{FrameBuffer, Transport, TransportError, RESPONSE_TIMEOUT};
+#[cfg(target_os = "android")]
+use async_trait::async_trait;
 
 #[derive(Debug, Clone)]
 pub struct StorageError(String);
@@ -27,10 +31,18 @@ impl From<jni::errors::Error> for StorageError {
     }
 }
 
+#[cfg(target_os = "android")]
+impl From<StorageError> for TransportError {
+    fn from(e: StorageError) -> Self {
+        TransportError::Io(e.0)
+    }
+}
+
 #[cfg(target_os = "android")]
 pub struct AndroidUsbSerial {
     pub port: Option<GlobalRef>,
     device_info: Option<AndroidUsbDevice>,
+    frames: FrameBuffer,
 }
 
 #[cfg(target_os = "android")]
@@ -49,6 +61,7 @@ impl AndroidUsbSerial {
         Self {
             port: None,
             device_info: None,
+            frames: FrameBuffer::default(),
         }
     }
 
@@ -126,26 +139,6 @@ impl AndroidUsbSerial {
         }
     }
 
-    /// Send command to the hardware wallet
-    pub async fn send_command(&self, command: Command) -> Result<Response, StorageError> {
-        // Clone the GlobalRef to avoid lifetime issues
-        let port_global = self.port.as_ref()
-            .ok_or(StorageError("Not connected to hardware wallet".to_string()))?
-            .clone();
-        let cmd_data = format_esp32_command(&command);
-        let (tx, rx) = std::sync::mpsc::channel();
-
-        dispatch(move |env, activity, _webview| {
-            let result = Self::java_usb_serial_transfer(env, activity, &port_global, &cmd_data);
-            tx.send(result).unwrap();
-        });
-
-        match rx.recv() {
-            Ok(result) => result.and_then(|response_data| parse_esp32_response(&response_data).map_err(|e| StorageError(format!("Failed to parse response: {}", e)))),
-            Err(e) => Err(StorageError(format!("Failed to send command: {}", e))),
-        }
-    }
-
     /// Disconnect from the USB device
     pub async fn disconnect(&mut self) {
         if let Some(port_global) = self.port.take() { // Use take() to move the value out
@@ -157,6 +150,7 @@ impl AndroidUsbSerial {
             let _ = rx.recv(); // Ignore result for simplicity
         }
         self.device_info = None;
+        self.frames = FrameBuffer::default();
         log::info!("🔌 Disconnected from USB serial device");
     }
 
@@ -254,13 +248,13 @@ impl AndroidUsbSerial {
         Ok(port_global)
     }
 
-    fn java_usb_serial_transfer(
+    fn java_usb_serial_write(
         env: &mut JNIEnv<'_>,
         _activity: &JObject<'_>,
         port_global: &GlobalRef,
         data: &[u8],
-    ) -> Result<Vec<u8>, StorageError> {
-        log::info!("📤 USB Serial Transfer: {} bytes", data.len());
+    ) -> Result<(), StorageError> {
+        log::info!("📤 USB Serial Write: {} bytes", data.len());
         let port = port_global.as_obj();
 
         let java_data = env.byte_array_from_slice(data)?;
@@ -269,18 +263,27 @@ impl AndroidUsbSerial {
         if bytes_written <= 0 {
             return Err(StorageError("Failed to write data to USB serial port".to_string()));
         }
+        Ok(())
+    }
+
+    /// Read whatever arrives within one second; empty if nothing did
+    fn java_usb_serial_read(
+        env: &mut JNIEnv<'_>,
+        _activity: &JObject<'_>,
+        port_global: &GlobalRef,
+    ) -> Result<Vec<u8>, StorageError> {
+        let port = port_global.as_obj();
 
-        let response_buffer = env.new_byte_array(1024)?;
-        let bytes_read = env.call_method(&port, "read", "([BI)I", &[(&response_buffer).into(), 5000.into()])?.i()?;
+        let response_buffer = env.new_byte_array(MAX_RESPONSE_LEN as i32)?;
+        let bytes_read = env.call_method(&port, "read", "([BI)I", &[(&response_buffer).into(), 1000.into()])?.i()?;
 
         if bytes_read <= 0 {
-            return Err(StorageError("No response received from hardware wallet".to_string()));
+            return Ok(Vec::new());
         }
 
         let response_data = env.convert_byte_array(&response_buffer)?;
-        let result = response_data[..bytes_read as usize].to_vec();
         log::info!("📥 Received {} bytes from hardware wallet", bytes_read);
-        Ok(result)
+        Ok(response_data[..bytes_read as usize].to_vec())
     }
 
     fn java_disconnect_usb_serial_device(
@@ -317,4 +320,49 @@ impl AndroidUsbSerial {
             _ => false,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(target_os = "android")]
+#[async_trait]
+impl Transport for AndroidUsbSerial {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<(), TransportError> {
+        // Clone the GlobalRef to avoid lifetime issues
+        let port_global = self.port.as_ref().ok_or(TransportError::NotConnected)?.clone();
+        let data = frame.to_vec();
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        dispatch(move |env, activity, _webview| {
+            let result = Self::java_usb_serial_write(env, activity, &port_global, &data);
+            tx.send(result).unwrap();
+        });
+
+        rx.recv().map_err(|e| TransportError::Io(format!("Failed to send command: {}", e)))??;
+        Ok(())
+    }
+
+    async fn receive_frame(&mut self) -> Result<Vec<u8>, TransportError> {
+        let deadline = std::time::Instant::now() + RESPONSE_TIMEOUT;
+        loop {
+            if let Some(frame) = self.frames.next_frame()? {
+                return Ok(frame);
+            }
+            if std::time::Instant::now() >= deadline {
+                return Err(TransportError::Timeout);
+            }
+
+            let port_global = self.port.as_ref().ok_or(TransportError::NotConnected)?.clone();
+            let (tx, rx) = std::sync::mpsc::channel();
+            dispatch(move |env, activity, _webview| {
+                let result = Self::java_usb_serial_read(env, activity, &port_global);
+                tx.send(result).unwrap();
+            });
+
+            let bytes = rx.recv().map_err(|e| TransportError::Io(format!("Failed to read response: {}", e)))??;
+            self.frames.push(&bytes);
+        }
+    }
+
+    async fn close(&mut self) {
+        self.disconnect().await;
+    }
+}
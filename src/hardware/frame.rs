@@ -0,0 +1,231 @@
+// src/hardware/frame.rs
+use std::fmt;
+
+/// Marks the start of a binary frame; bytes before it (boot chatter, noise) are skipped
+pub const MAGIC: [u8; 2] = [0xA5, 0x5A];
+/// Magic, version, kind, request id (u16), flags, payload length (u16)
+pub const HEADER_LEN: usize = 9;
+/// CRC32 of everything after the magic, little endian
+pub const CRC_LEN: usize = 4;
+/// Largest payload in one frame; longer payloads are split into chunks
+pub const MAX_CHUNK_LEN: usize = 512;
+/// Longest possible binary frame on the wire
+pub const MAX_FRAME_LEN: usize = HEADER_LEN + MAX_CHUNK_LEN + CRC_LEN;
+
+/// Largest payload reassembled from chunks; room for a full `SIGN_BATCH`
+pub const MAX_PAYLOAD_LEN: usize = 16 * 1024;
+
+/// Flag set on every chunk of a payload except the last
+pub const FLAG_MORE: u8 = 0x01;
+/// The flag bits above `FLAG_MORE` number the chunks of a payload from 0, wrapping at 128
+const SEQUENCE_SHIFT: u8 = 1;
+const SEQUENCE_MASK: u8 = 0x7F;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    NotBinary,
+    Truncated,
+    TooLong(usize),
+    BadChecksum,
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::NotBinary => write!(f, "Not a binary frame"),
+            FrameError::Truncated => write!(f, "Frame is truncated"),
+            FrameError::TooLong(len) => write!(f, "Frame payload of {} bytes is too long", len),
+            FrameError::BadChecksum => write!(f, "Frame checksum mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+/// One binary frame; `request_id` pairs each response with its command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub version: u8,
+    pub kind: u8,
+    pub request_id: u16,
+    pub flags: u8,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.payload.len() + CRC_LEN);
+        bytes.extend_from_slice(&MAGIC);
+        bytes.push(self.version);
+        bytes.push(self.kind);
+        bytes.extend_from_slice(&self.request_id.to_le_bytes());
+        bytes.push(self.flags);
+        bytes.extend_from_slice(&(self.payload.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(&self.payload);
+        let crc = crc32fast::hash(&bytes[MAGIC.len()..]);
+        bytes.extend_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    /// Decode exactly one frame, checking its CRC
+    pub fn decode(bytes: &[u8]) -> Result<Self, FrameError> {
+        let len = frame_len(bytes)?.ok_or(FrameError::Truncated)?;
+        if bytes.len() != len {
+            return Err(FrameError::Truncated);
+        }
+
+        let (body, crc) = bytes.split_at(len - CRC_LEN);
+        if crc32fast::hash(&body[MAGIC.len()..]).to_le_bytes() != crc {
+            return Err(FrameError::BadChecksum);
+        }
+        Ok(Self {
+            version: body[2],
+            kind: body[3],
+            request_id: u16::from_le_bytes([body[4], body[5]]),
+            flags: body[6],
+            payload: body[HEADER_LEN..].to_vec(),
+        })
+    }
+
+    pub fn has_more(&self) -> bool {
+        self.flags & FLAG_MORE != 0
+    }
+
+    /// Position of this chunk within its payload, modulo 128
+    pub fn sequence(&self) -> u8 {
+        (self.flags >> SEQUENCE_SHIFT) & SEQUENCE_MASK
+    }
+}
+
+/// Whether `bytes` starts like a binary frame rather than a text line
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes.starts_with(&MAGIC)
+}
+
+/// Where the next frame magic starts in `bytes`
+pub fn find_magic(bytes: &[u8]) -> Option<usize> {
+    bytes.windows(MAGIC.len()).position(|window| window == MAGIC)
+}
+
+/// Length of the binary frame at the start of `bytes`, or `None` until its header has arrived
+pub fn frame_len(bytes: &[u8]) -> Result<Option<usize>, FrameError> {
+    if !is_binary(bytes) {
+        return Err(FrameError::NotBinary);
+    }
+    if bytes.len() < HEADER_LEN {
+        return Ok(None);
+    }
+
+    let payload_len = u16::from_le_bytes([bytes[7], bytes[8]]) as usize;
+    if payload_len > MAX_CHUNK_LEN {
+        return Err(FrameError::TooLong(payload_len));
+    }
+    let len = HEADER_LEN + payload_len + CRC_LEN;
+    Ok((bytes.len() >= len).then_some(len))
+}
+
+/// Split `payload` into frames of at most `MAX_CHUNK_LEN` bytes
+pub fn chunk(version: u8, kind: u8, request_id: u16, payload: &[u8]) -> Vec<Frame> {
+    let chunks: Vec<&[u8]> = if payload.is_empty() {
+        vec![&[]]
+    } else {
+        payload.chunks(MAX_CHUNK_LEN).collect()
+    };
+    let last = chunks.len() - 1;
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| Frame {
+            version,
+            kind,
+            request_id,
+            flags: (index as u8 & SEQUENCE_MASK) << SEQUENCE_SHIFT | if index < last { FLAG_MORE } else { 0 },
+            payload: chunk.to_vec(),
+        })
+        .collect()
+}
+
+/// Joins the chunks of one payload back together
+#[derive(Debug, Default)]
+pub struct Reassembler {
+    kind: Option<u8>,
+    next_sequence: u8,
+    payload: Vec<u8>,
+}
+
+impl Reassembler {
+    /// Add the next chunk; returns the kind and whole payload once the last chunk arrives.
+    /// A chunk out of order, of another kind or past `MAX_PAYLOAD_LEN` discards the payload.
+    pub fn push(&mut self, frame: Frame) -> Result<Option<(u8, Vec<u8>)>, String> {
+        let result = self.append(frame);
+        if result.is_err() {
+            *self = Self::default();
+        }
+        result
+    }
+
+    fn append(&mut self, frame: Frame) -> Result<Option<(u8, Vec<u8>)>, String> {
+        match self.kind {
+            Some(kind) if kind != frame.kind => {
+                return Err(format!("Chunk of kind {:#04x} inside a {:#04x} payload", frame.kind, kind))
+            }
+            _ => self.kind = Some(frame.kind),
+        }
+        if frame.sequence() != self.next_sequence {
+            return Err(format!("Chunk {} arrived out of order, expected {}", frame.sequence(), self.next_sequence));
+        }
+        if self.payload.len() + frame.payload.len() > MAX_PAYLOAD_LEN {
+            return Err(format!("Payload is longer than {} bytes", MAX_PAYLOAD_LEN));
+        }
+
+        self.payload.extend_from_slice(&frame.payload);
+        if frame.has_more() {
+            self.next_sequence = (self.next_sequence + 1) & SEQUENCE_MASK;
+            return Ok(None);
+        }
+        let kind = self.kind.take().unwrap_or(frame.kind);
+        self.next_sequence = 0;
+        Ok(Some((kind, std::mem::take(&mut self.payload))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_roundtrip_and_chunking() {
+        let payload: Vec<u8> = (0..1200u32).map(|i| i as u8).collect();
+        let frames = chunk(1, 0x03, 7, &payload);
+        assert_eq!(frames.len(), 3);
+
+        let mut reassembler = Reassembler::default();
+        let mut whole = None;
+        for frame in frames {
+            let encoded = frame.encode();
+            assert_eq!(frame_len(&encoded).unwrap(), Some(encoded.len()));
+            assert_eq!(frame_len(&encoded[..encoded.len() - 1]).unwrap(), None);
+            whole = reassembler.push(Frame::decode(&encoded).unwrap()).unwrap();
+        }
+        assert_eq!(whole, Some((0x03, payload.clone())));
+
+        // A missing chunk is caught instead of splicing the payload together wrongly
+        let mut frames = chunk(1, 0x03, 8, &payload);
+        frames.remove(1);
+        assert!(reassembler.push(frames[0].clone()).unwrap().is_none());
+        assert!(reassembler.push(frames[1].clone()).is_err());
+        // ...and the next payload starts cleanly
+        let frames = chunk(1, 0x04, 9, &[1, 2, 3]);
+        assert_eq!(reassembler.push(frames[0].clone()).unwrap(), Some((0x04, vec![1, 2, 3])));
+
+        // A peer can't make us buffer an endless payload
+        let oversized = chunk(1, 0x03, 10, &vec![0; MAX_PAYLOAD_LEN + 1]);
+        let mut results = oversized.into_iter().map(|frame| reassembler.push(frame));
+        assert!(results.any(|result| result.is_err()));
+
+        let mut corrupted = chunk(1, 0x02, 1, &[]).remove(0).encode();
+        corrupted[5] ^= 0x01;
+        assert_eq!(Frame::decode(&corrupted), Err(FrameError::BadChecksum));
+        assert_eq!(Frame::decode(b"PUBKEY:abc\n"), Err(FrameError::NotBinary));
+    }
+}
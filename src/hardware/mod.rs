@@ -3,21 +3,166 @@
 pub mod serial;
 #[cfg(target_os = "android")]
 pub mod android_usb;
+#[cfg(all(not(target_os = "android"), any(test, feature = "simulator")))]
+pub mod simulator;
 
+pub mod frame;
 pub mod protocol;
+pub mod tcp;
+pub mod transport;
 
-use protocol::{Command, Response};
+use frame::{Frame, Reassembler};
+use protocol::{Command, Response, format_esp32_command, parse_esp32_response, MAX_BATCH_SIZE};
+use protocol::{KIND_HELLO, KIND_RESPONSE, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use transport::{Transport, TransportError};
 use std::error::Error;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
+/// How long old firmware gets to answer the binary hello before we fall back to text
+const NEGOTIATION_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// An open transport and the protocol agreed with the device on it
+struct Connection {
+    transport: Box<dyn Transport>,
+    /// Negotiated binary protocol version; `None` for firmware that only speaks text
+    version: Option<u8>,
+    next_request_id: u16,
+    /// Cleared once the firmware turns `SIGN_BATCH` down, so later batches go straight to `SIGN:`
+    batch_supported: bool,
+}
+
+/// Whether the device refused a command because its firmware doesn't know it,
+/// as opposed to the user rejecting it
+fn is_unsupported_command(error: &str) -> bool {
+    let error = error.to_ascii_lowercase();
+    ["unknown command", "unsupported", "invalid command"]
+        .iter()
+        .any(|pattern| error.contains(pattern))
+}
+
+impl Connection {
+    /// Offer the binary protocol; firmware that doesn't answer in kind stays on text
+    async fn negotiate(transport: Box<dyn Transport>) -> Self {
+        let mut connection = Self {
+            transport,
+            version: None,
+            next_request_id: 1,
+            batch_supported: true,
+        };
+        connection.version = connection.hello().await;
+        match connection.version {
+            Some(version) => log::info!("🔗 Hardware wallet speaks binary protocol v{}", version),
+            None => log::info!("ℹ️ Hardware wallet only speaks the text protocol"),
+        }
+        connection
+    }
+
+    async fn hello(&mut self) -> Option<u8> {
+        let mut hello = Frame {
+            version: PROTOCOL_VERSION,
+            kind: KIND_HELLO,
+            request_id: 0,
+            flags: 0,
+            payload: vec![MIN_PROTOCOL_VERSION, PROTOCOL_VERSION],
+        }
+        .encode();
+        // Ends the line on text firmware, so the hello doesn't swallow the next command
+        hello.push(b'\n');
+        self.transport.send_frame(&hello).await.ok()?;
+
+        let deadline = tokio::time::Instant::now() + NEGOTIATION_TIMEOUT;
+        loop {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            let received = tokio::time::timeout(remaining, self.transport.receive_frame()).await.ok()?.ok()?;
+            if !frame::is_binary(&received) {
+                // Text firmware rejects the hello; anything else is boot chatter
+                if received.starts_with(b"ERROR:") {
+                    return None;
+                }
+                continue;
+            }
+
+            let reply = Frame::decode(&received).ok()?;
+            if reply.request_id != 0 || reply.kind != KIND_HELLO | KIND_RESPONSE {
+                return None;
+            }
+            let version = *reply.payload.first()?;
+            return (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version).then_some(version);
+        }
+    }
+
+    /// Send one command and wait for the device's response
+    async fn exchange(&mut self, command: Command) -> Result<Response, Box<dyn Error>> {
+        let Some(version) = self.version else {
+            self.transport.send_frame(&format_esp32_command(&command)).await?;
+            let frame = self.transport.receive_frame().await?;
+            return parse_esp32_response(&frame);
+        };
+
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1).max(1);
+        let (kind, payload) = protocol::encode_binary_command(&command);
+        for chunk in frame::chunk(version, kind, request_id, &payload) {
+            self.transport.send_frame(&chunk.encode()).await?;
+        }
+
+        let mut reassembler = Reassembler::default();
+        loop {
+            let received = self.transport.receive_frame().await?;
+            // Boot chatter and log lines between frames
+            if !frame::is_binary(&received) {
+                log::info!("📟 Hardware wallet: {}", String::from_utf8_lossy(&received).trim());
+                continue;
+            }
+
+            let frame = Frame::decode(&received).map_err(|e| format!("Corrupted response from hardware wallet: {}", e))?;
+            // Late replies to requests we already gave up on
+            if frame.request_id != request_id {
+                continue;
+            }
+            if let Some((kind, payload)) = reassembler.push(frame)? {
+                return protocol::decode_binary_response(kind, &payload);
+            }
+        }
+    }
+
+    async fn sign(&mut self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        match self.exchange(Command::SignMessage(message.to_vec())).await? {
+            Response::Signature(sig) => Ok(sig),
+            Response::Error(e) => Err(format!("Hardware wallet error: {}", e).into()),
+            _ => Err("Unexpected response from hardware wallet".into())
+        }
+    }
+
+    /// Sign `batch` under one approval. `Ok(None)` means the firmware doesn't support
+    /// batches and the messages still need signing one by one.
+    async fn sign_batch(&mut self, batch: &[Vec<u8>]) -> Result<Option<Vec<Vec<u8>>>, Box<dyn Error>> {
+        match self.exchange(Command::SignBatch(batch.to_vec())).await {
+            Ok(Response::Signatures(sigs)) if sigs.len() == batch.len() => return Ok(Some(sigs)),
+            Ok(Response::Signatures(sigs)) => {
+                return Err(format!("Hardware wallet returned {} signatures for {} messages", sigs.len(), batch.len()).into())
+            }
+            Ok(Response::Error(e)) if !is_unsupported_command(&e) => {
+                return Err(format!("Hardware wallet error: {}", e).into())
+            }
+            Ok(Response::Error(e)) => log::info!("ℹ️ Hardware wallet can't sign batches ({}), signing one at a time", e),
+            Ok(other) => log::warn!("⚠️ Unexpected reply to a batch ({:?}), signing one at a time", other),
+            // A dead link or a corrupted binary frame is a real failure; an unparseable
+            // text line is old firmware answering a command it doesn't know
+            Err(e) if self.version.is_some() || e.downcast_ref::<TransportError>().is_some() => return Err(e),
+            Err(e) => log::warn!("⚠️ Unreadable reply to a batch ({}), signing one at a time", e),
+        }
+        self.batch_supported = false;
+        Ok(None)
+    }
+}
+
 /// Hardware wallet connection manager
 #[derive(Clone)]
 pub struct HardwareWallet {
-    #[cfg(not(target_os = "android"))]
-    connection: Arc<Mutex<Option<serial::SerialConnection>>>,
-    #[cfg(target_os = "android")]
-    connection: Arc<Mutex<Option<android_usb::AndroidUsbSerial>>>,
+    connection: Arc<Mutex<Option<Connection>>>,
     public_key: Arc<Mutex<Option<String>>>,
 }
 
@@ -40,9 +185,18 @@ impl HardwareWallet {
     
     /// Check if a hardware wallet device is present (without connecting)
     pub fn is_device_present() -> bool {
+        if std::env::var_os(tcp::TCP_BRIDGE_ENV).is_some() {
+            return true;
+        }
+
+        #[cfg(all(not(target_os = "android"), feature = "simulator"))]
+        if std::env::var_os(simulator::SIMULATOR_ENV).is_some() {
+            return true;
+        }
+
         #[cfg(not(target_os = "android"))]
         {
-            serial::SerialConnection::check_device_presence()
+            serial::check_device_presence()
         }
         #[cfg(target_os = "android")]
         {
@@ -54,80 +208,74 @@ impl HardwareWallet {
     
     /// Connect to the hardware wallet
     pub async fn connect(&self) -> Result<(), Box<dyn Error>> {
-        let mut conn_guard = self.connection.lock().await;
-        
+        let transport = Self::open_transport().await?;
+        self.connect_with(transport).await
+    }
+
+    /// A configured TCP bridge or simulator, otherwise the first USB device found
+    async fn open_transport() -> Result<Box<dyn Transport>, Box<dyn Error>> {
+        if let Ok(address) = std::env::var(tcp::TCP_BRIDGE_ENV) {
+            log::info!("🌐 Connecting to hardware wallet bridge at {}", address);
+            return Ok(Box::new(tcp::connect(&address).await?));
+        }
+
         #[cfg(not(target_os = "android"))]
         {
-            // Find and connect to the device using SerialConnection
-            let connection = serial::SerialConnection::find_and_connect().await?;
-            
-            // Get the public key
-            let response = connection.send_command(Command::GetPubkey).await?;
-            match response {
-                Response::Pubkey(pubkey) => {
-                    // Validate that the pubkey is a valid Solana address
-                    if let Err(e) = bs58::decode(&pubkey).into_vec() {
-                        return Err(format!("Invalid public key format: {}", e).into());
-                    }
-                    *self.public_key.lock().await = Some(pubkey);
-                }
-                Response::Error(e) => {
-                    return Err(format!("Hardware wallet error: {}", e).into());
-                }
-                _ => {
-                    return Err("Unexpected response from hardware wallet".into());
-                }
+            // HARDWARE_WALLET_SIMULATOR stands in for the device in desktop demos,
+            // only in builds made with the `simulator` feature
+            #[cfg(feature = "simulator")]
+            if let Some(simulator) = simulator::DeviceSimulator::from_env() {
+                log::info!("🧪 Using simulated hardware wallet {}", simulator.public_key());
+                return Ok(Box::new(simulator.connect()));
             }
-            
-            *conn_guard = Some(connection);
+
+            // Find and connect to the device over serial
+            Ok(Box::new(serial::find_and_connect().await?))
         }
-        
+
         #[cfg(target_os = "android")]
         {
             // Find and connect to the device using AndroidUsbSerial
             let mut connection = android_usb::AndroidUsbSerial::new();
             connection.find_and_connect().await
                 .map_err(|e| format!("Failed to connect to hardware wallet: {}", e))?;
-            
-            // Get the public key
-            let response = connection.send_command(Command::GetPubkey).await
-                .map_err(|e| format!("Failed to get public key: {}", e))?;
-            match response {
-                Response::Pubkey(pubkey) => {
-                    // Validate that the pubkey is a valid Solana address
-                    if let Err(e) = bs58::decode(&pubkey).into_vec() {
-                        return Err(format!("Invalid public key format: {}", e).into());
-                    }
-                    *self.public_key.lock().await = Some(pubkey);
-                }
-                Response::Error(e) => {
-                    return Err(format!("Hardware wallet error: {}", e).into());
-                }
-                _ => {
-                    return Err("Unexpected response from hardware wallet".into());
+            Ok(Box::new(connection))
+        }
+    }
+
+    /// Use an already open transport, agreeing on a protocol and reading the device's public key
+    pub async fn connect_with(&self, transport: Box<dyn Transport>) -> Result<(), Box<dyn Error>> {
+        let mut conn_guard = self.connection.lock().await;
+        let mut connection = Connection::negotiate(transport).await;
+
+        // Get the public key
+        let response = connection.exchange(Command::GetPubkey).await?;
+        match response {
+            Response::Pubkey(pubkey) => {
+                // Validate that the pubkey is a valid Solana address
+                if let Err(e) = bs58::decode(&pubkey).into_vec() {
+                    return Err(format!("Invalid public key format: {}", e).into());
                 }
+                *self.public_key.lock().await = Some(pubkey);
+            }
+            Response::Error(e) => {
+                return Err(format!("Hardware wallet error: {}", e).into());
+            }
+            _ => {
+                return Err("Unexpected response from hardware wallet".into());
             }
-            
-            *conn_guard = Some(connection);
         }
-        
+
+        if let Some(mut previous) = conn_guard.replace(connection) {
+            previous.transport.close().await;
+        }
         Ok(())
     }
     
     /// Disconnect from the hardware wallet
     pub async fn disconnect(&self) {
-        let mut conn_guard = self.connection.lock().await;
-        
-        #[cfg(target_os = "android")]
-        {
-            if let Some(mut connection) = conn_guard.take() {
-                connection.disconnect().await;
-            }
-        }
-        
-        #[cfg(not(target_os = "android"))]
-        {
-            *conn_guard = None;
+        if let Some(mut connection) = self.connection.lock().await.take() {
+            connection.transport.close().await;
         }
         
         *self.public_key.lock().await = None;
@@ -145,33 +293,97 @@ impl HardwareWallet {
             None => Err("Not connected to hardware wallet".into()),
         }
     }
-    
+
     /// Sign a message
     pub async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
-        let conn_guard = self.connection.lock().await;
-        match &*conn_guard {
-            Some(connection) => {
-                #[cfg(not(target_os = "android"))]
-                {
-                    let response = connection.send_command(Command::SignMessage(message.to_vec())).await?;
-                    match response {
-                        Response::Signature(sig) => Ok(sig),
-                        Response::Error(e) => Err(format!("Hardware wallet error: {}", e).into()),
-                        _ => Err("Unexpected response from hardware wallet".into())
-                    }
-                }
-                #[cfg(target_os = "android")]
-                {
-                    let response = connection.send_command(Command::SignMessage(message.to_vec())).await
-                        .map_err(|e| format!("Failed to send command: {}", e))?;
-                    match response {
-                        Response::Signature(sig) => Ok(sig),
-                        Response::Error(e) => Err(format!("Hardware wallet error: {}", e).into()),
-                        _ => Err("Unexpected response from hardware wallet".into())
-                    }
+        let mut conn_guard = self.connection.lock().await;
+        let connection = conn_guard.as_mut().ok_or("Not connected to hardware wallet")?;
+        connection.sign(message).await
+    }
+
+    /// Sign messages in order with one approval per `MAX_BATCH_SIZE` messages.
+    /// A single message, or firmware without `SIGN_BATCH`, uses one `SIGN:` per message.
+    pub async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        let mut conn_guard = self.connection.lock().await;
+        let connection = conn_guard.as_mut().ok_or("Not connected to hardware wallet")?;
+
+        let mut signatures = Vec::with_capacity(messages.len());
+        for batch in messages.chunks(MAX_BATCH_SIZE) {
+            if batch.len() > 1 && connection.batch_supported {
+                if let Some(sigs) = connection.sign_batch(batch).await? {
+                    signatures.extend(sigs);
+                    continue;
                 }
             }
-            None => Err("Not connected to hardware wallet".into()),
+            for message in batch {
+                signatures.push(connection.sign(message).await?);
+            }
         }
+        Ok(signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use transport::{MockTransport, TransportError};
+
+    #[tokio::test]
+    async fn test_protocol_over_mock_transport() {
+        let mock = MockTransport::new();
+        // Old firmware rejects the binary hello, so the text protocol is used
+        mock.reply("ERROR:Unknown command\n");
+        mock.reply("PUBKEY:11111111111111111111111111111111\n");
+        mock.reply("SIGNATURE:AQI=\n");
+        mock.fail(TransportError::Timeout);
+
+        let wallet = HardwareWallet::new();
+        wallet.connect_with(Box::new(mock.clone())).await.unwrap();
+        assert_eq!(wallet.sign_message(b"hi").await.unwrap(), vec![1, 2]);
+        let error = wallet.sign_message(b"hi").await.unwrap_err();
+        assert_eq!(error.to_string(), "Timeout waiting for response");
+        let sent = mock.sent();
+        assert!(frame::is_binary(&sent[0]));
+        assert_eq!(sent[1..], [b"GET_PUBKEY\n".to_vec(), b"SIGN:aGk=\n".to_vec(), b"SIGN:aGk=\n".to_vec()]);
+
+        wallet.disconnect().await;
+        assert!(mock.is_closed());
+        assert!(wallet.sign_message(b"hi").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_text_firmware_without_batch_support() {
+        let mock = MockTransport::new();
+        mock.reply("ERROR:Unknown command\n");
+        mock.reply("PUBKEY:11111111111111111111111111111111\n");
+        let wallet = HardwareWallet::new();
+        wallet.connect_with(Box::new(mock.clone())).await.unwrap();
+        let messages = vec![b"a".to_vec(), b"b".to_vec()];
+
+        // One message never needs SIGN_BATCH
+        mock.reply("SIGNATURE:AQI=\n");
+        assert_eq!(wallet.sign_messages(&messages[..1]).await.unwrap(), [vec![1, 2]]);
+
+        // The user turning the batch down is not a reason to ask again per message
+        mock.reply("ERROR:Rejected by user\n");
+        let rejected = wallet.sign_messages(&messages).await.unwrap_err();
+        assert!(rejected.to_string().contains("Rejected by user"));
+
+        // Firmware that doesn't know SIGN_BATCH signs each message with SIGN:
+        mock.reply("ERROR:Unknown command\n");
+        mock.reply("SIGNATURE:AQI=\n");
+        mock.reply("SIGNATURE:AwQ=\n");
+        assert_eq!(wallet.sign_messages(&messages).await.unwrap(), [vec![1, 2], vec![3, 4]]);
+
+        // ...and later batches skip straight to SIGN:
+        mock.reply("SIGNATURE:AQI=\n");
+        mock.reply("SIGNATURE:AwQ=\n");
+        assert_eq!(wallet.sign_messages(&messages).await.unwrap().len(), 2);
+
+        let sent: Vec<String> = mock.sent()[2..].iter().map(|f| String::from_utf8_lossy(f).trim().to_string()).collect();
+        assert_eq!(
+            sent,
+            ["SIGN:YQ==", "SIGN_BATCH:2:YQ==,Yg==", "SIGN_BATCH:2:YQ==,Yg==", "SIGN:YQ==", "SIGN:Yg==", "SIGN:YQ==", "SIGN:Yg=="]
+        );
     }
-}
\ No newline at end of file
+}
@@ -2,11 +2,18 @@
 use std::error::Error;
 use base64::Engine; // Add this import
 
+/// Most messages the device signs under one approval
+pub const MAX_BATCH_SIZE: usize = 10;
+/// Longest response line accepted from the device; fits a full batch of signatures
+pub const MAX_RESPONSE_LEN: usize = 4096;
+
 /// Command types that can be sent to the hardware wallet
 #[derive(Debug, Clone)]
 pub enum Command {
     GetPubkey,
     SignMessage(Vec<u8>),
+    /// Ordered messages approved with one button press; the device shows the count
+    SignBatch(Vec<Vec<u8>>),
 }
 
 /// Response types from the hardware wallet
@@ -14,6 +21,8 @@ pub enum Command {
 pub enum Response {
     Pubkey(String),
     Signature(Vec<u8>),
+    /// One signature per `SignBatch` message, in the same order
+    Signatures(Vec<Vec<u8>>),
     Error(String),
 }
 
@@ -29,6 +38,13 @@ pub fn format_esp32_command(cmd: &Command) -> Vec<u8> {
             formatted.push(b'\n');
             formatted
         }
+        Command::SignBatch(messages) => {
+            let encoded: Vec<String> = messages
+                .iter()
+                .map(|m| base64::engine::general_purpose::STANDARD.encode(m))
+                .collect();
+            format!("SIGN_BATCH:{}:{}\n", messages.len(), encoded.join(",")).into_bytes()
+        }
     }
 }
 
@@ -37,7 +53,13 @@ pub fn parse_esp32_response(data: &[u8]) -> Result<Response, Box<dyn Error>> {
     let response_str = String::from_utf8_lossy(data);
     let response_str = response_str.trim();
     
-    if response_str.starts_with("PUBKEY:") {
+    if let Some(list) = response_str.strip_prefix("SIGNATURES:") {
+        let signatures = list
+            .split(',')
+            .map(|sig_b64| base64::engine::general_purpose::STANDARD.decode(sig_b64))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Response::Signatures(signatures))
+    } else if response_str.starts_with("PUBKEY:") {
         let pubkey = response_str.strip_prefix("PUBKEY:").unwrap();
         Ok(Response::Pubkey(pubkey.to_string()))
     } else if response_str.starts_with("SIGNATURE:") {
@@ -51,4 +73,123 @@ pub fn parse_esp32_response(data: &[u8]) -> Result<Response, Box<dyn Error>> {
     } else {
         Err(format!("Unknown response format: {}", response_str).into())
     }
-}
\ No newline at end of file
+}
+
+/// Binary protocol versions this app speaks
+pub const MIN_PROTOCOL_VERSION: u8 = 1;
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// Binary frame kinds; a response uses its command's kind with the high bit set
+pub const KIND_HELLO: u8 = 0x01;
+pub const KIND_GET_PUBKEY: u8 = 0x02;
+pub const KIND_SIGN_MESSAGE: u8 = 0x03;
+pub const KIND_SIGN_BATCH: u8 = 0x04;
+pub const KIND_RESPONSE: u8 = 0x80;
+pub const KIND_ERROR: u8 = 0xFF;
+
+/// Kind and payload of a command in the binary protocol
+pub fn encode_binary_command(cmd: &Command) -> (u8, Vec<u8>) {
+    match cmd {
+        Command::GetPubkey => (KIND_GET_PUBKEY, Vec::new()),
+        Command::SignMessage(data) => (KIND_SIGN_MESSAGE, data.clone()),
+        Command::SignBatch(messages) => {
+            // Count, then each message prefixed with its u16 length
+            let mut payload = vec![messages.len() as u8];
+            for message in messages {
+                payload.extend_from_slice(&(message.len() as u16).to_le_bytes());
+                payload.extend_from_slice(message);
+            }
+            (KIND_SIGN_BATCH, payload)
+        }
+    }
+}
+
+/// Parse a binary command, as the device does
+pub fn decode_binary_command(kind: u8, payload: &[u8]) -> Result<Command, String> {
+    match kind {
+        KIND_GET_PUBKEY => Ok(Command::GetPubkey),
+        KIND_SIGN_MESSAGE => Ok(Command::SignMessage(payload.to_vec())),
+        KIND_SIGN_BATCH => {
+            let (&count, mut rest) = payload.split_first().ok_or("Empty batch")?;
+            let mut messages = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                if rest.len() < 2 {
+                    return Err("Truncated batch".to_string());
+                }
+                let len = u16::from_le_bytes([rest[0], rest[1]]) as usize;
+                let message = rest.get(2..2 + len).ok_or("Truncated batch")?;
+                messages.push(message.to_vec());
+                rest = &rest[2 + len..];
+            }
+            if !rest.is_empty() {
+                return Err("Trailing bytes after batch".to_string());
+            }
+            Ok(Command::SignBatch(messages))
+        }
+        other => Err(format!("Unknown command kind {:#04x}", other)),
+    }
+}
+
+/// Kind and payload of a response in the binary protocol, as the device sends it
+pub fn encode_binary_response(response: &Response) -> Result<(u8, Vec<u8>), String> {
+    match response {
+        Response::Pubkey(pubkey) => {
+            let bytes = bs58::decode(pubkey).into_vec().map_err(|e| e.to_string())?;
+            Ok((KIND_GET_PUBKEY | KIND_RESPONSE, bytes))
+        }
+        Response::Signature(signature) => Ok((KIND_SIGN_MESSAGE | KIND_RESPONSE, signature.clone())),
+        Response::Signatures(signatures) => Ok((KIND_SIGN_BATCH | KIND_RESPONSE, signatures.concat())),
+        Response::Error(message) => Ok((KIND_ERROR, message.as_bytes().to_vec())),
+    }
+}
+
+/// Parse a binary response; public keys arrive as 32 raw bytes and signatures as 64
+pub fn decode_binary_response(kind: u8, payload: &[u8]) -> Result<Response, Box<dyn Error>> {
+    match kind {
+        KIND_ERROR => Ok(Response::Error(String::from_utf8_lossy(payload).to_string())),
+        k if k == KIND_GET_PUBKEY | KIND_RESPONSE && payload.len() == 32 => {
+            Ok(Response::Pubkey(bs58::encode(payload).into_string()))
+        }
+        k if k == KIND_SIGN_MESSAGE | KIND_RESPONSE && payload.len() == 64 => Ok(Response::Signature(payload.to_vec())),
+        k if k == KIND_SIGN_BATCH | KIND_RESPONSE && payload.len().is_multiple_of(64) => {
+            Ok(Response::Signatures(payload.chunks(64).map(|s| s.to_vec()).collect()))
+        }
+        other => Err(format!("Unexpected binary response kind {:#04x} with {} bytes", other, payload.len()).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_batch_format() {
+        let command = Command::SignBatch(vec![b"first".to_vec(), b"second".to_vec()]);
+        assert_eq!(format_esp32_command(&command), b"SIGN_BATCH:2:Zmlyc3Q=,c2Vjb25k\n".to_vec());
+
+        match parse_esp32_response(b"SIGNATURES:AQI=,AwQ=\r\n").unwrap() {
+            Response::Signatures(signatures) => assert_eq!(signatures, vec![vec![1, 2], vec![3, 4]]),
+            other => panic!("unexpected response {:?}", other),
+        }
+        assert!(matches!(parse_esp32_response(b"SIGNATURE:AQI=").unwrap(), Response::Signature(_)));
+        assert!(parse_esp32_response(b"SIGNATURES:not base64!").is_err());
+    }
+
+    #[test]
+    fn test_binary_batch_roundtrip() {
+        let command = Command::SignBatch(vec![b"first".to_vec(), Vec::new(), vec![7; 300]]);
+        let (kind, payload) = encode_binary_command(&command);
+        match decode_binary_command(kind, &payload).unwrap() {
+            Command::SignBatch(messages) => assert_eq!(messages, vec![b"first".to_vec(), Vec::new(), vec![7; 300]]),
+            other => panic!("unexpected command {:?}", other),
+        }
+        assert!(decode_binary_command(kind, &payload[..payload.len() - 1]).is_err());
+
+        let (kind, payload) = encode_binary_response(&Response::Signatures(vec![vec![1; 64], vec![2; 64]])).unwrap();
+        match decode_binary_response(kind, &payload).unwrap() {
+            Response::Signatures(signatures) => assert_eq!(signatures, vec![vec![1; 64], vec![2; 64]]),
+            other => panic!("unexpected response {:?}", other),
+        }
+        assert!(decode_binary_response(KIND_SIGN_MESSAGE | KIND_RESPONSE, &[0; 63]).is_err());
+    }
+}
@@ -0,0 +1,554 @@
+// src/hardware/simulator.rs
+use crate::hardware::frame::{self, Frame, Reassembler};
+use crate::hardware::protocol::{
+    decode_binary_command, encode_binary_response, format_esp32_command, Command, Response, KIND_HELLO,
+    KIND_RESPONSE, MAX_BATCH_SIZE, PROTOCOL_VERSION,
+};
+use crate::hardware::transport::{ByteStream, FrameBuffer, StreamTransport};
+use crate::wallet::Wallet;
+use base64::Engine;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Makes `HardwareWallet::connect` use a simulated device instead of scanning serial ports
+/// in builds with the `simulator` feature.
+/// Set it to a base58 private key to fix the device's key, or to anything else for a random one.
+pub const SIMULATOR_ENV: &str = "HARDWARE_WALLET_SIMULATOR";
+
+/// What the simulated user does when the device asks for a button press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Approve,
+    Reject,
+}
+
+/// Misbehaviour that replaces the device's reply to the next command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fault {
+    /// Reply with an error
+    Error(String),
+    /// Reply with these bytes followed by a newline
+    Garbage(Vec<u8>),
+    /// Never reply
+    Timeout,
+    /// Flip a bit in the reply; binary frames fail their checksum, the text protocol can't tell
+    Corrupt,
+}
+
+struct State {
+    response_delay: Duration,
+    approval_delay: Duration,
+    legacy: bool,
+    batch: bool,
+    decisions: VecDeque<Decision>,
+    faults: VecDeque<Fault>,
+    received: Vec<String>,
+    pending: Reassembler,
+}
+
+/// Software stand-in for the ESP32 device. It speaks the binary protocol and the
+/// `GET_PUBKEY` / `SIGN:` text protocol, or only the text one when set to legacy.
+/// Clones share the script and the command log, so a test can keep one while the device runs.
+#[derive(Clone)]
+pub struct DeviceSimulator {
+    wallet: Arc<Wallet>,
+    state: Arc<Mutex<State>>,
+}
+
+impl DeviceSimulator {
+    /// A device holding `wallet`'s key that answers at once and approves everything
+    pub fn new(wallet: Wallet) -> Self {
+        Self {
+            wallet: Arc::new(wallet),
+            state: Arc::new(Mutex::new(State {
+                response_delay: Duration::ZERO,
+                approval_delay: Duration::ZERO,
+                legacy: false,
+                batch: true,
+                decisions: VecDeque::new(),
+                faults: VecDeque::new(),
+                received: Vec::new(),
+                pending: Reassembler::default(),
+            })),
+        }
+    }
+
+    /// A device with a freshly generated key
+    pub fn random() -> Self {
+        Self::new(Wallet::new("Simulated device".to_string()))
+    }
+
+    /// The device configured by `HARDWARE_WALLET_SIMULATOR`, if it is set
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(SIMULATOR_ENV).ok()?;
+        let wallet = bs58::decode(value.trim())
+            .into_vec()
+            .ok()
+            .and_then(|bytes| Wallet::from_private_key(&bytes, "Simulated device".to_string()).ok());
+        Some(wallet.map(Self::new).unwrap_or_else(Self::random))
+    }
+
+    /// The address the device reports for `GET_PUBKEY`
+    pub fn public_key(&self) -> String {
+        self.wallet.get_public_key()
+    }
+
+    /// Wait this long before every reply
+    pub fn set_response_delay(&self, delay: Duration) {
+        self.state.lock().unwrap().response_delay = delay;
+    }
+
+    /// Extra wait before answering a signing request, as if the user were reading the screen
+    pub fn set_approval_delay(&self, delay: Duration) {
+        self.state.lock().unwrap().approval_delay = delay;
+    }
+
+    /// Behave like old firmware that reads only text lines; set before connecting
+    pub fn set_legacy(&self, legacy: bool) {
+        self.state.lock().unwrap().legacy = legacy;
+    }
+
+    /// Behave like firmware that predates `SIGN_BATCH` and answers it as an unknown command
+    pub fn set_batch_support(&self, batch: bool) {
+        self.state.lock().unwrap().batch = batch;
+    }
+
+    /// Queue the user's answers to the next signing requests; unscripted requests are approved
+    pub fn script(&self, decisions: impl IntoIterator<Item = Decision>) {
+        self.state.lock().unwrap().decisions.extend(decisions);
+    }
+
+    /// Misbehave on the next command instead of answering it
+    pub fn inject(&self, fault: Fault) {
+        self.state.lock().unwrap().faults.push_back(fault);
+    }
+
+    /// Every command received so far, written in the text protocol's notation
+    pub fn received(&self) -> Vec<String> {
+        self.state.lock().unwrap().received.clone()
+    }
+
+    /// Reply to one request (a binary frame or a text line), and how long to wait before
+    /// sending it. `None` means the device stays silent.
+    pub fn respond(&self, request: &[u8]) -> (Duration, Option<Vec<u8>>) {
+        let mut state = self.state.lock().unwrap();
+        if frame::is_binary(request) && !state.legacy {
+            return self.respond_binary(&mut state, request);
+        }
+
+        let line = String::from_utf8_lossy(request).trim().to_string();
+        // Stray line endings, e.g. the one after a hello
+        if line.is_empty() {
+            return (Duration::ZERO, None);
+        }
+        state.received.push(line.clone());
+        let command = parse_text_command(&line);
+        self.reply(&mut state, command, None)
+    }
+
+    fn respond_binary(&self, state: &mut State, request: &[u8]) -> (Duration, Option<Vec<u8>>) {
+        // A corrupted request is dropped and the host times out
+        let Ok(frame) = Frame::decode(request) else {
+            return (Duration::ZERO, None);
+        };
+        let request_id = frame.request_id;
+
+        if frame.kind == KIND_HELLO {
+            state.received.push("HELLO".to_string());
+            // The host offers [min, max]; pick the newest version both sides speak
+            let offered = match frame.payload[..] {
+                [min, max, ..] => Some((min, max)),
+                _ => None,
+            };
+            let reply = match offered {
+                Some((min, max)) if min <= PROTOCOL_VERSION => Frame {
+                    version: PROTOCOL_VERSION,
+                    kind: KIND_HELLO | KIND_RESPONSE,
+                    request_id,
+                    flags: 0,
+                    payload: vec![max.min(PROTOCOL_VERSION)],
+                }
+                .encode(),
+                _ => encode_reply(&Response::Error("Unsupported protocol version".to_string()), Some(request_id)),
+            };
+            return (state.response_delay, Some(reply));
+        }
+
+        let command = match state.pending.push(frame) {
+            Ok(Some((kind, payload))) => decode_binary_command(kind, &payload),
+            // Wait for the rest of the chunks
+            Ok(None) => return (Duration::ZERO, None),
+            Err(e) => {
+                state.pending = Reassembler::default();
+                Err(e)
+            }
+        };
+        if let Ok(command) = &command {
+            let notation = String::from_utf8_lossy(&format_esp32_command(command)).trim().to_string();
+            state.received.push(notation);
+        }
+        self.reply(state, command, Some(request_id))
+    }
+
+    /// Answer `command`, or misbehave if a fault is queued. Binary replies carry `request_id`.
+    fn reply(
+        &self,
+        state: &mut State,
+        command: Result<Command, String>,
+        request_id: Option<u16>,
+    ) -> (Duration, Option<Vec<u8>>) {
+        let mut delay = state.response_delay;
+        let fault = state.faults.pop_front();
+        let response = match (&fault, command) {
+            (Some(Fault::Error(message)), _) => Response::Error(message.clone()),
+            (Some(Fault::Garbage(bytes)), _) => {
+                let mut garbage = bytes.clone();
+                garbage.push(b'\n');
+                return (delay, Some(garbage));
+            }
+            (Some(Fault::Timeout), _) => return (delay, None),
+            (_, Ok(command)) => self.answer(state, command, &mut delay),
+            (_, Err(e)) => Response::Error(e),
+        };
+
+        let mut reply = encode_reply(&response, request_id);
+        if fault == Some(Fault::Corrupt) {
+            // The CRC ends a binary frame; a text line ends with its newline
+            let index = reply.len() - if request_id.is_some() { 1 } else { 2 };
+            reply[index] ^= 0x01;
+        }
+        (delay, Some(reply))
+    }
+
+    fn answer(&self, state: &mut State, command: Command, delay: &mut Duration) -> Response {
+        let (messages, batch) = match command {
+            Command::GetPubkey => return Response::Pubkey(self.public_key()),
+            Command::SignMessage(message) => (vec![message], false),
+            Command::SignBatch(_) if !state.batch => return Response::Error("Unknown command".to_string()),
+            Command::SignBatch(messages) if messages.is_empty() || messages.len() > MAX_BATCH_SIZE => {
+                return Response::Error(format!("Batch of {} messages not supported", messages.len()))
+            }
+            Command::SignBatch(messages) => (messages, true),
+        };
+
+        // Waiting for the button press
+        *delay += state.approval_delay;
+        if state.decisions.pop_front().unwrap_or(Decision::Approve) == Decision::Reject {
+            return Response::Error("Rejected by user".to_string());
+        }
+
+        let mut signatures: Vec<Vec<u8>> = messages
+            .iter()
+            .map(|message| self.wallet.sign_message(message).to_bytes().to_vec())
+            .collect();
+        if batch {
+            Response::Signatures(signatures)
+        } else {
+            Response::Signature(signatures.remove(0))
+        }
+    }
+
+    /// Answer requests on `stream` until the host hangs up
+    pub async fn serve(&self, mut stream: impl ByteStream) {
+        let mut requests = RequestReader::new(self.state.lock().unwrap().legacy);
+        let mut chunk = [0u8; 256];
+        loop {
+            let n = match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => n,
+            };
+
+            for request in requests.push(&chunk[..n]) {
+                let (delay, reply) = self.respond(&request);
+                let Some(reply) = reply else { continue };
+                tokio::time::sleep(delay).await;
+                if stream.write_all(&reply).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// An in-memory connection to this device, which runs until the connection is dropped
+    pub fn connect(&self) -> StreamTransport {
+        let (host, device) = tokio::io::duplex(4096);
+        let simulator = self.clone();
+        tokio::spawn(async move { simulator.serve(device).await });
+        StreamTransport::new(host)
+    }
+
+    /// Run the device behind a pseudo-terminal that can be opened like a serial port
+    #[cfg(unix)]
+    pub fn spawn_pty(&self) -> Result<PtySimulator, Box<dyn Error>> {
+        use serialport::SerialPort;
+
+        let (mut master, slave) = serialport::TTYPort::pair()?;
+        let port_name = slave.name().ok_or("Pseudo-terminal has no name")?;
+        master.set_timeout(Duration::from_millis(100))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let simulator = self.clone();
+        let stop_flag = stop.clone();
+        let thread = std::thread::spawn(move || simulator.serve_blocking(&mut master, &stop_flag));
+
+        Ok(PtySimulator {
+            port_name,
+            stop,
+            thread: Some(thread),
+            _slave: slave,
+        })
+    }
+
+    fn serve_blocking(&self, port: &mut (impl Read + Write), stop: &AtomicBool) {
+        let mut requests = RequestReader::new(self.state.lock().unwrap().legacy);
+        let mut chunk = [0u8; 256];
+        while !stop.load(Ordering::Relaxed) {
+            let n = match port.read(&mut chunk) {
+                Ok(n) => n,
+                Err(e) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted) => continue,
+                Err(_) => return,
+            };
+
+            for request in requests.push(&chunk[..n]) {
+                let (delay, reply) = self.respond(&request);
+                let Some(reply) = reply else { continue };
+                std::thread::sleep(delay);
+                if port.write_all(&reply).and_then(|_| port.flush()).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Splits what the host sends into requests: frames and lines, or only lines on old firmware
+struct RequestReader {
+    legacy: bool,
+    frames: FrameBuffer,
+    line: Vec<u8>,
+}
+
+impl RequestReader {
+    fn new(legacy: bool) -> Self {
+        Self {
+            legacy,
+            frames: FrameBuffer::default(),
+            line: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut requests = Vec::new();
+        if self.legacy {
+            self.line.extend_from_slice(bytes);
+            while let Some(end) = self.line.iter().position(|&b| b == b'\n') {
+                requests.push(self.line.drain(..=end).collect());
+            }
+        } else {
+            self.frames.push(bytes);
+            while let Ok(Some(request)) = self.frames.next_frame() {
+                requests.push(request);
+            }
+        }
+        requests
+    }
+}
+
+/// Encode a response as binary frames when answering `request_id`, otherwise as a text line
+fn encode_reply(response: &Response, request_id: Option<u16>) -> Vec<u8> {
+    let Some(request_id) = request_id else {
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::STANDARD.encode(bytes);
+        let line = match response {
+            Response::Pubkey(pubkey) => format!("PUBKEY:{}", pubkey),
+            Response::Signature(signature) => format!("SIGNATURE:{}", encode(signature)),
+            Response::Signatures(signatures) => {
+                let encoded: Vec<String> = signatures.iter().map(|s| encode(s)).collect();
+                format!("SIGNATURES:{}", encoded.join(","))
+            }
+            Response::Error(message) => format!("ERROR:{}", message),
+        };
+        return format!("{}\n", line).into_bytes();
+    };
+
+    let (kind, payload) = encode_binary_response(response)
+        .unwrap_or_else(|e| encode_binary_response(&Response::Error(e)).unwrap());
+    frame::chunk(PROTOCOL_VERSION, kind, request_id, &payload)
+        .iter()
+        .flat_map(Frame::encode)
+        .collect()
+}
+
+/// Parse a text protocol command line
+fn parse_text_command(line: &str) -> Result<Command, String> {
+    if line == "GET_PUBKEY" {
+        Ok(Command::GetPubkey)
+    } else if let Some(batch) = line.strip_prefix("SIGN_BATCH:") {
+        decode_batch(batch).map(Command::SignBatch)
+    } else if let Some(encoded) = line.strip_prefix("SIGN:") {
+        base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map(Command::SignMessage)
+            .map_err(|_| "Invalid base64".to_string())
+    } else {
+        Err("Unknown command".to_string())
+    }
+}
+
+/// Parse `<count>:<base64>,<base64>,...`
+fn decode_batch(batch: &str) -> Result<Vec<Vec<u8>>, String> {
+    let (count, list) = batch.split_once(':').ok_or("Malformed batch")?;
+    let count: usize = count.parse().map_err(|_| "Malformed batch")?;
+
+    let messages = list
+        .split(',')
+        .map(|m| base64::engine::general_purpose::STANDARD.decode(m))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "Invalid base64")?;
+    if messages.len() != count {
+        return Err(format!("Expected {} messages, got {}", count, messages.len()));
+    }
+    Ok(messages)
+}
+
+/// A simulated device on a pseudo-terminal; stops when dropped
+#[cfg(unix)]
+pub struct PtySimulator {
+    port_name: String,
+    stop: Arc<AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+    // Keeps the terminal open between host connections
+    _slave: serialport::TTYPort,
+}
+
+#[cfg(unix)]
+impl PtySimulator {
+    /// Path to pass to `serial::connect`
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+}
+
+#[cfg(unix)]
+impl Drop for PtySimulator {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hardware::{serial, HardwareWallet};
+    use ed25519_dalek::{Signature, VerifyingKey};
+
+    fn verify(simulator: &DeviceSimulator, message: &[u8], signature: &[u8]) {
+        let key_bytes: [u8; 32] = bs58::decode(simulator.public_key()).into_vec().unwrap().try_into().unwrap();
+        let key = VerifyingKey::from_bytes(&key_bytes).unwrap();
+        let signature = Signature::from_slice(signature).unwrap();
+        key.verify_strict(message, &signature).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_hardware_wallet_against_simulator() {
+        let simulator = DeviceSimulator::random();
+        simulator.set_legacy(true);
+        simulator.set_response_delay(Duration::from_millis(20));
+        let wallet = HardwareWallet::new();
+        wallet.connect_with(Box::new(simulator.connect())).await.unwrap();
+        assert_eq!(wallet.get_public_key().await.unwrap(), simulator.public_key());
+
+        let signature = wallet.sign_message(b"hello").await.unwrap();
+        verify(&simulator, b"hello", &signature);
+
+        let messages = vec![b"first".to_vec(), b"second".to_vec()];
+        let signatures = wallet.sign_messages(&messages).await.unwrap();
+        verify(&simulator, b"second", &signatures[1]);
+
+        simulator.script([Decision::Reject]);
+        let rejected = wallet.sign_message(b"hello").await.unwrap_err();
+        assert!(rejected.to_string().contains("Rejected by user"));
+
+        simulator.inject(Fault::Error("Low battery".to_string()));
+        assert!(wallet.sign_message(b"hello").await.unwrap_err().to_string().contains("Low battery"));
+        simulator.inject(Fault::Garbage(b"\x07\x07".to_vec()));
+        assert!(wallet.sign_message(b"hello").await.is_err());
+
+        // The rejected binary hello comes first
+        assert_eq!(simulator.received()[1], "GET_PUBKEY");
+        assert!(simulator.received()[3].starts_with("SIGN_BATCH:2:"));
+    }
+
+    #[tokio::test]
+    async fn test_binary_protocol_against_simulator() {
+        let simulator = DeviceSimulator::random();
+        let wallet = HardwareWallet::new();
+        wallet.connect_with(Box::new(simulator.connect())).await.unwrap();
+        assert_eq!(wallet.get_public_key().await.unwrap(), simulator.public_key());
+
+        // Full-size transactions are split into chunks on the way to the device
+        let messages: Vec<Vec<u8>> = (0..MAX_BATCH_SIZE as u8).map(|i| vec![i; 1232]).collect();
+        let signatures = wallet.sign_messages(&messages).await.unwrap();
+        verify(&simulator, &messages[9], &signatures[9]);
+
+        simulator.inject(Fault::Corrupt);
+        let error = wallet.sign_message(b"hello").await.unwrap_err();
+        assert!(error.to_string().contains("checksum"));
+        simulator.script([Decision::Reject]);
+        assert!(wallet.sign_message(b"hello").await.unwrap_err().to_string().contains("Rejected by user"));
+
+        assert_eq!(simulator.received()[..2], ["HELLO".to_string(), "GET_PUBKEY".to_string()]);
+        assert!(simulator.received()[2].starts_with("SIGN_BATCH:10:"));
+    }
+
+    #[tokio::test]
+    async fn test_simulator_timeout() {
+        let simulator = DeviceSimulator::random();
+        let transport = simulator.connect().with_timeout(Duration::from_millis(200));
+        let wallet = HardwareWallet::new();
+        wallet.connect_with(Box::new(transport)).await.unwrap();
+        simulator.inject(Fault::Timeout);
+        let error = wallet.sign_message(b"hello").await.unwrap_err();
+        assert!(error.to_string().contains("Timeout"));
+    }
+
+    #[tokio::test]
+    async fn test_firmware_without_batch_support() {
+        let simulator = DeviceSimulator::random();
+        simulator.set_legacy(true);
+        simulator.set_batch_support(false);
+        let wallet = HardwareWallet::new();
+        wallet.connect_with(Box::new(simulator.connect())).await.unwrap();
+
+        let messages = vec![b"first".to_vec(), b"second".to_vec()];
+        let signatures = wallet.sign_messages(&messages).await.unwrap();
+        verify(&simulator, b"first", &signatures[0]);
+        verify(&simulator, b"second", &signatures[1]);
+
+        // Once refused, batches are not offered again on this connection
+        wallet.sign_messages(&messages).await.unwrap();
+        let received = simulator.received();
+        assert_eq!(received.iter().filter(|c| c.starts_with("SIGN_BATCH:")).count(), 1);
+        assert_eq!(received.iter().filter(|c| c.starts_with("SIGN:")).count(), 4);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_serial_connection_over_pty() {
+        let simulator = DeviceSimulator::random();
+        simulator.set_approval_delay(Duration::from_millis(50));
+        let pty = simulator.spawn_pty().unwrap();
+
+        let wallet = HardwareWallet::new();
+        wallet.connect_with(Box::new(serial::connect(pty.port_name()).await.unwrap())).await.unwrap();
+        let signature = wallet.sign_message(b"over the wire").await.unwrap();
+        verify(&simulator, b"over the wire", &signature);
+    }
+}
@@ -0,0 +1,36 @@
+// src/hardware/tcp.rs
+use crate::hardware::transport::{StreamTransport, TransportError};
+
+/// Makes `HardwareWallet::connect` use a TCP bridge at this `host:port`
+pub const TCP_BRIDGE_ENV: &str = "HARDWARE_WALLET_TCP";
+
+/// Reach a device exposed over TCP, e.g. by `ser2net` or `socat TCP-LISTEN:7000 /dev/ttyUSB0,raw`
+pub async fn connect(address: &str) -> Result<StreamTransport, TransportError> {
+    let stream = tokio::net::TcpStream::connect(address).await?;
+    stream.set_nodelay(true)?;
+    Ok(StreamTransport::new(stream))
+}
+
+#[cfg(all(test, not(target_os = "android")))]
+mod tests {
+    use super::*;
+    use crate::hardware::simulator::DeviceSimulator;
+    use crate::hardware::HardwareWallet;
+
+    #[tokio::test]
+    async fn test_hardware_wallet_over_tcp_bridge() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let simulator = DeviceSimulator::random();
+        let device = simulator.clone();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            device.serve(socket).await;
+        });
+
+        let wallet = HardwareWallet::new();
+        wallet.connect_with(Box::new(connect(&address).await.unwrap())).await.unwrap();
+        assert_eq!(wallet.get_public_key().await.unwrap(), simulator.public_key());
+        assert_eq!(wallet.sign_message(b"bridged").await.unwrap().len(), 64);
+    }
+}
@@ -0,0 +1,277 @@
+// src/hardware/transport.rs
+use crate::hardware::frame;
+use crate::hardware::protocol::MAX_RESPONSE_LEN;
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// How long to wait for the device to answer a command
+pub const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors shared by every transport
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransportError {
+    NotConnected,
+    Timeout,
+    /// The device or bridge hung up
+    Closed,
+    FrameTooLong,
+    Io(String),
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::NotConnected => write!(f, "Not connected to hardware wallet"),
+            TransportError::Timeout => write!(f, "Timeout waiting for response"),
+            TransportError::Closed => write!(f, "Hardware wallet connection closed"),
+            TransportError::FrameTooLong => write!(f, "Response too long"),
+            TransportError::Io(e) => write!(f, "Hardware wallet I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+impl From<io::Error> for TransportError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::TimedOut => TransportError::Timeout,
+            io::ErrorKind::UnexpectedEof | io::ErrorKind::BrokenPipe => TransportError::Closed,
+            _ => TransportError::Io(e.to_string()),
+        }
+    }
+}
+
+/// Moves whole frames between the app and the device. A frame is exactly what the
+/// protocol encodes: one binary frame, or one newline-terminated line of the legacy protocol.
+#[async_trait]
+pub trait Transport: Send {
+    /// Send one frame
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<(), TransportError>;
+
+    /// Wait for the next frame from the device
+    async fn receive_frame(&mut self) -> Result<Vec<u8>, TransportError>;
+
+    /// Release the device; the transport can't be used afterwards
+    async fn close(&mut self);
+}
+
+/// Collects received bytes and splits them into binary frames and text lines
+#[derive(Debug, Default)]
+pub struct FrameBuffer {
+    buffer: Vec<u8>,
+}
+
+impl FrameBuffer {
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// The next complete frame, if one has arrived
+    pub fn next_frame(&mut self) -> Result<Option<Vec<u8>>, TransportError> {
+        loop {
+            let newline = self.buffer.iter().position(|&b| b == b'\n');
+
+            // Binary frames carry their own length and may contain newlines
+            if let Some(start) = frame::find_magic(&self.buffer) {
+                if newline.is_none_or(|end| start < end) {
+                    match frame::frame_len(&self.buffer[start..]) {
+                        Ok(Some(len)) => return Ok(Some(self.buffer.drain(..start + len).skip(start).collect())),
+                        Ok(None) => return Ok(None),
+                        // Noise that happens to look like a magic; skip past it
+                        Err(_) => {
+                            self.buffer.drain(..=start);
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            return match newline {
+                Some(end) => Ok(Some(self.buffer.drain(..=end).collect())),
+                None if self.buffer.len() > MAX_RESPONSE_LEN => {
+                    self.buffer.clear();
+                    Err(TransportError::FrameTooLong)
+                }
+                None => Ok(None),
+            };
+        }
+    }
+}
+
+/// Anything frames can travel over as bytes: a serial port, a TCP socket or an in-memory pipe
+pub trait ByteStream: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> ByteStream for T {}
+
+/// Transport over a byte stream
+pub struct StreamTransport {
+    stream: Box<dyn ByteStream>,
+    frames: FrameBuffer,
+    timeout: Duration,
+}
+
+impl StreamTransport {
+    pub fn new(stream: impl ByteStream + 'static) -> Self {
+        Self {
+            stream: Box::new(stream),
+            frames: FrameBuffer::default(),
+            timeout: RESPONSE_TIMEOUT,
+        }
+    }
+
+    /// Give up on a frame after `timeout` instead of the default 10 seconds
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+#[async_trait]
+impl Transport for StreamTransport {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<(), TransportError> {
+        self.stream.write_all(frame).await?;
+        self.stream.flush().await?;
+        Ok(())
+    }
+
+    async fn receive_frame(&mut self) -> Result<Vec<u8>, TransportError> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+        let mut chunk = [0u8; 256];
+        loop {
+            if let Some(frame) = self.frames.next_frame()? {
+                return Ok(frame);
+            }
+
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            match tokio::time::timeout(remaining, self.stream.read(&mut chunk)).await {
+                Err(_) => return Err(TransportError::Timeout),
+                Ok(Ok(0)) => return Err(TransportError::Closed),
+                Ok(Ok(n)) => self.frames.push(&chunk[..n]),
+                // Serial ports report their own short timeouts; keep waiting until ours
+                Ok(Err(e)) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock | io::ErrorKind::Interrupted) => {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                Ok(Err(e)) => return Err(e.into()),
+            }
+        }
+    }
+
+    async fn close(&mut self) {
+        let _ = self.stream.shutdown().await;
+    }
+}
+
+#[derive(Default)]
+struct MockState {
+    sent: Vec<Vec<u8>>,
+    replies: VecDeque<Result<Vec<u8>, TransportError>>,
+    closed: bool,
+}
+
+/// In-memory transport that records sent frames and replays queued replies.
+/// Clones share state, so a test can keep one after handing the other to `HardwareWallet`.
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a frame to be received
+    pub fn reply(&self, frame: impl Into<Vec<u8>>) {
+        self.state.lock().unwrap().replies.push_back(Ok(frame.into()));
+    }
+
+    /// Queue an error to be returned instead of a frame
+    pub fn fail(&self, error: TransportError) {
+        self.state.lock().unwrap().replies.push_back(Err(error));
+    }
+
+    /// Frames sent so far
+    pub fn sent(&self) -> Vec<Vec<u8>> {
+        self.state.lock().unwrap().sent.clone()
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+}
+
+#[async_trait]
+impl Transport for MockTransport {
+    async fn send_frame(&mut self, frame: &[u8]) -> Result<(), TransportError> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Err(TransportError::Closed);
+        }
+        state.sent.push(frame.to_vec());
+        Ok(())
+    }
+
+    async fn receive_frame(&mut self) -> Result<Vec<u8>, TransportError> {
+        // Nothing queued behaves like a silent device
+        self.state.lock().unwrap().replies.pop_front().unwrap_or(Err(TransportError::Timeout))
+    }
+
+    async fn close(&mut self) {
+        self.state.lock().unwrap().closed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stream_transport_frames() {
+        let (host, mut device) = tokio::io::duplex(64);
+        let mut transport = StreamTransport::new(host).with_timeout(Duration::from_millis(200));
+
+        transport.send_frame(b"GET_PUBKEY\n").await.unwrap();
+        let mut received = [0u8; 11];
+        device.read_exact(&mut received).await.unwrap();
+        assert_eq!(&received, b"GET_PUBKEY\n");
+
+        // Two frames in one write, then one split across writes
+        device.write_all(b"PUBKEY:a\nERROR:b\nSIGNA").await.unwrap();
+        assert_eq!(transport.receive_frame().await.unwrap(), b"PUBKEY:a\n");
+        assert_eq!(transport.receive_frame().await.unwrap(), b"ERROR:b\n");
+        device.write_all(b"TURE:c\n").await.unwrap();
+        assert_eq!(transport.receive_frame().await.unwrap(), b"SIGNATURE:c\n");
+
+        assert_eq!(transport.receive_frame().await, Err(TransportError::Timeout));
+        drop(device);
+        assert_eq!(transport.receive_frame().await, Err(TransportError::Closed));
+    }
+
+    #[test]
+    fn test_frame_too_long() {
+        let mut frames = FrameBuffer::default();
+        frames.push(&vec![b'A'; MAX_RESPONSE_LEN + 1]);
+        assert_eq!(frames.next_frame(), Err(TransportError::FrameTooLong));
+        frames.push(b"OK\n");
+        assert_eq!(frames.next_frame(), Ok(Some(b"OK\n".to_vec())));
+    }
+
+    #[test]
+    fn test_binary_frame_after_boot_chatter() {
+        let encoded = frame::chunk(1, 0x82, 3, b"payload\nwith newline").remove(0).encode();
+        let mut frames = FrameBuffer::default();
+        frames.push(b"ets Jun  8 2016 boot\r\nrst:0x1");
+        frames.push(&encoded[..5]);
+        assert_eq!(frames.next_frame(), Ok(Some(b"ets Jun  8 2016 boot\r\n".to_vec())));
+        assert_eq!(frames.next_frame(), Ok(None));
+        frames.push(&encoded[5..]);
+        assert_eq!(frames.next_frame(), Ok(Some(encoded)));
+        assert_eq!(frames.next_frame(), Ok(None));
+    }
+}
@@ -0,0 +1,182 @@
+// src/history.rs
+use crate::rpc::{self, TransactionInfo};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Which way value moved between the wallet and a counterparty
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+/// Another address that a transaction moved SOL or tokens to or from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterparty {
+    pub address: String,
+    pub direction: Direction,
+}
+
+/// A history row: the signature status plus who the transaction was with
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub info: TransactionInfo,
+    pub counterparties: Vec<Counterparty>,
+}
+
+fn push_unique(parties: &mut Vec<Counterparty>, address: &str, direction: Direction) {
+    if !parties.iter().any(|p| p.address == address) {
+        parties.push(Counterparty {
+            address: address.to_string(),
+            direction,
+        });
+    }
+}
+
+/// Sum of raw token amounts per owner in a `pre/postTokenBalances` list
+fn token_totals(balances: Option<&Value>) -> HashMap<String, i128> {
+    let mut totals = HashMap::new();
+    for balance in balances.and_then(Value::as_array).into_iter().flatten() {
+        let owner = balance.get("owner").and_then(Value::as_str);
+        let amount = balance
+            .pointer("/uiTokenAmount/amount")
+            .and_then(Value::as_str)
+            .and_then(|a| a.parse::<i128>().ok());
+        if let (Some(owner), Some(amount)) = (owner, amount) {
+            *totals.entry(owner.to_string()).or_insert(0) += amount;
+        }
+    }
+    totals
+}
+
+/// Find who `owner` paid or was paid by, from a `jsonParsed` transaction's message and meta.
+///
+/// SOL transfers come from parsed system instructions. Token transfers come from the
+/// token balance changes, which name wallet owners rather than token accounts.
+pub fn counterparties(message: Option<&Value>, meta: Option<&Value>, owner: &str) -> Vec<Counterparty> {
+    let mut parties = Vec::new();
+
+    let instructions = message
+        .and_then(|m| m.get("instructions"))
+        .and_then(Value::as_array);
+    for instruction in instructions.into_iter().flatten() {
+        if instruction.get("program").and_then(Value::as_str) != Some("system") {
+            continue;
+        }
+        let info = instruction.pointer("/parsed/info");
+        let source = info.and_then(|i| i.get("source")).and_then(Value::as_str);
+        let destination = info.and_then(|i| i.get("destination")).and_then(Value::as_str);
+        match (source, destination) {
+            (Some(source), Some(destination)) if source == owner && destination != owner => {
+                push_unique(&mut parties, destination, Direction::Sent)
+            }
+            (Some(source), Some(destination)) if destination == owner && source != owner => {
+                push_unique(&mut parties, source, Direction::Received)
+            }
+            _ => {}
+        }
+    }
+
+    let pre = token_totals(meta.and_then(|m| m.get("preTokenBalances")));
+    let post = token_totals(meta.and_then(|m| m.get("postTokenBalances")));
+    let mut owners: Vec<&String> = pre.keys().chain(post.keys()).collect();
+    owners.sort();
+    owners.dedup();
+    for other in owners.into_iter().filter(|o| o.as_str() != owner) {
+        let change = post.get(other).copied().unwrap_or(0) - pre.get(other).copied().unwrap_or(0);
+        if change > 0 {
+            push_unique(&mut parties, other, Direction::Sent);
+        } else if change < 0 {
+            push_unique(&mut parties, other, Direction::Received);
+        }
+    }
+
+    parties
+}
+
+/// Recent transactions for `address` with their counterparties. Details for all of
+/// them are fetched in one batch request rather than one request per transaction.
+/// A transaction whose details fail to load is still listed, without counterparties.
+pub async fn load_history(address: &str, limit: usize, rpc_url: Option<&str>) -> Result<Vec<HistoryEntry>, String> {
+    let transactions = rpc::get_transaction_history(address, limit, rpc_url).await?;
+
+    let signatures: Vec<String> = transactions.iter().map(|info| info.signature.clone()).collect();
+    let details = match rpc::get_transactions(&signatures, rpc_url).await {
+        Ok(details) => details,
+        Err(e) => {
+            log::warn!("⚠️ Failed to load transaction details for {}: {}", address, e);
+            Vec::new()
+        }
+    };
+
+    let entries = transactions
+        .into_iter()
+        .enumerate()
+        .map(|(index, info)| {
+            let counterparties = match details.get(index) {
+                Some(Ok(transaction)) => counterparties(
+                    transaction.get("transaction").and_then(|t| t.get("message")),
+                    transaction.get("meta"),
+                    address,
+                ),
+                Some(Err(e)) => {
+                    log::warn!("⚠️ Failed to load details for {}: {}", info.signature, e);
+                    Vec::new()
+                }
+                None => Vec::new(),
+            };
+            HistoryEntry { info, counterparties }
+        })
+        .collect();
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ME: &str = "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk";
+    const FRIEND: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    fn system_transfer(source: &str, destination: &str) -> Value {
+        json!({
+            "program": "system",
+            "parsed": { "type": "transfer", "info": { "source": source, "destination": destination, "lamports": 1000 } }
+        })
+    }
+
+    fn token_balance(owner: &str, amount: &str) -> Value {
+        json!({ "accountIndex": 1, "owner": owner, "uiTokenAmount": { "amount": amount, "decimals": 6 } })
+    }
+
+    #[test]
+    fn test_sol_transfer_counterparties() {
+        let message = json!({ "instructions": [
+            { "program": "compute-budget", "parsed": { "type": "setComputeUnitLimit" } },
+            system_transfer(ME, FRIEND),
+            system_transfer(ME, FRIEND),
+        ]});
+        assert_eq!(
+            counterparties(Some(&message), None, ME),
+            [Counterparty { address: FRIEND.to_string(), direction: Direction::Sent }]
+        );
+
+        let incoming = json!({ "instructions": [system_transfer(FRIEND, ME)] });
+        assert_eq!(counterparties(Some(&incoming), None, ME)[0].direction, Direction::Received);
+    }
+
+    #[test]
+    fn test_token_transfer_counterparties() {
+        let meta = json!({
+            "preTokenBalances": [token_balance(ME, "500"), token_balance(FRIEND, "0")],
+            "postTokenBalances": [token_balance(ME, "200"), token_balance(FRIEND, "300")],
+        });
+        assert_eq!(
+            counterparties(None, Some(&meta), ME),
+            [Counterparty { address: FRIEND.to_string(), direction: Direction::Sent }]
+        );
+        // Nothing to show for a transaction that moved nothing between wallets
+        assert!(counterparties(Some(&json!({ "instructions": [] })), Some(&json!({})), ME).is_empty());
+    }
+}
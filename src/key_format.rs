@@ -0,0 +1,295 @@
+// src/key_format.rs
+use base64::{
+    engine::general_purpose::{STANDARD as BASE64, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD},
+    Engine as _,
+};
+use std::fmt;
+
+/// Textual encodings accepted for private keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// Base58 string (Phantom / Solflare export)
+    Base58,
+    /// Solana CLI `id.json` byte array, e.g. `[12,34,...]`
+    JsonArray,
+    /// Hex string, optionally prefixed with `0x`
+    Hex,
+    /// Bare comma-separated byte list, e.g. `12, 34, ...`
+    ByteList,
+    /// Base64, standard or URL-safe, with or without padding
+    Base64,
+}
+
+impl KeyFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            KeyFormat::Base58 => "base58",
+            KeyFormat::JsonArray => "JSON byte array",
+            KeyFormat::Hex => "hex",
+            KeyFormat::ByteList => "byte list",
+            KeyFormat::Base64 => "base64",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyImportError {
+    Empty,
+    InvalidJson(String),
+    /// A byte entry that is not an integer
+    InvalidByte { position: usize, value: String },
+    /// An integer entry outside 0..=255
+    ByteOutOfRange { position: usize, value: String },
+    InvalidHex(String),
+    InvalidBase64(String),
+    InvalidBase58(String),
+    /// Decoded fine but is neither a 32-byte secret nor a 64-byte keypair
+    WrongLength { format: KeyFormat, len: usize },
+    /// Decodes to a key as both base58 and base64, so the key can't be known
+    Ambiguous,
+}
+
+impl fmt::Display for KeyImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyImportError::Empty => write!(f, "Private key is empty"),
+            KeyImportError::InvalidJson(e) => write!(f, "Invalid JSON keypair: {}", e),
+            KeyImportError::InvalidByte { position, value } => {
+                write!(f, "Byte {} is not a number: '{}'", position + 1, value)
+            }
+            KeyImportError::ByteOutOfRange { position, value } => {
+                write!(f, "Byte {} is out of range (0-255): {}", position + 1, value)
+            }
+            KeyImportError::InvalidHex(e) => write!(f, "Invalid hex key: {}", e),
+            KeyImportError::InvalidBase64(e) => write!(f, "Invalid base64 key: {}", e),
+            KeyImportError::InvalidBase58(e) => write!(f, "Invalid base58 key: {}", e),
+            KeyImportError::WrongLength { format, len } => write!(
+                f,
+                "Decoded {} key is {} bytes, expected a 32-byte private key or 64-byte keypair",
+                format.label(),
+                len
+            ),
+            KeyImportError::Ambiguous => write!(
+                f,
+                "This key reads as both base58 and base64. Paste it in another format, such as the JSON byte array"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeyImportError {}
+
+impl From<KeyImportError> for String {
+    fn from(e: KeyImportError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Guess the encoding of a pasted key without decoding it. Some base58 keys are also
+/// valid base64; `parse_private_key` refuses those rather than pick one.
+pub fn detect_format(input: &str) -> KeyFormat {
+    let input = input.trim();
+    let hex_body = input.strip_prefix("0x").unwrap_or(input);
+
+    if input.starts_with('[') {
+        KeyFormat::JsonArray
+    } else if input.contains(',') {
+        KeyFormat::ByteList
+    } else if (hex_body.len() == 64 || hex_body.len() == 128 || input.starts_with("0x"))
+        && hex_body.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        // Base58 encodings of 32/64 bytes are 43-44/87-88 chars, so these lengths can't collide
+        KeyFormat::Hex
+    } else if !input.chars().all(is_base58_char) {
+        // Anything outside the base58 alphabet (`+/=-_`, `0OIl`) can only be base64
+        KeyFormat::Base64
+    } else {
+        KeyFormat::Base58
+    }
+}
+
+/// Decode a private key in any supported format into 32 or 64 raw bytes
+pub fn parse_private_key(input: &str) -> Result<(KeyFormat, Vec<u8>), KeyImportError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(KeyImportError::Empty);
+    }
+
+    let format = detect_format(input);
+    let bytes = match format {
+        KeyFormat::JsonArray => parse_json_array(input)?,
+        KeyFormat::ByteList => parse_byte_list(input)?,
+        KeyFormat::Hex => {
+            let body = input.strip_prefix("0x").unwrap_or(input);
+            hex::decode(body).map_err(|e| KeyImportError::InvalidHex(e.to_string()))?
+        }
+        KeyFormat::Base64 => decode_base64(input)?,
+        KeyFormat::Base58 => {
+            let bytes = bs58::decode(input)
+                .into_vec()
+                .map_err(|e| KeyImportError::InvalidBase58(e.to_string()))?;
+            // A 43-character base58 key can also be unpadded base64 of 32 bytes
+            if matches!(bytes.len(), 32 | 64) && matches!(decode_base64(input).map(|b| b.len()), Ok(32 | 64)) {
+                return Err(KeyImportError::Ambiguous);
+            }
+            bytes
+        }
+    };
+
+    match bytes.len() {
+        32 | 64 => Ok((format, bytes)),
+        len => Err(KeyImportError::WrongLength { format, len }),
+    }
+}
+
+fn is_base58_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l')
+}
+
+/// Accept every base64 dialect wallets export: standard or URL-safe, padded or not
+fn decode_base64(input: &str) -> Result<Vec<u8>, KeyImportError> {
+    let url_safe = input.contains(['-', '_']);
+    let padded = input.ends_with('=');
+    let engine = match (url_safe, padded) {
+        (false, true) => &BASE64,
+        (false, false) => &STANDARD_NO_PAD,
+        (true, true) => &URL_SAFE,
+        (true, false) => &URL_SAFE_NO_PAD,
+    };
+    engine
+        .decode(input)
+        .map_err(|e| KeyImportError::InvalidBase64(e.to_string()))
+}
+
+fn parse_json_array(input: &str) -> Result<Vec<u8>, KeyImportError> {
+    let values: Vec<serde_json::Value> =
+        serde_json::from_str(input).map_err(|e| KeyImportError::InvalidJson(e.to_string()))?;
+
+    values
+        .iter()
+        .enumerate()
+        .map(|(position, value)| match value.as_i64() {
+            Some(byte) => u8::try_from(byte).map_err(|_| KeyImportError::ByteOutOfRange {
+                position,
+                value: byte.to_string(),
+            }),
+            None => Err(KeyImportError::InvalidByte {
+                position,
+                value: value.to_string(),
+            }),
+        })
+        .collect()
+}
+
+fn parse_byte_list(input: &str) -> Result<Vec<u8>, KeyImportError> {
+    input
+        .split(',')
+        .map(str::trim)
+        // Tolerate a trailing comma
+        .filter(|token| !token.is_empty())
+        .enumerate()
+        .map(|(position, token)| match token.parse::<i64>() {
+            Ok(byte) => u8::try_from(byte).map_err(|_| KeyImportError::ByteOutOfRange {
+                position,
+                value: token.to_string(),
+            }),
+            Err(_) => Err(KeyImportError::InvalidByte {
+                position,
+                value: token.to_string(),
+            }),
+        })
+        .collect()
+}
+
+/// Solana CLI keypair file contents (`solana-keygen` writes the same compact array)
+pub fn to_cli_json(keypair: &[u8]) -> String {
+    let bytes: Vec<String> = keypair.iter().map(|b| b.to_string()).collect();
+    format!("[{}]", bytes.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_keypair() -> Vec<u8> {
+        // 64 bytes; validity of the public half is checked by Wallet, not here
+        (0..64).collect()
+    }
+
+    #[test]
+    fn test_detect_and_parse_formats() {
+        let keypair = sample_keypair();
+
+        let json = to_cli_json(&keypair);
+        assert_eq!(parse_private_key(&json).unwrap(), (KeyFormat::JsonArray, keypair.clone()));
+
+        let pretty_json = serde_json::to_string_pretty(&keypair).unwrap();
+        assert_eq!(parse_private_key(&pretty_json).unwrap().1, keypair);
+
+        let list = json.trim_matches(|c| c == '[' || c == ']').replace(',', ", ");
+        assert_eq!(parse_private_key(&list).unwrap(), (KeyFormat::ByteList, keypair.clone()));
+
+        let hex_key = hex::encode(&keypair);
+        assert_eq!(parse_private_key(&hex_key).unwrap(), (KeyFormat::Hex, keypair.clone()));
+        assert_eq!(parse_private_key(&format!("0x{}", hex_key)).unwrap().1, keypair);
+
+        let b64 = BASE64.encode(&keypair);
+        assert_eq!(parse_private_key(&b64).unwrap(), (KeyFormat::Base64, keypair.clone()));
+
+        let b64_unpadded = STANDARD_NO_PAD.encode(&keypair);
+        assert_eq!(parse_private_key(&b64_unpadded).unwrap(), (KeyFormat::Base64, keypair.clone()));
+
+        let b64_url = URL_SAFE.encode(&keypair);
+        assert!(b64_url.contains(['-', '_']));
+        assert_eq!(parse_private_key(&b64_url).unwrap(), (KeyFormat::Base64, keypair.clone()));
+
+        let b64_url_unpadded = URL_SAFE_NO_PAD.encode(&keypair);
+        assert_eq!(parse_private_key(&b64_url_unpadded).unwrap(), (KeyFormat::Base64, keypair.clone()));
+
+        let b58 = bs58::encode(&keypair).into_string();
+        assert_eq!(parse_private_key(&b58).unwrap(), (KeyFormat::Base58, keypair.clone()));
+
+        let secret_only = bs58::encode(&keypair[..32]).into_string();
+        assert_eq!(parse_private_key(&secret_only).unwrap().1.len(), 32);
+    }
+
+    #[test]
+    fn test_precise_errors() {
+        assert_eq!(parse_private_key("   "), Err(KeyImportError::Empty));
+        assert!(matches!(parse_private_key("[1, 2,"), Err(KeyImportError::InvalidJson(_))));
+        assert_eq!(
+            parse_private_key("[1, 256, 3]"),
+            Err(KeyImportError::ByteOutOfRange { position: 1, value: "256".to_string() })
+        );
+        assert_eq!(
+            parse_private_key("[1, \"a\"]"),
+            Err(KeyImportError::InvalidByte { position: 1, value: "\"a\"".to_string() })
+        );
+        assert_eq!(
+            parse_private_key("1, 2, x"),
+            Err(KeyImportError::InvalidByte { position: 2, value: "x".to_string() })
+        );
+        assert_eq!(
+            parse_private_key("1, -2"),
+            Err(KeyImportError::ByteOutOfRange { position: 1, value: "-2".to_string() })
+        );
+        assert!(matches!(parse_private_key("0xabc"), Err(KeyImportError::InvalidHex(_))));
+        assert!(matches!(parse_private_key("abc=d"), Err(KeyImportError::InvalidBase64(_))));
+        assert!(matches!(parse_private_key("ab-c_d+"), Err(KeyImportError::InvalidBase64(_))));
+        assert_eq!(
+            parse_private_key("[1, 2, 3]"),
+            Err(KeyImportError::WrongLength { format: KeyFormat::JsonArray, len: 3 })
+        );
+        assert_eq!(
+            parse_private_key("3yZe7d"),
+            Err(KeyImportError::WrongLength { format: KeyFormat::Base58, len: 4 })
+        );
+
+        // Some 32-byte keys encode to 43 base58 characters that are also unpadded base64
+        let ambiguous = (0u8..=255)
+            .map(|n| bs58::encode([n; 32]).into_string())
+            .find(|key| STANDARD_NO_PAD.decode(key).is_ok_and(|bytes| bytes.len() == 32))
+            .unwrap();
+        assert_eq!(parse_private_key(&ambiguous), Err(KeyImportError::Ambiguous));
+    }
+}
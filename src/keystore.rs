@@ -0,0 +1,337 @@
+// src/keystore.rs
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use zeroize::Zeroizing;
+
+const KDF_ARGON2ID: &str = "argon2id";
+const CIPHER_XCHACHA20POLY1305: &str = "xchacha20poly1305";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+/// Argon2id cost parameters, stored with every secret so they can be raised later
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory cost in KiB
+    pub m_cost: u32,
+    /// Number of passes
+    pub t_cost: u32,
+    /// Degree of parallelism
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    /// OWASP recommended minimum for Argon2id (19 MiB, 2 passes)
+    fn default() -> Self {
+        Self {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+/// A secret encrypted under a passphrase-derived key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EncryptedSecret {
+    pub kdf: String,
+    pub kdf_params: KdfParams,
+    pub cipher: String,
+    /// Base64 Argon2 salt
+    pub salt: String,
+    /// Base64 AEAD nonce
+    pub nonce: String,
+    /// Base64 ciphertext including the authentication tag
+    pub ciphertext: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeystoreError {
+    /// No passphrase has been entered in this session
+    Locked,
+    /// Decryption failed authentication (wrong passphrase or tampered data)
+    WrongPassphrase,
+    /// The secret uses a KDF or cipher this build does not know
+    Unsupported(String),
+    /// Salt, nonce or ciphertext could not be decoded
+    Corrupted(String),
+    Kdf(String),
+}
+
+impl std::fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            KeystoreError::Locked => write!(f, "Wallet is locked, enter your passphrase first"),
+            KeystoreError::WrongPassphrase => write!(f, "Incorrect passphrase"),
+            KeystoreError::Unsupported(what) => write!(f, "Unsupported keystore format: {}", what),
+            KeystoreError::Corrupted(what) => write!(f, "Corrupted keystore entry: {}", what),
+            KeystoreError::Kdf(e) => write!(f, "Key derivation failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for KeystoreError {}
+
+impl From<KeystoreError> for String {
+    fn from(e: KeystoreError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Derive the 32-byte encryption key for `passphrase` and `salt`
+fn derive_key(passphrase: &str, salt: &[u8], params: &KdfParams) -> Result<Zeroizing<[u8; KEY_LEN]>, KeystoreError> {
+    let argon_params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(KEY_LEN))
+        .map_err(|e| KeystoreError::Kdf(e.to_string()))?;
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params);
+
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    argon2
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| KeystoreError::Kdf(e.to_string()))?;
+    Ok(key)
+}
+
+fn encrypt_with_key(
+    secret: &[u8],
+    key: &[u8; KEY_LEN],
+    salt: &[u8],
+    params: KdfParams,
+) -> Result<EncryptedSecret, KeystoreError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), secret)
+        .map_err(|_| KeystoreError::Corrupted("encryption failed".to_string()))?;
+
+    Ok(EncryptedSecret {
+        kdf: KDF_ARGON2ID.to_string(),
+        kdf_params: params,
+        cipher: CIPHER_XCHACHA20POLY1305.to_string(),
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt_with_key(secret: &EncryptedSecret, key: &[u8; KEY_LEN]) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+    let nonce = BASE64
+        .decode(&secret.nonce)
+        .map_err(|e| KeystoreError::Corrupted(format!("nonce: {}", e)))?;
+    if nonce.len() != NONCE_LEN {
+        return Err(KeystoreError::Corrupted(format!("nonce is {} bytes", nonce.len())));
+    }
+    let ciphertext = BASE64
+        .decode(&secret.ciphertext)
+        .map_err(|e| KeystoreError::Corrupted(format!("ciphertext: {}", e)))?;
+
+    let cipher = XChaCha20Poly1305::new(key.into());
+    cipher
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+        .map(Zeroizing::new)
+        .map_err(|_| KeystoreError::WrongPassphrase)
+}
+
+fn check_supported(secret: &EncryptedSecret) -> Result<Vec<u8>, KeystoreError> {
+    if secret.kdf != KDF_ARGON2ID {
+        return Err(KeystoreError::Unsupported(secret.kdf.clone()));
+    }
+    if secret.cipher != CIPHER_XCHACHA20POLY1305 {
+        return Err(KeystoreError::Unsupported(secret.cipher.clone()));
+    }
+    BASE64
+        .decode(&secret.salt)
+        .map_err(|e| KeystoreError::Corrupted(format!("salt: {}", e)))
+}
+
+/// Encrypt `secret` under `passphrase` with a fresh salt and nonce
+pub fn encrypt_secret(secret: &[u8], passphrase: &str, params: KdfParams) -> Result<EncryptedSecret, KeystoreError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, &params)?;
+    encrypt_with_key(secret, &key, &salt, params)
+}
+
+/// Decrypt a secret with an explicit passphrase
+pub fn decrypt_secret(secret: &EncryptedSecret, passphrase: &str) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+    let salt = check_supported(secret)?;
+    let key = derive_key(passphrase, &salt, &secret.kdf_params)?;
+    decrypt_with_key(secret, &key)
+}
+
+/// Passphrase and derived keys held in memory while the app is unlocked.
+/// Secrets sealed in one session share its salt (each still gets its own nonce),
+/// so Argon2 only runs once per unlock instead of once per wallet.
+/// Everything secret is wiped when the session is dropped.
+struct Session {
+    passphrase: Zeroizing<String>,
+    params: KdfParams,
+    salt: [u8; SALT_LEN],
+    key: Zeroizing<[u8; KEY_LEN]>,
+    derived: HashMap<String, Zeroizing<[u8; KEY_LEN]>>,
+}
+
+impl Session {
+    fn key_for(&mut self, secret: &EncryptedSecret, salt: &[u8]) -> Result<Zeroizing<[u8; KEY_LEN]>, KeystoreError> {
+        let cache_key = format!(
+            "{}:{}:{}:{}",
+            secret.salt, secret.kdf_params.m_cost, secret.kdf_params.t_cost, secret.kdf_params.p_cost
+        );
+        if let Some(key) = self.derived.get(&cache_key) {
+            return Ok(key.clone());
+        }
+        let key = derive_key(&self.passphrase, salt, &secret.kdf_params)?;
+        self.derived.insert(cache_key, key.clone());
+        Ok(key)
+    }
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+/// Start a session with the default KDF cost
+pub fn unlock_session(passphrase: &str) -> Result<(), KeystoreError> {
+    unlock_session_with_params(passphrase, KdfParams::default())
+}
+
+/// Start a session; new secrets are sealed with `params`
+pub fn unlock_session_with_params(passphrase: &str, params: KdfParams) -> Result<(), KeystoreError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt, &params)?;
+
+    let mut session = SESSION.lock().unwrap();
+    *session = Some(Session {
+        passphrase: Zeroizing::new(passphrase.to_string()),
+        params,
+        salt,
+        key,
+        derived: HashMap::new(),
+    });
+    Ok(())
+}
+
+/// Forget the passphrase and every derived key
+pub fn lock_session() {
+    *SESSION.lock().unwrap() = None;
+}
+
+pub fn is_unlocked() -> bool {
+    SESSION.lock().unwrap().is_some()
+}
+
+/// Check `passphrase` against the unlocked session, for actions that ask for it again
+pub fn verify_passphrase(passphrase: &str) -> Result<(), KeystoreError> {
+    let (salt, params, expected) = {
+        let session = SESSION.lock().unwrap();
+        let session = session.as_ref().ok_or(KeystoreError::Locked)?;
+        (session.salt, session.params, session.key.clone())
+    };
+    let key = derive_key(passphrase, &salt, &params)?;
+    if *key == *expected {
+        Ok(())
+    } else {
+        Err(KeystoreError::WrongPassphrase)
+    }
+}
+
+/// Encrypt a secret with the unlocked session's passphrase
+pub fn seal(secret: &[u8]) -> Result<EncryptedSecret, KeystoreError> {
+    let session = SESSION.lock().unwrap();
+    let session = session.as_ref().ok_or(KeystoreError::Locked)?;
+    encrypt_with_key(secret, &session.key, &session.salt, session.params)
+}
+
+/// Decrypt a secret with the unlocked session's passphrase
+pub fn open(secret: &EncryptedSecret) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+    let salt = check_supported(secret)?;
+    let mut session = SESSION.lock().unwrap();
+    let session = session.as_mut().ok_or(KeystoreError::Locked)?;
+    let key = session.key_for(secret, &salt)?;
+    decrypt_with_key(secret, &key)
+}
+
+/// Cheap parameters so unit tests don't spend seconds in Argon2
+#[cfg(test)]
+pub(crate) fn test_params() -> KdfParams {
+    KdfParams {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    }
+}
+
+/// Unlock the shared session for tests. Every test uses the same passphrase,
+/// so tests running in parallel can still open each other's secrets.
+#[cfg(test)]
+pub(crate) fn unlock_test_session() {
+    unlock_session_with_params("test passphrase", test_params()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let secret = b"super secret key material";
+        let encrypted = encrypt_secret(secret, "correct horse", test_params()).unwrap();
+
+        assert_eq!(encrypted.kdf, "argon2id");
+        assert_eq!(encrypted.kdf_params, test_params());
+        assert!(!encrypted.ciphertext.contains("super"));
+
+        assert_eq!(decrypt_secret(&encrypted, "correct horse").unwrap().as_slice(), secret);
+        assert_eq!(
+            decrypt_secret(&encrypted, "wrong horse"),
+            Err(KeystoreError::WrongPassphrase)
+        );
+    }
+
+    #[test]
+    fn test_fresh_salt_and_nonce() {
+        let a = encrypt_secret(b"same", "pass", test_params()).unwrap();
+        let b = encrypt_secret(b"same", "pass", test_params()).unwrap();
+        assert_ne!(a.salt, b.salt);
+        assert_ne!(a.nonce, b.nonce);
+        assert_ne!(a.ciphertext, b.ciphertext);
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_rejected() {
+        let mut encrypted = encrypt_secret(b"secret", "pass", test_params()).unwrap();
+        let mut bytes = BASE64.decode(&encrypted.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        encrypted.ciphertext = BASE64.encode(bytes);
+        assert_eq!(decrypt_secret(&encrypted, "pass"), Err(KeystoreError::WrongPassphrase));
+
+        let mut unknown = encrypt_secret(b"secret", "pass", test_params()).unwrap();
+        unknown.cipher = "rot13".to_string();
+        assert!(matches!(decrypt_secret(&unknown, "pass"), Err(KeystoreError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_session_seal_open() {
+        unlock_test_session();
+        let sealed = seal(b"session secret").unwrap();
+        assert_eq!(open(&sealed).unwrap().as_slice(), b"session secret");
+
+        // Secrets sealed under the same passphrase in an earlier session still open
+        let earlier = encrypt_secret(b"older", "test passphrase", test_params()).unwrap();
+        assert_eq!(open(&earlier).unwrap().as_slice(), b"older");
+
+        let foreign = encrypt_secret(b"other", "different passphrase", test_params()).unwrap();
+        assert_eq!(open(&foreign), Err(KeystoreError::WrongPassphrase));
+
+        assert!(verify_passphrase("test passphrase").is_ok());
+        assert_eq!(verify_passphrase("different passphrase"), Err(KeystoreError::WrongPassphrase));
+    }
+}
@@ -3,12 +3,28 @@
 use dioxus::prelude::*;
 
 mod wallet;
+mod derivation;
+mod keystore;
+mod discovery;
+mod key_format;
+mod qr;
+mod vanity;
+mod wallet_repository;
+mod backup;
+mod shamir;
+mod address_book;
+mod transaction_notes;
+mod history;
 mod rpc;
 mod prices;
 mod transaction;
 mod signing;
 mod hardware;
+mod offline;
+mod nonce;
+mod preview;
 mod storage;
+mod schema;
 mod components;
 mod validators;
 mod staking;
@@ -30,6 +46,8 @@ use async_channel::{unbounded, Receiver, Sender};
 use once_cell::sync::OnceCell;
 #[cfg(target_os = "android")]
 use solana_sdk::pubkey::Pubkey;
+#[cfg(target_os = "android")]
+use signing::mwa::MwaReply;
 
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
@@ -45,7 +63,10 @@ const MAIN_CSS: Asset = asset!("/assets/main.css");
 pub enum MsgFromKotlin {
     Pubkey(String),
     SignedTransaction(String),
+    SignedTransactions(Vec<String>),
     SignedMessage(String),
+    /// The wallet app rejected the request or it failed
+    RequestFailed(String),
 }
 
 #[cfg(target_os = "android")]
@@ -103,21 +124,32 @@ fn App() -> Element {
         // Listen for MWA messages from Kotlin (EXACT pattern from original_main.rs)
         use_future(move || async move {
             if let Some(rx) = RX.get().cloned() {
+                // Replies resolve whichever MwaSigner request is waiting on them
+                let bridge = signing::mwa::android_bridge();
                 while let Ok(msg) = rx.recv().await {
                     match msg {
                         MsgFromKotlin::Pubkey(base58) => {
                             if let Ok(pubkey) = Pubkey::from_str(base58.as_str()) {
                                 log::info!("🔗 MWA Connected with pubkey: {}", pubkey);
                                 mwa_wallet_state.set(WalletState::Pubkey(pubkey));
+                                bridge.deliver(MwaReply::Pubkey(base58));
                             }
                         }
                         MsgFromKotlin::SignedTransaction(base64_tx) => {
                             log::info!("📝 MWA: Received signed transaction: {}", base64_tx);
-                            // Handle signed transaction here if needed
+                            bridge.deliver(MwaReply::SignedTransaction(base64_tx));
+                        }
+                        MsgFromKotlin::SignedTransactions(base64_txs) => {
+                            log::info!("📝 MWA: Received {} signed transactions", base64_txs.len());
+                            bridge.deliver(MwaReply::SignedTransactions(base64_txs));
                         }
                         MsgFromKotlin::SignedMessage(signature) => {
                             log::info!("✍️ MWA: Received signed message: {}", signature);
-                            // Handle signed message here if needed
+                            bridge.deliver(MwaReply::SignedMessage(signature));
+                        }
+                        MsgFromKotlin::RequestFailed(reason) => {
+                            log::warn!("🛑 MWA: Wallet app request failed: {}", reason);
+                            bridge.cancel();
                         }
                     }
                 }
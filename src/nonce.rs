@@ -0,0 +1,230 @@
+// src/nonce.rs
+use crate::rpc::{get_minimum_balance_for_rent_exemption, DEFAULT_RPC_URL};
+use crate::signing::partial::PartialTransaction;
+use crate::signing::TransactionSigner;
+use crate::transaction::TransactionClient;
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    message::VersionedMessage,
+    nonce::state::{State, Versions},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Size of a nonce account's data
+pub const NONCE_ACCOUNT_SIZE: usize = State::size();
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NonceError {
+    NotFound(String),
+    /// The account exists but is not an initialized nonce account
+    NotNonceAccount(String),
+    Rpc(String),
+    Transaction(String),
+}
+
+impl fmt::Display for NonceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NonceError::NotFound(address) => write!(f, "Nonce account {} does not exist", address),
+            NonceError::NotNonceAccount(reason) => write!(f, "Not a nonce account: {}", reason),
+            NonceError::Rpc(reason) => write!(f, "RPC error: {}", reason),
+            NonceError::Transaction(reason) => write!(f, "Transaction failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for NonceError {}
+
+impl From<NonceError> for String {
+    fn from(e: NonceError) -> Self {
+        e.to_string()
+    }
+}
+
+/// State of an initialized nonce account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceAccountInfo {
+    pub address: Pubkey,
+    /// Must sign every transaction that uses or changes the nonce
+    pub authority: Pubkey,
+    /// Use in place of a recent blockhash
+    pub nonce: Hash,
+    pub lamports_per_signature: u64,
+    pub balance: u64,
+}
+
+impl NonceAccountInfo {
+    /// Decode raw account data as returned by `getAccountInfo`
+    pub fn parse(address: Pubkey, balance: u64, data: &[u8]) -> Result<Self, NonceError> {
+        let versions: Versions =
+            bincode::deserialize(data).map_err(|e| NonceError::NotNonceAccount(e.to_string()))?;
+        match versions.state() {
+            State::Initialized(state) => Ok(Self {
+                address,
+                authority: state.authority,
+                nonce: state.blockhash(),
+                lamports_per_signature: state.get_lamports_per_signature(),
+                balance,
+            }),
+            State::Uninitialized => Err(NonceError::NotNonceAccount("account is uninitialized".to_string())),
+        }
+    }
+}
+
+/// Creates and manages durable nonce accounts.
+/// Pair with `TransactionClient::with_durable_nonce` to build transactions that don't expire.
+pub struct NonceClient {
+    transaction_client: TransactionClient,
+    rpc_url: String,
+}
+
+impl NonceClient {
+    pub fn new(rpc_url: Option<&str>) -> Self {
+        let url = rpc_url.unwrap_or(DEFAULT_RPC_URL);
+        Self {
+            transaction_client: TransactionClient::new(Some(url)),
+            rpc_url: url.to_string(),
+        }
+    }
+
+    pub async fn get_nonce_account(&self, address: &str) -> Result<NonceAccountInfo, NonceError> {
+        let address = Pubkey::from_str(address).map_err(|e| NonceError::NotNonceAccount(e.to_string()))?;
+        self.transaction_client.get_nonce_account(&address).await
+    }
+
+    /// Create a nonce account funded by the signer. `authority` defaults to the signer.
+    /// Returns the new account's address and the transaction signature.
+    pub async fn create_nonce_account(
+        &self,
+        signer: &dyn TransactionSigner,
+        authority: Option<Pubkey>,
+    ) -> Result<(Pubkey, String), NonceError> {
+        let payer = signer_pubkey(signer).await?;
+        let rent = get_minimum_balance_for_rent_exemption(NONCE_ACCOUNT_SIZE, Some(&self.rpc_url))
+            .await
+            .map_err(|e| NonceError::Rpc(e.to_string()))?;
+
+        let nonce_keypair = Keypair::new();
+        let nonce_address = nonce_keypair.pubkey();
+        let instructions = system_instruction::create_nonce_account(
+            &payer,
+            &nonce_address,
+            &authority.unwrap_or(payer),
+            rent,
+        );
+        log::info!("🔐 Creating nonce account {} with {} lamports", nonce_address, rent);
+
+        let signature = self.send(signer, &payer, &instructions, Some(&nonce_keypair)).await?;
+        Ok((nonce_address, signature))
+    }
+
+    /// Move the account to a new nonce value, invalidating anything signed against the old one
+    pub async fn advance_nonce(&self, signer: &dyn TransactionSigner, address: &str) -> Result<String, NonceError> {
+        let (authority, nonce) = self.authorized(signer, address).await?;
+        let instruction = system_instruction::advance_nonce_account(&nonce.address, &authority);
+        self.send(signer, &authority, &[instruction], None).await
+    }
+
+    /// Withdraw lamports; withdrawing the whole balance closes the account
+    pub async fn withdraw(
+        &self,
+        signer: &dyn TransactionSigner,
+        address: &str,
+        to_address: &str,
+        lamports: u64,
+    ) -> Result<String, NonceError> {
+        let (authority, nonce) = self.authorized(signer, address).await?;
+        let to = Pubkey::from_str(to_address).map_err(|e| NonceError::Transaction(e.to_string()))?;
+        let instruction = system_instruction::withdraw_nonce_account(&nonce.address, &authority, &to, lamports);
+        self.send(signer, &authority, &[instruction], None).await
+    }
+
+    /// Hand the nonce authority to another key
+    pub async fn set_authority(
+        &self,
+        signer: &dyn TransactionSigner,
+        address: &str,
+        new_authority: &str,
+    ) -> Result<String, NonceError> {
+        let (authority, nonce) = self.authorized(signer, address).await?;
+        let new_authority = Pubkey::from_str(new_authority).map_err(|e| NonceError::Transaction(e.to_string()))?;
+        let instruction = system_instruction::authorize_nonce_account(&nonce.address, &authority, &new_authority);
+        self.send(signer, &authority, &[instruction], None).await
+    }
+
+    /// Load the account and check the signer is its authority
+    async fn authorized(
+        &self,
+        signer: &dyn TransactionSigner,
+        address: &str,
+    ) -> Result<(Pubkey, NonceAccountInfo), NonceError> {
+        let signer_key = signer_pubkey(signer).await?;
+        let nonce = self.get_nonce_account(address).await?;
+        if nonce.authority != signer_key {
+            return Err(NonceError::Transaction(format!(
+                "{} is not the authority of this nonce account ({})",
+                signer_key, nonce.authority
+            )));
+        }
+        Ok((signer_key, nonce))
+    }
+
+    async fn send(
+        &self,
+        signer: &dyn TransactionSigner,
+        payer: &Pubkey,
+        instructions: &[Instruction],
+        new_account: Option<&Keypair>,
+    ) -> Result<String, NonceError> {
+        let result: Result<String, Box<dyn Error>> = async {
+            let message = self.transaction_client.build_message(payer, instructions).await?;
+            let mut transaction = PartialTransaction::new(VersionedMessage::Legacy(message));
+            if let Some(keypair) = new_account {
+                transaction.sign_with_keypair(keypair)?;
+            }
+            transaction.sign_with(signer).await?;
+            self.transaction_client.send_partial_transaction(transaction).await
+        }
+        .await;
+        result.map_err(|e| NonceError::Transaction(e.to_string()))
+    }
+}
+
+async fn signer_pubkey(signer: &dyn TransactionSigner) -> Result<Pubkey, NonceError> {
+    let address = signer
+        .get_public_key()
+        .await
+        .map_err(|e| NonceError::Transaction(e.to_string()))?;
+    Pubkey::from_str(&address).map_err(|e| NonceError::Transaction(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::nonce::state::{Data, DurableNonce};
+
+    #[test]
+    fn test_parse_nonce_account() {
+        let address = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let durable_nonce = DurableNonce::from_blockhash(&Hash::new_unique());
+        let state = State::Initialized(Data::new(authority, durable_nonce, 5_000));
+        let data = bincode::serialize(&Versions::new(state)).unwrap();
+        assert_eq!(data.len(), NONCE_ACCOUNT_SIZE);
+
+        let info = NonceAccountInfo::parse(address, 1_447_680, &data).unwrap();
+        assert_eq!(info.authority, authority);
+        assert_eq!(info.nonce, *durable_nonce.as_hash());
+        assert_eq!(info.lamports_per_signature, 5_000);
+
+        let uninitialized = bincode::serialize(&Versions::new(State::Uninitialized)).unwrap();
+        assert!(NonceAccountInfo::parse(address, 0, &uninitialized).is_err());
+        assert!(NonceAccountInfo::parse(address, 0, &[1, 2, 3]).is_err());
+    }
+}
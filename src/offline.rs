@@ -0,0 +1,263 @@
+// src/offline.rs
+use crate::signing::partial::{PartialSignError, PartialTransaction};
+use crate::signing::TransactionSigner;
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::str::FromStr;
+
+const REQUEST_PREFIX: &str = "unruggable-tx:";
+const SIGNATURE_PREFIX: &str = "unruggable-sig:";
+const PART_PREFIX: &str = "unruggable-part:";
+/// Payload characters per QR frame, small enough to scan reliably off a phone screen
+pub const QR_PART_LEN: usize = 400;
+/// Upper bound on the part count a frame may claim
+const MAX_PARTS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OfflineError {
+    Malformed(String),
+    /// A scanned part belongs to a different payload than the ones before it
+    PartMismatch,
+    Transaction(PartialSignError),
+}
+
+impl fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OfflineError::Malformed(reason) => write!(f, "Not a signing request or signature: {}", reason),
+            OfflineError::PartMismatch => write!(f, "This QR code is from a different transaction"),
+            OfflineError::Transaction(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+impl From<OfflineError> for String {
+    fn from(e: OfflineError) -> Self {
+        e.to_string()
+    }
+}
+
+impl From<PartialSignError> for OfflineError {
+    fn from(e: PartialSignError) -> Self {
+        OfflineError::Transaction(e)
+    }
+}
+
+/// Text form of an unsigned (or partly signed) transaction for the offline device
+pub fn encode_request(transaction: &PartialTransaction) -> String {
+    format!("{}{}", REQUEST_PREFIX, transaction.to_base64())
+}
+
+/// Accepts the prefixed text or bare base64
+pub fn decode_request(text: &str) -> Result<PartialTransaction, OfflineError> {
+    let text = text.trim();
+    Ok(PartialTransaction::from_base64(text.strip_prefix(REQUEST_PREFIX).unwrap_or(text))?)
+}
+
+/// A signature carried back from the offline device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureReply {
+    pub signer: Pubkey,
+    pub signature: Vec<u8>,
+}
+
+impl SignatureReply {
+    pub fn to_text(&self) -> String {
+        format!("{}{}:{}", SIGNATURE_PREFIX, self.signer, bs58::encode(&self.signature).into_string())
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, OfflineError> {
+        let body = text
+            .trim()
+            .strip_prefix(SIGNATURE_PREFIX)
+            .ok_or_else(|| OfflineError::Malformed(format!("expected {}<address>:<signature>", SIGNATURE_PREFIX)))?;
+        let (signer, signature) = body
+            .split_once(':')
+            .ok_or_else(|| OfflineError::Malformed("missing signature".to_string()))?;
+        let signer = Pubkey::from_str(signer).map_err(|e| OfflineError::Malformed(e.to_string()))?;
+        let signature = bs58::decode(signature)
+            .into_vec()
+            .map_err(|e| OfflineError::Malformed(e.to_string()))?;
+        Ok(Self { signer, signature })
+    }
+}
+
+/// Offline side: sign the request and produce the reply to carry back
+pub async fn sign_request<S: TransactionSigner + ?Sized>(
+    transaction: &mut PartialTransaction,
+    signer: &S,
+) -> Result<SignatureReply, OfflineError> {
+    let key = transaction.sign_with(signer).await?;
+    let signature = transaction
+        .signature(&key)
+        .ok_or_else(|| OfflineError::Malformed("signature was not recorded".to_string()))?;
+    Ok(SignatureReply {
+        signer: key,
+        signature: signature.as_ref().to_vec(),
+    })
+}
+
+/// Online side: attach a returned signature before broadcasting
+pub fn attach_signature(transaction: &mut PartialTransaction, reply: &SignatureReply) -> Result<(), OfflineError> {
+    Ok(transaction.add_signature(&reply.signer, &reply.signature)?)
+}
+
+/// Split `payload` into QR frames. Short payloads stay a single plain frame;
+/// longer ones become `unruggable-part:<n>/<total>:<id>:<chunk>` frames shown in turn.
+pub fn split_qr_parts(payload: &str) -> Vec<String> {
+    if payload.len() <= QR_PART_LEN {
+        return vec![payload.to_string()];
+    }
+    // Payloads are base64/base58 text, so byte chunks are always on character boundaries
+    let chunks: Vec<&str> = payload
+        .as_bytes()
+        .chunks(QR_PART_LEN)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or_default())
+        .collect();
+    let id = payload_id(payload);
+    chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| format!("{}{}/{}:{}:{}", PART_PREFIX, i + 1, chunks.len(), id, chunk))
+        .collect()
+}
+
+/// Ties the parts of one payload together and checks the reassembled result
+fn payload_id(payload: &str) -> String {
+    hex::encode(&Sha256::digest(payload.as_bytes())[..4])
+}
+
+/// Reassembles scanned QR frames in any order
+#[derive(Debug, Default)]
+pub struct MultipartDecoder {
+    id: Option<String>,
+    parts: Vec<Option<String>>,
+}
+
+impl MultipartDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one scanned frame. Returns the whole payload once every part has been seen;
+    /// a plain single-frame payload is returned straight away.
+    pub fn add(&mut self, scanned: &str) -> Result<Option<String>, OfflineError> {
+        let scanned = scanned.trim();
+        let Some(part) = scanned.strip_prefix(PART_PREFIX) else {
+            return Ok(Some(scanned.to_string()));
+        };
+
+        let malformed = || OfflineError::Malformed("bad QR part header".to_string());
+        let mut fields = part.splitn(3, ':');
+        let (Some(position), Some(id), Some(chunk)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(malformed());
+        };
+        let (index, total) = position.split_once('/').ok_or_else(malformed)?;
+        let index: usize = index.parse().map_err(|_| malformed())?;
+        let total: usize = total.parse().map_err(|_| malformed())?;
+        if index == 0 || index > total || total > MAX_PARTS {
+            return Err(malformed());
+        }
+
+        match &self.id {
+            Some(existing) if existing != id || self.parts.len() != total => return Err(OfflineError::PartMismatch),
+            Some(_) => {}
+            None => {
+                self.id = Some(id.to_string());
+                self.parts = vec![None; total];
+            }
+        }
+        self.parts[index - 1] = Some(chunk.to_string());
+
+        if self.parts.iter().any(Option::is_none) {
+            return Ok(None);
+        }
+        let payload: String = self.parts.iter().flatten().map(String::as_str).collect();
+        if payload_id(&payload) != id {
+            self.reset();
+            return Err(OfflineError::Malformed("reassembled payload does not match its checksum".to_string()));
+        }
+        Ok(Some(payload))
+    }
+
+    /// (parts received, parts expected); (0, 0) before the first part
+    pub fn progress(&self) -> (usize, usize) {
+        (self.parts.iter().flatten().count(), self.parts.len())
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preview::TransactionPreview;
+    use crate::signing::SignerType;
+    use crate::wallet::Wallet;
+    use solana_sdk::{
+        hash::Hash,
+        message::{Message, VersionedMessage},
+        system_instruction,
+    };
+
+    fn transfer_request(from: &Pubkey, to: &Pubkey) -> PartialTransaction {
+        let mut message = Message::new(&[system_instruction::transfer(from, to, 1_500_000_000)], Some(from));
+        message.recent_blockhash = Hash::new_unique();
+        PartialTransaction::new(VersionedMessage::Legacy(message))
+    }
+
+    #[tokio::test]
+    async fn test_offline_signing_round_trip() {
+        let wallet = Wallet::new("Cold".to_string());
+        let from = Pubkey::from_str(&wallet.get_public_key()).unwrap();
+        let to = Pubkey::new_unique();
+
+        // Online: export the unsigned transaction
+        let online = transfer_request(&from, &to);
+        let frames = split_qr_parts(&encode_request(&online));
+
+        // Offline: scan the frames in any order, review, sign
+        let mut decoder = MultipartDecoder::new();
+        let mut scanned = None;
+        for frame in frames.iter().rev() {
+            scanned = decoder.add(frame).unwrap();
+        }
+        let mut offline = decode_request(&scanned.unwrap()).unwrap();
+        assert_eq!(
+            TransactionPreview::new(offline.message()).lines(),
+            vec![format!("Send 1.5 SOL from {} to {}", from, to)]
+        );
+        let reply = sign_request(&mut offline, &SignerType::from_wallet(wallet)).await.unwrap();
+
+        // Online: attach and the transaction is ready to broadcast
+        let mut online = online;
+        attach_signature(&mut online, &SignatureReply::from_text(&reply.to_text()).unwrap()).unwrap();
+        assert!(online.finish().is_ok());
+    }
+
+    #[test]
+    fn test_multipart_decoder() {
+        let payload = "a".repeat(QR_PART_LEN * 2) + "tail";
+        let frames = split_qr_parts(&payload);
+        assert_eq!(frames.len(), 3);
+        assert!(frames[0].starts_with("unruggable-part:1/3:"));
+
+        let mut decoder = MultipartDecoder::new();
+        assert_eq!(decoder.add(&frames[2]).unwrap(), None);
+        assert_eq!(decoder.add(&frames[2]).unwrap(), None);
+        assert_eq!(decoder.progress(), (1, 3));
+        let other = split_qr_parts(&"b".repeat(QR_PART_LEN * 2));
+        assert_eq!(decoder.add(&other[0]), Err(OfflineError::PartMismatch));
+        assert_eq!(decoder.add(&frames[0]).unwrap(), None);
+        assert_eq!(decoder.add(&frames[1]).unwrap(), Some(payload));
+
+        assert!(MultipartDecoder::new().add("unruggable-part:4/3:abcd:x").is_err());
+        assert_eq!(MultipartDecoder::new().add(" plain ").unwrap(), Some("plain".to_string()));
+        assert!(SignatureReply::from_text("unruggable-sig:nope").is_err());
+    }
+}
@@ -0,0 +1,708 @@
+// src/preview.rs
+use crate::signing::offchain::OffchainMessage;
+use crate::signing::TransactionSigner;
+use async_trait::async_trait;
+use solana_sdk::{
+    compute_budget,
+    instruction::CompiledInstruction,
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    stake::instruction::StakeInstruction,
+    system_instruction::SystemInstruction,
+    system_program,
+};
+use spl_token::instruction::TokenInstruction;
+use std::error::Error;
+use std::fmt;
+
+pub const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PMjPE9GvDdE4W8";
+pub const MEMO_PROGRAMS: [&str; 2] = [
+    "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+    "Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo",
+];
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// An account an instruction refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountRef {
+    Key(Pubkey),
+    /// Loaded from an address lookup table, so only known once the transaction runs
+    Lookup(u8),
+    /// The instruction lists too few accounts
+    Missing,
+}
+
+impl AccountRef {
+    pub fn key(&self) -> Option<Pubkey> {
+        match self {
+            AccountRef::Key(key) => Some(*key),
+            _ => None,
+        }
+    }
+
+    pub fn is(&self, key: &Pubkey) -> bool {
+        self.key().as_ref() == Some(key)
+    }
+}
+
+impl fmt::Display for AccountRef {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccountRef::Key(key) => write!(f, "{}", key),
+            AccountRef::Lookup(index) => write!(f, "lookup table account #{}", index),
+            AccountRef::Missing => f.write_str("missing account"),
+        }
+    }
+}
+
+/// What one instruction does, decoded from its program and data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstructionSummary {
+    /// System transfer, transfer with seed, or nonce withdrawal.
+    /// `authority` signs for `from`, and is `from` itself for a plain transfer.
+    SolTransfer { from: AccountRef, to: AccountRef, lamports: u64, authority: AccountRef },
+    CreateAccount { funder: AccountRef, account: AccountRef, lamports: u64, owner: Pubkey },
+    Assign { account: AccountRef, owner: Pubkey },
+    InitializeNonce { nonce: AccountRef, authority: Pubkey },
+    AdvanceNonce { nonce: AccountRef },
+    AuthorizeNonce { nonce: AccountRef, new_authority: Pubkey },
+    System { name: String },
+    /// `mint` and `decimals` are only known for checked transfers
+    TokenTransfer {
+        program: Pubkey,
+        source: AccountRef,
+        destination: AccountRef,
+        authority: AccountRef,
+        mint: Option<AccountRef>,
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    TokenApprove { program: Pubkey, source: AccountRef, delegate: AccountRef, amount: u64 },
+    TokenSetAuthority { program: Pubkey, account: AccountRef },
+    CloseTokenAccount { program: Pubkey, account: AccountRef, destination: AccountRef },
+    Token { program: Pubkey, name: String },
+    CreateTokenAccount { payer: AccountRef, account: AccountRef, owner: AccountRef, mint: AccountRef },
+    StakeInitialize { stake_account: AccountRef, staker: AccountRef, withdrawer: AccountRef },
+    StakeDelegate { stake_account: AccountRef, vote_account: AccountRef },
+    StakeWithdraw { stake_account: AccountRef, to: AccountRef, lamports: u64, authority: AccountRef },
+    StakeDeactivate { stake_account: AccountRef },
+    StakeAuthorize { stake_account: AccountRef, new_authority: AccountRef },
+    Stake { name: String },
+    ComputeUnitLimit(u32),
+    /// Micro-lamports per compute unit
+    ComputeUnitPrice(u64),
+    ComputeBudget { name: String },
+    Memo(String),
+    Unknown { program: Pubkey, accounts: usize, data_len: usize },
+}
+
+impl InstructionSummary {
+    /// Instructions that hand over control of funds, or that nobody can vouch for
+    pub fn is_risky(&self) -> bool {
+        matches!(
+            self,
+            InstructionSummary::Assign { .. }
+                | InstructionSummary::AuthorizeNonce { .. }
+                | InstructionSummary::TokenApprove { .. }
+                | InstructionSummary::TokenSetAuthority { .. }
+                | InstructionSummary::CloseTokenAccount { .. }
+                | InstructionSummary::StakeAuthorize { .. }
+                | InstructionSummary::Unknown { .. }
+        )
+    }
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL
+}
+
+fn token_program_name(program: &Pubkey) -> &'static str {
+    if *program == spl_token::id() {
+        "Token"
+    } else {
+        "Token-2022"
+    }
+}
+
+impl fmt::Display for InstructionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InstructionSummary::SolTransfer { from, to, lamports, .. } => {
+                write!(f, "Send {} SOL from {} to {}", sol(*lamports), from, to)
+            }
+            InstructionSummary::CreateAccount { account, lamports, owner, .. } => write!(
+                f,
+                "Create account {} with {} SOL, owned by {}",
+                account, sol(*lamports), owner
+            ),
+            InstructionSummary::Assign { account, owner } => {
+                write!(f, "⚠️ Hand account {} to program {}", account, owner)
+            }
+            InstructionSummary::InitializeNonce { nonce, authority } => {
+                write!(f, "Set up nonce account {} for {}", nonce, authority)
+            }
+            InstructionSummary::AdvanceNonce { nonce } => write!(f, "Use durable nonce {}", nonce),
+            InstructionSummary::AuthorizeNonce { nonce, new_authority } => {
+                write!(f, "⚠️ Give control of nonce account {} to {}", nonce, new_authority)
+            }
+            InstructionSummary::System { name } => write!(f, "System program: {}", name),
+            InstructionSummary::TokenTransfer { source, destination, mint, amount, decimals, .. } => {
+                match (mint, decimals) {
+                    (Some(mint), Some(decimals)) => write!(
+                        f,
+                        "Send {} of token {} from {} to {}",
+                        *amount as f64 / 10_f64.powi(*decimals as i32),
+                        mint, source, destination
+                    ),
+                    _ => write!(f, "Send {} token units from {} to {}", amount, source, destination),
+                }
+            }
+            InstructionSummary::TokenApprove { source, delegate, amount, .. } => write!(
+                f,
+                "⚠️ Let {} spend up to {} token units from {}",
+                delegate, amount, source
+            ),
+            InstructionSummary::TokenSetAuthority { account, .. } => {
+                write!(f, "⚠️ Change who controls token account {}", account)
+            }
+            InstructionSummary::CloseTokenAccount { account, destination, .. } => {
+                write!(f, "Close token account {}, returning its rent to {}", account, destination)
+            }
+            InstructionSummary::Token { program, name } => {
+                write!(f, "{} program: {}", token_program_name(program), name)
+            }
+            InstructionSummary::CreateTokenAccount { account, owner, mint, .. } => {
+                write!(f, "Create token account {} for {} (mint {})", account, owner, mint)
+            }
+            InstructionSummary::StakeInitialize { stake_account, withdrawer, .. } => write!(
+                f,
+                "Set up stake account {}, withdrawable by {}",
+                stake_account, withdrawer
+            ),
+            InstructionSummary::StakeDelegate { stake_account, vote_account } => write!(
+                f,
+                "Delegate stake account {} to validator {}",
+                stake_account, vote_account
+            ),
+            InstructionSummary::StakeWithdraw { stake_account, to, lamports, .. } => write!(
+                f,
+                "Withdraw {} SOL from stake account {} to {}",
+                sol(*lamports), stake_account, to
+            ),
+            InstructionSummary::StakeDeactivate { stake_account } => {
+                write!(f, "Deactivate stake account {}", stake_account)
+            }
+            InstructionSummary::StakeAuthorize { stake_account, new_authority } => write!(
+                f,
+                "⚠️ Give control of stake account {} to {}",
+                stake_account, new_authority
+            ),
+            InstructionSummary::Stake { name } => write!(f, "Stake program: {}", name),
+            InstructionSummary::ComputeUnitLimit(units) => write!(f, "Compute limit: {} units", units),
+            InstructionSummary::ComputeUnitPrice(price) => {
+                write!(f, "Priority fee: {} micro-lamports per compute unit", price)
+            }
+            InstructionSummary::ComputeBudget { name } => write!(f, "Compute budget: {}", name),
+            InstructionSummary::Memo(text) => write!(f, "Memo: \"{}\"", text),
+            InstructionSummary::Unknown { program, accounts, data_len } => write!(
+                f,
+                "⚠️ Unknown program {}: {} accounts, {} bytes of data",
+                program, accounts, data_len
+            ),
+        }
+    }
+}
+
+/// A readable summary of everything a transaction message does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionPreview {
+    pub fee_payer: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub instructions: Vec<InstructionSummary>,
+    /// Some accounts come from address lookup tables and are shown by index
+    pub uses_lookup_tables: bool,
+}
+
+impl TransactionPreview {
+    pub fn new(message: &VersionedMessage) -> Self {
+        let keys = message.static_account_keys();
+        let required = message.header().num_required_signatures as usize;
+        Self {
+            fee_payer: keys.first().copied().unwrap_or_default(),
+            signers: keys.iter().take(required).copied().collect(),
+            instructions: message.instructions().iter().map(|i| summarize(keys, i)).collect(),
+            uses_lookup_tables: message.address_table_lookups().is_some_and(|l| !l.is_empty()),
+        }
+    }
+
+    /// Decode the bytes a signer is asked to sign. Anything other than a valid
+    /// transaction message, such as an off-chain message, is `None`.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        decode_message(bytes).map(|message| Self::new(&message))
+    }
+
+    pub fn has_unknown(&self) -> bool {
+        self.instructions.iter().any(|i| matches!(i, InstructionSummary::Unknown { .. }))
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.instructions.iter().map(|i| i.to_string()).collect()
+    }
+}
+
+/// Parse `bytes` as a transaction message, only if they are exactly one
+pub fn decode_message(bytes: &[u8]) -> Option<VersionedMessage> {
+    let message: VersionedMessage = bincode::deserialize(bytes).ok()?;
+    (message.sanitize().is_ok() && message.serialize() == bytes).then_some(message)
+}
+
+/// The variant name from a decoded instruction's debug output
+fn variant_name(instruction: &dyn fmt::Debug) -> String {
+    let debug = format!("{:?}", instruction);
+    debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn summarize(keys: &[Pubkey], instruction: &CompiledInstruction) -> InstructionSummary {
+    let Some(program) = keys.get(instruction.program_id_index as usize).copied() else {
+        // Only an unsanitized message can point past its keys; the program can't be named
+        return InstructionSummary::Unknown {
+            program: Pubkey::default(),
+            accounts: instruction.accounts.len(),
+            data_len: instruction.data.len(),
+        };
+    };
+    let account = |i: usize| match instruction.accounts.get(i) {
+        Some(index) => keys
+            .get(*index as usize)
+            .map(|key| AccountRef::Key(*key))
+            .unwrap_or(AccountRef::Lookup(*index)),
+        None => AccountRef::Missing,
+    };
+    let unknown = InstructionSummary::Unknown {
+        program,
+        accounts: instruction.accounts.len(),
+        data_len: instruction.data.len(),
+    };
+    let data = &instruction.data;
+
+    if program == system_program::id() {
+        let Ok(decoded) = bincode::deserialize::<SystemInstruction>(data) else { return unknown };
+        match decoded {
+            SystemInstruction::Transfer { lamports } => InstructionSummary::SolTransfer {
+                from: account(0),
+                to: account(1),
+                lamports,
+                authority: account(0),
+            },
+            SystemInstruction::TransferWithSeed { lamports, .. } => InstructionSummary::SolTransfer {
+                from: account(0),
+                to: account(2),
+                lamports,
+                authority: account(1),
+            },
+            SystemInstruction::WithdrawNonceAccount(lamports) => InstructionSummary::SolTransfer {
+                from: account(0),
+                to: account(1),
+                lamports,
+                authority: account(4),
+            },
+            SystemInstruction::CreateAccount { lamports, owner, .. }
+            | SystemInstruction::CreateAccountWithSeed { lamports, owner, .. } => InstructionSummary::CreateAccount {
+                funder: account(0),
+                account: account(1),
+                lamports,
+                owner,
+            },
+            SystemInstruction::Assign { owner } | SystemInstruction::AssignWithSeed { owner, .. } => {
+                InstructionSummary::Assign { account: account(0), owner }
+            }
+            SystemInstruction::InitializeNonceAccount(authority) => {
+                InstructionSummary::InitializeNonce { nonce: account(0), authority }
+            }
+            SystemInstruction::AdvanceNonceAccount => InstructionSummary::AdvanceNonce { nonce: account(0) },
+            SystemInstruction::AuthorizeNonceAccount(new_authority) => {
+                InstructionSummary::AuthorizeNonce { nonce: account(0), new_authority }
+            }
+            other => InstructionSummary::System { name: variant_name(&other) },
+        }
+    } else if program == spl_token::id() || program.to_string() == TOKEN_2022_PROGRAM {
+        // Token-2022 `TransferFeeExtension` (26) / `TransferCheckedWithFee` (1):
+        // amount, decimals and the expected fee, with the accounts of `TransferChecked`
+        if let [26, 1, rest @ ..] = data.as_slice() {
+            if rest.len() != 17 || program == spl_token::id() {
+                return unknown;
+            }
+            return InstructionSummary::TokenTransfer {
+                program,
+                source: account(0),
+                destination: account(2),
+                authority: account(3),
+                mint: Some(account(1)),
+                amount: u64::from_le_bytes(rest[..8].try_into().unwrap()),
+                decimals: Some(rest[8]),
+            };
+        }
+        // Other Token-2022 extension instructions aren't understood by the classic decoder and stay unknown
+        let Ok(decoded) = TokenInstruction::unpack(data) else { return unknown };
+        match decoded {
+            TokenInstruction::Transfer { amount } => InstructionSummary::TokenTransfer {
+                program,
+                source: account(0),
+                destination: account(1),
+                authority: account(2),
+                mint: None,
+                amount,
+                decimals: None,
+            },
+            TokenInstruction::TransferChecked { amount, decimals } => InstructionSummary::TokenTransfer {
+                program,
+                source: account(0),
+                destination: account(2),
+                authority: account(3),
+                mint: Some(account(1)),
+                amount,
+                decimals: Some(decimals),
+            },
+            TokenInstruction::Approve { amount } => InstructionSummary::TokenApprove {
+                program,
+                source: account(0),
+                delegate: account(1),
+                amount,
+            },
+            TokenInstruction::ApproveChecked { amount, .. } => InstructionSummary::TokenApprove {
+                program,
+                source: account(0),
+                delegate: account(2),
+                amount,
+            },
+            TokenInstruction::SetAuthority { .. } => {
+                InstructionSummary::TokenSetAuthority { program, account: account(0) }
+            }
+            TokenInstruction::CloseAccount => InstructionSummary::CloseTokenAccount {
+                program,
+                account: account(0),
+                destination: account(1),
+            },
+            other => InstructionSummary::Token { program, name: variant_name(&other) },
+        }
+    } else if program == spl_associated_token_account::id() {
+        // Empty data is the original Create; 1 is CreateIdempotent
+        match data.first() {
+            None | Some(0) | Some(1) => InstructionSummary::CreateTokenAccount {
+                payer: account(0),
+                account: account(1),
+                owner: account(2),
+                mint: account(3),
+            },
+            _ => unknown,
+        }
+    } else if program == solana_sdk::stake::program::id() {
+        let Ok(decoded) = bincode::deserialize::<StakeInstruction>(data) else { return unknown };
+        match decoded {
+            StakeInstruction::Initialize(authorized, _) => InstructionSummary::StakeInitialize {
+                stake_account: account(0),
+                staker: AccountRef::Key(authorized.staker),
+                withdrawer: AccountRef::Key(authorized.withdrawer),
+            },
+            StakeInstruction::InitializeChecked => InstructionSummary::StakeInitialize {
+                stake_account: account(0),
+                staker: account(2),
+                withdrawer: account(3),
+            },
+            StakeInstruction::DelegateStake => InstructionSummary::StakeDelegate {
+                stake_account: account(0),
+                vote_account: account(1),
+            },
+            StakeInstruction::Withdraw(lamports) => InstructionSummary::StakeWithdraw {
+                stake_account: account(0),
+                to: account(1),
+                lamports,
+                authority: account(4),
+            },
+            StakeInstruction::Deactivate => InstructionSummary::StakeDeactivate { stake_account: account(0) },
+            StakeInstruction::Authorize(new_authority, _) => InstructionSummary::StakeAuthorize {
+                stake_account: account(0),
+                new_authority: AccountRef::Key(new_authority),
+            },
+            StakeInstruction::AuthorizeWithSeed(args) => InstructionSummary::StakeAuthorize {
+                stake_account: account(0),
+                new_authority: AccountRef::Key(args.new_authorized_pubkey),
+            },
+            StakeInstruction::AuthorizeChecked(_) | StakeInstruction::AuthorizeCheckedWithSeed(_) => {
+                InstructionSummary::StakeAuthorize {
+                    stake_account: account(0),
+                    new_authority: account(3),
+                }
+            }
+            other => InstructionSummary::Stake { name: variant_name(&other) },
+        }
+    } else if program == compute_budget::id() {
+        // Borsh layout: a one-byte tag then little-endian fields
+        match (data.first(), data.get(1..)) {
+            (Some(2), Some(rest)) if rest.len() == 4 => {
+                InstructionSummary::ComputeUnitLimit(u32::from_le_bytes(rest.try_into().unwrap()))
+            }
+            (Some(3), Some(rest)) if rest.len() == 8 => {
+                InstructionSummary::ComputeUnitPrice(u64::from_le_bytes(rest.try_into().unwrap()))
+            }
+            (Some(1), Some(rest)) if rest.len() == 4 => InstructionSummary::ComputeBudget {
+                name: "RequestHeapFrame".to_string(),
+            },
+            (Some(4), Some(rest)) if rest.len() == 4 => InstructionSummary::ComputeBudget {
+                name: "SetLoadedAccountsDataSizeLimit".to_string(),
+            },
+            _ => unknown,
+        }
+    } else if MEMO_PROGRAMS.contains(&program.to_string().as_str()) {
+        InstructionSummary::Memo(String::from_utf8_lossy(data).into_owned())
+    } else {
+        unknown
+    }
+}
+
+/// Wraps any signer and reports what each transaction does just before it is signed,
+/// so confirmation screens can show it while the signer waits for approval.
+pub struct PreviewSigner<S> {
+    inner: S,
+    on_preview: Box<dyn Fn(Vec<TransactionPreview>) + Send + Sync>,
+    /// When set, nothing is signed until the user answers the preview
+    approvals: Option<async_channel::Receiver<bool>>,
+}
+
+impl<S: TransactionSigner> PreviewSigner<S> {
+    pub fn new(inner: S, on_preview: impl Fn(Vec<TransactionPreview>) + Send + Sync + 'static) -> Self {
+        Self {
+            inner,
+            on_preview: Box::new(on_preview),
+            approvals: None,
+        }
+    }
+
+    /// For signers without a confirmation step of their own: after each preview,
+    /// signing waits for `true` on `approvals` and fails on anything else.
+    pub fn with_approval(
+        inner: S,
+        on_preview: impl Fn(Vec<TransactionPreview>) + Send + Sync + 'static,
+        approvals: async_channel::Receiver<bool>,
+    ) -> Self {
+        Self {
+            approvals: Some(approvals),
+            ..Self::new(inner, on_preview)
+        }
+    }
+
+    async fn report(&self, messages: &[Vec<u8>]) -> Result<(), Box<dyn Error>> {
+        let previews: Vec<TransactionPreview> =
+            messages.iter().filter_map(|m| TransactionPreview::from_bytes(m)).collect();
+        if previews.is_empty() {
+            return Ok(());
+        }
+        (self.on_preview)(previews);
+        let Some(approvals) = &self.approvals else { return Ok(()) };
+        if approvals.recv().await == Ok(true) {
+            Ok(())
+        } else {
+            Err("Transaction was not approved".into())
+        }
+    }
+}
+
+#[async_trait]
+impl<S: TransactionSigner> TransactionSigner for PreviewSigner<S> {
+    async fn get_public_key(&self) -> Result<String, Box<dyn Error>> {
+        self.inner.get_public_key().await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.report(&[message.to_vec()]).await?;
+        self.inner.sign_message(message).await
+    }
+
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        self.report(messages).await?;
+        self.inner.sign_messages(messages).await
+    }
+
+    async fn sign_offchain_message(&self, message: &OffchainMessage) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.inner.sign_offchain_message(message).await
+    }
+
+    fn get_name(&self) -> String {
+        self.inner.get_name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction,
+        hash::Hash,
+        instruction::Instruction,
+        message::{v0::{self, MessageAddressTableLookup}, Message, MessageHeader},
+        stake::{self, state::Authorized},
+        system_instruction,
+    };
+    use crate::signing::SignerType;
+    use crate::wallet::Wallet;
+    use spl_associated_token_account::instruction::create_associated_token_account;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_decodes_known_instructions() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let stake_account = Pubkey::new_unique();
+        let vote_account = Pubkey::new_unique();
+        let source = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+
+        let mut instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            ComputeBudgetInstruction::set_compute_unit_price(5_000),
+            system_instruction::transfer(&payer, &recipient, 1_500_000_000),
+            create_associated_token_account(&payer, &recipient, &mint, &spl_token::id()),
+            spl_token::instruction::transfer_checked(
+                &spl_token::id(), &source, &mint, &destination, &payer, &[], 2_500_000, 6,
+            )
+            .unwrap(),
+            Instruction::new_with_bytes(Pubkey::from_str(MEMO_PROGRAMS[0]).unwrap(), b"invoice 42", vec![]),
+        ];
+        instructions.extend(stake::instruction::create_account_and_delegate_stake(
+            &payer,
+            &stake_account,
+            &vote_account,
+            &Authorized::auto(&payer),
+            &Default::default(),
+            2_000_000_000,
+        ));
+        instructions.push(stake::instruction::withdraw(&stake_account, &payer, &recipient, 1_000, None));
+
+        let message = VersionedMessage::Legacy(Message::new(&instructions, Some(&payer)));
+        let preview = TransactionPreview::new(&message);
+        let summaries = &preview.instructions;
+
+        assert_eq!(preview.fee_payer, payer);
+        assert_eq!(summaries[0], InstructionSummary::ComputeUnitLimit(200_000));
+        assert_eq!(summaries[1], InstructionSummary::ComputeUnitPrice(5_000));
+        assert_eq!(
+            summaries[2],
+            InstructionSummary::SolTransfer {
+                from: AccountRef::Key(payer),
+                to: AccountRef::Key(recipient),
+                lamports: 1_500_000_000,
+                authority: AccountRef::Key(payer),
+            }
+        );
+        assert!(matches!(summaries[3], InstructionSummary::CreateTokenAccount { owner, .. } if owner.is(&recipient)));
+        assert!(matches!(
+            summaries[4],
+            InstructionSummary::TokenTransfer { amount: 2_500_000, decimals: Some(6), mint: Some(m), .. } if m.is(&mint)
+        ));
+        assert_eq!(summaries[5], InstructionSummary::Memo("invoice 42".to_string()));
+        assert!(matches!(summaries[6], InstructionSummary::CreateAccount { lamports: 2_000_000_000, .. }));
+        assert!(matches!(summaries[7], InstructionSummary::StakeInitialize { withdrawer, .. } if withdrawer.is(&payer)));
+        assert!(matches!(summaries[8], InstructionSummary::StakeDelegate { vote_account: v, .. } if v.is(&vote_account)));
+        assert!(matches!(summaries[9], InstructionSummary::StakeWithdraw { lamports: 1_000, .. }));
+        assert!(!preview.has_unknown());
+        assert!(preview.lines()[2].starts_with("Send 1.5 SOL from"));
+        assert!(preview.lines()[4].starts_with("Send 2.5 of token"));
+    }
+
+    #[test]
+    fn test_flags_unknown_and_lookup_table_accounts() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let instructions = [
+            Instruction::new_with_bytes(program, &[1, 2, 3], vec![]),
+            system_instruction::assign(&payer, &program),
+        ];
+        let message = VersionedMessage::Legacy(Message::new(&instructions, Some(&payer)));
+        let preview = TransactionPreview::new(&message);
+        assert!(preview.has_unknown());
+        assert!(preview.instructions.iter().all(|i| i.is_risky()));
+        assert!(preview.lines()[0].starts_with("⚠️ Unknown program"));
+
+        // A recipient from a lookup table is shown by index
+        let transfer = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let message = v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, system_program::id()],
+            recent_blockhash: Hash::new_unique(),
+            instructions: vec![CompiledInstruction::new_from_raw_parts(1, transfer.data, vec![0, 2])],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: Pubkey::new_unique(),
+                writable_indexes: vec![0],
+                readonly_indexes: vec![],
+            }],
+        };
+        let preview = TransactionPreview::new(&VersionedMessage::V0(message));
+        assert!(preview.uses_lookup_tables);
+        assert!(matches!(preview.instructions[0], InstructionSummary::SolTransfer { to: AccountRef::Lookup(2), .. }));
+
+        // Off-chain messages are not transactions
+        assert!(TransactionPreview::from_bytes(b"\xffsolana offchain hello").is_none());
+    }
+
+    #[test]
+    fn test_out_of_range_program_is_unknown() {
+        let payer = Pubkey::new_unique();
+        let message = Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 0,
+            },
+            account_keys: vec![payer],
+            recent_blockhash: Hash::new_unique(),
+            instructions: vec![CompiledInstruction::new_from_raw_parts(7, vec![2, 0, 0, 0], vec![0])],
+        };
+        let preview = TransactionPreview::new(&VersionedMessage::Legacy(message));
+        assert_eq!(
+            preview.instructions,
+            vec![InstructionSummary::Unknown { program: Pubkey::default(), accounts: 1, data_len: 4 }]
+        );
+        assert!(preview.instructions[0].is_risky());
+    }
+
+    #[tokio::test]
+    async fn test_approval_gates_signing() {
+        let wallet = Wallet::new("Review".to_string());
+        let payer = Pubkey::from_str(&wallet.get_public_key()).unwrap();
+        let mut message = Message::new(&[system_instruction::transfer(&payer, &Pubkey::new_unique(), 1)], Some(&payer));
+        message.recent_blockhash = Hash::new_unique();
+        let bytes = VersionedMessage::Legacy(message).serialize();
+
+        let shown = Arc::new(Mutex::new(0));
+        let (approve, approvals) = async_channel::bounded(1);
+        let on_preview = {
+            let shown = shown.clone();
+            move |_: Vec<TransactionPreview>| *shown.lock().unwrap() += 1
+        };
+        let signer = PreviewSigner::with_approval(SignerType::from_wallet(wallet), on_preview, approvals);
+
+        approve.send(false).await.unwrap();
+        assert!(signer.sign_message(&bytes).await.is_err());
+        approve.send(true).await.unwrap();
+        assert_eq!(signer.sign_message(&bytes).await.unwrap().len(), 64);
+        assert_eq!(*shown.lock().unwrap(), 2);
+
+        // Off-chain messages have nothing to preview and don't wait
+        assert_eq!(signer.sign_message(b"\xffsolana offchain hello").await.unwrap().len(), 64);
+    }
+}
@@ -0,0 +1,49 @@
+// src/qr.rs
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Decode the first QR code found in a PNG/JPEG image
+pub fn decode_qr_image(image_bytes: &[u8]) -> Result<String, String> {
+    let image = image::load_from_memory(image_bytes)
+        .map_err(|e| format!("Unsupported image: {}", e))?
+        .to_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare(image);
+    let grids = prepared.detect_grids();
+    let grid = grids.first().ok_or("No QR code found in image")?;
+    let (_meta, content) = grid
+        .decode()
+        .map_err(|e| format!("Failed to decode QR code: {}", e))?;
+
+    Ok(content)
+}
+
+/// Extract a Solana address from scanned text: a bare address or a
+/// `solana:<address>?amount=...` payment URI
+pub fn parse_address_payload(payload: &str) -> Result<String, String> {
+    let payload = payload.trim();
+    let address = payload.strip_prefix("solana:").unwrap_or(payload);
+    let address = address.split(['?', '#']).next().unwrap_or_default();
+
+    Pubkey::from_str(address)
+        .map(|pubkey| pubkey.to_string())
+        .map_err(|_| format!("Not a valid Solana address: {}", address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address_payload() {
+        let address = "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk";
+        assert_eq!(parse_address_payload(address).unwrap(), address);
+        assert_eq!(parse_address_payload(&format!("  {}\n", address)).unwrap(), address);
+        assert_eq!(
+            parse_address_payload(&format!("solana:{}?amount=1.5&label=Treasury", address)).unwrap(),
+            address
+        );
+        assert!(parse_address_payload("solana:not-an-address").is_err());
+        assert!(parse_address_payload("").is_err());
+    }
+}
@@ -4,7 +4,7 @@ use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::error::Error;
 
-const DEFAULT_RPC_URL: &str = "https://serene-stylish-mound.solana-mainnet.quiknode.pro/5489821bcd1547d9cd7b2d81f90c086e36e0e9f7/";
+pub(crate) const DEFAULT_RPC_URL: &str = "https://serene-stylish-mound.solana-mainnet.quiknode.pro/5489821bcd1547d9cd7b2d81f90c086e36e0e9f7/";
 
 #[derive(Debug, Serialize)]
 struct RpcRequest {
@@ -404,17 +404,10 @@ pub async fn get_transaction_history(
     }
 }
 
-/// Gets detailed information about a specific transaction
-pub async fn get_transaction_details(
-    signature: &str,
-    rpc_url: Option<&str>,
-) -> Result<HashMap<String, serde_json::Value>, String> {
-    let client = Client::new();
-    let url = rpc_url.unwrap_or(DEFAULT_RPC_URL);
-    
-    let request = RpcRequest {
+fn get_transaction_request(id: u64, signature: &str) -> RpcRequest {
+    RpcRequest {
         jsonrpc: "2.0".to_string(),
-        id: 1,
+        id,
         method: "getTransaction".to_string(),
         params: vec![
             serde_json::Value::String(signature.to_string()),
@@ -424,7 +417,81 @@ pub async fn get_transaction_details(
                 "maxSupportedTransactionVersion": 0
             }),
         ],
+    }
+}
+
+/// Fetches several parsed transactions in one JSON-RPC batch request.
+/// Each entry is that transaction's `result`, or why it could not be loaded.
+pub async fn get_transactions(
+    signatures: &[String],
+    rpc_url: Option<&str>,
+) -> Result<Vec<Result<serde_json::Value, String>>, String> {
+    if signatures.is_empty() {
+        return Ok(Vec::new());
+    }
+    let client = Client::new();
+    let url = rpc_url.unwrap_or(DEFAULT_RPC_URL);
+
+    let requests: Vec<RpcRequest> = signatures
+        .iter()
+        .enumerate()
+        .map(|(id, signature)| get_transaction_request(id as u64, signature))
+        .collect();
+
+    let response = client
+        .post(url)
+        .header("Content-Type", "application/json")
+        .json(&requests)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("RPC error: {}", response.status()));
+    }
+
+    let json: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    // A batch is answered with an array in any order; a single error object means
+    // the node refused the whole batch
+    let Some(replies) = json.as_array() else {
+        return Err(match json.get("error") {
+            Some(error) => format!("RPC error: {:?}", error),
+            None => "Failed to get transactions from response".to_string(),
+        });
     };
+
+    let mut results: Vec<Result<serde_json::Value, String>> =
+        vec![Err("Missing from the RPC response".to_string()); signatures.len()];
+    for reply in replies {
+        let Some(slot) = reply
+            .get("id")
+            .and_then(|id| id.as_u64())
+            .and_then(|id| results.get_mut(id as usize))
+        else {
+            continue;
+        };
+        *slot = match (reply.get("error"), reply.get("result")) {
+            (Some(error), _) => Err(format!("RPC error: {:?}", error)),
+            (None, Some(result)) if !result.is_null() => Ok(result.clone()),
+            _ => Err("Transaction not found".to_string()),
+        };
+    }
+    Ok(results)
+}
+
+/// Gets detailed information about a specific transaction
+pub async fn get_transaction_details(
+    signature: &str,
+    rpc_url: Option<&str>,
+) -> Result<HashMap<String, serde_json::Value>, String> {
+    let client = Client::new();
+    let url = rpc_url.unwrap_or(DEFAULT_RPC_URL);
+    
+    let request = get_transaction_request(1, signature);
     
     let response = client
         .post(url)
@@ -0,0 +1,302 @@
+// src/schema.rs
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+const VERSION_KEY: &str = "schema_version";
+const DATA_KEY: &str = "data";
+
+/// Upgrades a document's data from one schema version to the next
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// Everything the app persists, in files or web localStorage.
+///
+/// Each document is stored as `{"schema_version": N, "data": ...}`. Files
+/// written before versioning are version 0 and are upgraded on load through
+/// the document's migration chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Document {
+    Wallets,
+    WatchOnly,
+    JitoSettings,
+    Rpc,
+    Currency,
+    Background,
+    Contacts,
+    TransactionNotes,
+    SigningPolicy,
+    PolicyLog,
+}
+
+impl Document {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Document::Wallets => "wallets",
+            Document::WatchOnly => "watch-only wallets",
+            Document::JitoSettings => "Jito settings",
+            Document::Rpc => "RPC URL",
+            Document::Currency => "currency",
+            Document::Background => "background",
+            Document::Contacts => "contacts",
+            Document::TransactionNotes => "transaction notes",
+            Document::SigningPolicy => "signing policy",
+            Document::PolicyLog => "signing policy log",
+        }
+    }
+
+    /// Migration `i` upgrades version `i` to `i + 1`. Append to these lists
+    /// whenever a persisted type changes shape; never edit an existing entry.
+    fn migrations(&self) -> &'static [Migration] {
+        match self {
+            Document::Wallets | Document::WatchOnly => &[legacy_wallet_list],
+            Document::JitoSettings => &[legacy_jito_settings],
+            Document::Rpc | Document::Currency | Document::Background => &[legacy_plain_text],
+            // Introduced after versioning, so there is no legacy format to upgrade
+            Document::Contacts
+            | Document::TransactionNotes
+            | Document::SigningPolicy
+            | Document::PolicyLog => &[],
+        }
+    }
+
+    pub fn current_version(&self) -> u32 {
+        self.migrations().len() as u32
+    }
+
+    /// Documents whose data is a single string (rpc and currency were plain text files before versioning)
+    fn is_text(&self) -> bool {
+        matches!(self, Document::Rpc | Document::Currency | Document::Background)
+    }
+}
+
+/// v0 -> v1: a bare JSON array of `WalletInfo`
+fn legacy_wallet_list(data: Value) -> Result<Value, String> {
+    match data {
+        Value::Array(_) => Ok(data),
+        other => Err(format!("expected a list of wallets, found {}", other)),
+    }
+}
+
+/// v0 -> v1: a bare `JitoSettings` object
+fn legacy_jito_settings(data: Value) -> Result<Value, String> {
+    match data {
+        Value::Object(_) => Ok(data),
+        other => Err(format!("expected an object, found {}", other)),
+    }
+}
+
+/// v0 -> v1: the raw file contents, surrounding whitespace trimmed
+fn legacy_plain_text(data: Value) -> Result<Value, String> {
+    match data {
+        Value::String(text) => Ok(Value::String(text.trim().to_string())),
+        other => Err(format!("expected text, found {}", other)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    /// Written by a newer version of the app; left untouched
+    Newer { document: Document, found: u32, supported: u32 },
+    Migration { document: Document, from: u32, reason: String },
+    Invalid { document: Document, reason: String },
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SchemaError::Newer { document, found, supported } => write!(
+                f,
+                "Stored {} use schema version {} but this app only understands up to {}. Please update the app.",
+                document.label(), found, supported
+            ),
+            SchemaError::Migration { document, from, reason } => write!(
+                f,
+                "Failed to upgrade stored {} from schema version {}: {}",
+                document.label(), from, reason
+            ),
+            SchemaError::Invalid { document, reason } => {
+                write!(f, "Stored {} are unreadable: {}", document.label(), reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+impl From<SchemaError> for String {
+    fn from(e: SchemaError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Split raw contents into (version, data). Anything without an envelope is version 0.
+fn read_envelope(document: Document, raw: &str) -> Result<(u32, Value), SchemaError> {
+    let parsed = match serde_json::from_str::<Value>(raw) {
+        Ok(value) => value,
+        Err(_) if document.is_text() => return Ok((0, Value::String(raw.to_string()))),
+        Err(e) => {
+            return Err(SchemaError::Invalid {
+                document,
+                reason: e.to_string(),
+            })
+        }
+    };
+
+    match parsed {
+        Value::Object(mut map) if map.contains_key(VERSION_KEY) => {
+            let version = map
+                .get(VERSION_KEY)
+                .and_then(Value::as_u64)
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| SchemaError::Invalid {
+                    document,
+                    reason: format!("{} is not a version number", VERSION_KEY),
+                })?;
+            let data = map.remove(DATA_KEY).unwrap_or(Value::Null);
+            Ok((version, data))
+        }
+        // A legacy text file that happens to parse as JSON, e.g. a bare number
+        _ if document.is_text() => Ok((0, Value::String(raw.to_string()))),
+        other => Ok((0, other)),
+    }
+}
+
+fn apply_migrations(
+    document: Document,
+    version: u32,
+    mut data: Value,
+    migrations: &[Migration],
+) -> Result<Value, SchemaError> {
+    let supported = migrations.len() as u32;
+    if version > supported {
+        return Err(SchemaError::Newer {
+            document,
+            found: version,
+            supported,
+        });
+    }
+
+    for (from, migrate) in migrations.iter().enumerate().skip(version as usize) {
+        data = migrate(data).map_err(|reason| SchemaError::Migration {
+            document,
+            from: from as u32,
+            reason,
+        })?;
+    }
+    if version < supported {
+        log::info!(
+            "⬆️ Upgraded stored {} from schema version {} to {}",
+            document.label(),
+            version,
+            supported
+        );
+    }
+    Ok(data)
+}
+
+/// Parse stored contents of any known version into the current type
+pub fn decode<T: DeserializeOwned>(document: Document, raw: &str) -> Result<T, SchemaError> {
+    let (version, data) = read_envelope(document, raw)?;
+    let data = apply_migrations(document, version, data, document.migrations())?;
+    serde_json::from_value(data).map_err(|e| SchemaError::Invalid {
+        document,
+        reason: e.to_string(),
+    })
+}
+
+/// Serialize `value` at the current schema version
+pub fn encode<T: Serialize>(document: Document, value: &T) -> Result<String, SchemaError> {
+    let envelope = json!({
+        VERSION_KEY: document.current_version(),
+        DATA_KEY: value,
+    });
+    serde_json::to_string_pretty(&envelope).map_err(|e| SchemaError::Invalid {
+        document,
+        reason: e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::JitoSettings;
+    use crate::wallet::{SeedSource, WalletInfo};
+
+    #[test]
+    fn test_upgrade_legacy_wallets() {
+        // wallets.json as written before versioning, including a pre-keystore entry
+        let legacy = r#"[
+            {"name":"Main","address":"addr1","encrypted_key":"key1"},
+            {"name":"Phrase","address":"addr2","encrypted_key":"","seed_source":{"type":"mnemonic","derivation_path":"m/44'/501'/0'/0'"},"account_index":0}
+        ]"#;
+        let wallets: Vec<WalletInfo> = decode(Document::Wallets, legacy).unwrap();
+        assert_eq!(wallets.len(), 2);
+        assert_eq!(wallets[0].encrypted_key, "key1");
+        assert_eq!(wallets[0].seed_source, SeedSource::PrivateKey);
+        assert_eq!(wallets[1].account_index, Some(0));
+
+        // Re-encoding produces an envelope that reads back unchanged
+        let encoded = encode(Document::Wallets, &wallets).unwrap();
+        assert!(encoded.contains("\"schema_version\": 1"));
+        assert_eq!(decode::<Vec<WalletInfo>>(Document::Wallets, &encoded).unwrap(), wallets);
+    }
+
+    #[test]
+    fn test_upgrade_legacy_jito_settings() {
+        let settings: JitoSettings =
+            decode(Document::JitoSettings, r#"{"jito_tx":false,"jito_bundles":true}"#).unwrap();
+        assert_eq!(settings, JitoSettings { jito_tx: false, jito_bundles: true });
+
+        // A missing field takes its default instead of resetting the whole file
+        let partial: JitoSettings = decode(Document::JitoSettings, r#"{"jito_bundles":true}"#).unwrap();
+        assert_eq!(partial, JitoSettings { jito_tx: true, jito_bundles: true });
+    }
+
+    #[test]
+    fn test_upgrade_legacy_text_files() {
+        let rpc: String = decode(Document::Rpc, "https://api.mainnet-beta.solana.com\n").unwrap();
+        assert_eq!(rpc, "https://api.mainnet-beta.solana.com");
+        let currency: String = decode(Document::Currency, "EUR").unwrap();
+        assert_eq!(currency, "EUR");
+
+        let encoded = encode(Document::Currency, &currency).unwrap();
+        assert_eq!(decode::<String>(Document::Currency, &encoded).unwrap(), "EUR");
+    }
+
+    #[test]
+    fn test_newer_and_invalid_rejected() {
+        let future = r#"{"schema_version": 99, "data": []}"#;
+        assert!(matches!(
+            decode::<Vec<WalletInfo>>(Document::Wallets, future),
+            Err(SchemaError::Newer { found: 99, .. })
+        ));
+        assert!(matches!(
+            decode::<Vec<WalletInfo>>(Document::Wallets, "not json"),
+            Err(SchemaError::Invalid { .. })
+        ));
+        assert!(matches!(
+            decode::<Vec<WalletInfo>>(Document::Wallets, r#"{"name":"x"}"#),
+            Err(SchemaError::Migration { from: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_migration_chain_runs_in_order() {
+        fn add_field(mut data: Value) -> Result<Value, String> {
+            data["added"] = json!(true);
+            Ok(data)
+        }
+        fn rename_field(mut data: Value) -> Result<Value, String> {
+            let old = data["old"].take();
+            data["new"] = old;
+            Ok(data)
+        }
+        let chain: &[Migration] = &[legacy_jito_settings, add_field, rename_field];
+
+        let upgraded = apply_migrations(Document::JitoSettings, 0, json!({"old": 1}), chain).unwrap();
+        assert_eq!(upgraded, json!({"old": null, "new": 1, "added": true}));
+
+        // Starting part way only runs the remaining steps
+        let partial = apply_migrations(Document::JitoSettings, 2, json!({"old": 1}), chain).unwrap();
+        assert_eq!(partial, json!({"old": null, "new": 1}));
+    }
+}
@@ -0,0 +1,543 @@
+// src/shamir.rs
+use crate::derivation::{self, DerivationPath};
+use crate::wallet::{SeedSource, Wallet};
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use zeroize::Zeroizing;
+
+const SHARE_PREFIX: &str = "unruggable-share:";
+/// Shares of a private key made before the secret kind and derivation path were recorded
+const LEGACY_SHARE_VERSION: u8 = 1;
+const SHARE_VERSION: u8 = 2;
+const CHECKSUM_LEN: usize = 4;
+/// version, group id (2), threshold, index
+const HEADER_LEN: usize = 5;
+const PUBKEY_LEN: usize = 32;
+const SECRET_LEN: usize = 32;
+const LEGACY_SHARE_LEN: usize = HEADER_LEN + PUBKEY_LEN + SECRET_LEN + CHECKSUM_LEN;
+/// BIP39 entropy sizes, from 12 to 24 words
+const ENTROPY_LENS: [usize; 5] = [16, 20, 24, 28, 32];
+
+/// Multiplication in GF(2^8) with the AES polynomial x^8 + x^4 + x^3 + x + 1.
+/// Loops a fixed 8 times with no secret-dependent branches.
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = a >> 7;
+        a = (a << 1) ^ (0x1b & 0u8.wrapping_sub(carry));
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplicative inverse, a^254 (a must be non-zero)
+fn gf_inv(a: u8) -> u8 {
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exponent = 254u8;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exponent >>= 1;
+    }
+    result
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShamirError {
+    /// Threshold or share count out of range
+    InvalidParameters(String),
+    /// A share's text could not be decoded or failed its checksum
+    InvalidShare(String),
+    /// Shares come from different splits
+    Mismatched,
+    NotEnoughShares { needed: u8, given: usize },
+    /// The recombined key does not belong to the wallet the shares were made from
+    WrongKey,
+    /// The recovery phrase to split is invalid or does not belong to the wallet
+    InvalidPhrase(String),
+}
+
+impl fmt::Display for ShamirError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShamirError::InvalidParameters(reason) => write!(f, "{}", reason),
+            ShamirError::InvalidShare(reason) => write!(f, "Invalid share: {}", reason),
+            ShamirError::Mismatched => write!(f, "These shares belong to different backups"),
+            ShamirError::NotEnoughShares { needed, given } => {
+                write!(f, "{} shares are needed but only {} were given", needed, given)
+            }
+            ShamirError::WrongKey => write!(f, "Recombined key does not match the wallet address"),
+            ShamirError::InvalidPhrase(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ShamirError {}
+
+impl From<ShamirError> for String {
+    fn from(e: ShamirError) -> Self {
+        e.to_string()
+    }
+}
+
+/// Split `secret` into `count` shares, any `threshold` of which rebuild it.
+/// Returns (x, share bytes) pairs with x in 1..=count.
+fn split_secret(secret: &[u8], threshold: u8, count: u8) -> Vec<(u8, Zeroizing<Vec<u8>>)> {
+    let mut shares: Vec<(u8, Zeroizing<Vec<u8>>)> = (1..=count)
+        .map(|x| (x, Zeroizing::new(Vec::with_capacity(secret.len()))))
+        .collect();
+    let mut coefficients = Zeroizing::new(vec![0u8; threshold as usize]);
+
+    for &byte in secret {
+        // A fresh random polynomial of degree threshold - 1 per byte, with the secret byte at x = 0
+        coefficients[0] = byte;
+        OsRng.fill_bytes(&mut coefficients[1..]);
+        for (x, share) in shares.iter_mut() {
+            // Horner's rule
+            let y = coefficients.iter().rev().fold(0u8, |acc, &c| gf_mul(acc, *x) ^ c);
+            share.push(y);
+        }
+    }
+    shares
+}
+
+/// Lagrange interpolation at x = 0. Points must have distinct non-zero x.
+fn combine_secret(points: &[(u8, &[u8])]) -> Zeroizing<Vec<u8>> {
+    let len = points[0].1.len();
+    let mut secret = Zeroizing::new(vec![0u8; len]);
+    for (i, (xi, yi)) in points.iter().enumerate() {
+        let mut basis = 1u8;
+        for (j, (xj, _)) in points.iter().enumerate() {
+            if i != j {
+                // (0 - xj) / (xi - xj); subtraction is xor in GF(2^8)
+                basis = gf_mul(basis, gf_mul(*xj, gf_inv(xi ^ xj)));
+            }
+        }
+        for (out, &y) in secret.iter_mut().zip(yi.iter()) {
+            *out ^= gf_mul(basis, y);
+        }
+    }
+    secret
+}
+
+fn checksum(bytes: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let digest = Sha256::digest(bytes);
+    let mut out = [0u8; CHECKSUM_LEN];
+    out.copy_from_slice(&digest[..CHECKSUM_LEN]);
+    out
+}
+
+/// What a set of shares rebuilds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareKind {
+    /// The wallet's 32-byte private key
+    PrivateKey,
+    /// The BIP39 entropy behind the wallet's recovery phrase, which also restores
+    /// every other account derived from that phrase
+    SeedEntropy,
+}
+
+impl ShareKind {
+    fn code(self) -> u8 {
+        match self {
+            ShareKind::PrivateKey => 0,
+            ShareKind::SeedEntropy => 1,
+        }
+    }
+
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(ShareKind::PrivateKey),
+            1 => Some(ShareKind::SeedEntropy),
+            _ => None,
+        }
+    }
+
+    fn secret_len_ok(self, len: usize) -> bool {
+        match self {
+            ShareKind::PrivateKey => len == SECRET_LEN,
+            ShareKind::SeedEntropy => ENTROPY_LENS.contains(&len),
+        }
+    }
+}
+
+/// One share of a wallet's private key or recovery phrase.
+///
+/// Text form is `unruggable-share:` followed by base58 of
+/// version | group id | threshold | index | kind | path length | derivation path |
+/// wallet public key | share | checksum.
+/// The public key lets recombination prove it rebuilt the right key; the derivation
+/// path says which account of a recovery phrase the wallet is.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyShare {
+    /// Random id shared by every share of one split
+    pub group_id: u16,
+    pub threshold: u8,
+    /// 1-based share number
+    pub index: u8,
+    pub kind: ShareKind,
+    /// Path the wallet was derived along, for wallets made from a recovery phrase
+    pub derivation_path: Option<String>,
+    pub address: String,
+    data: Zeroizing<Vec<u8>>,
+}
+
+impl fmt::Debug for KeyShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("group_id", &self.group_id)
+            .field("threshold", &self.threshold)
+            .field("index", &self.index)
+            .field("kind", &self.kind)
+            .field("derivation_path", &self.derivation_path)
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+fn truncated() -> ShamirError {
+    ShamirError::InvalidShare("wrong length, check for missing characters".to_string())
+}
+
+impl KeyShare {
+    pub fn to_text(&self) -> Zeroizing<String> {
+        let pubkey = bs58::decode(&self.address).into_vec().unwrap_or_default();
+        let path = self.derivation_path.as_deref().unwrap_or_default().as_bytes();
+        let mut bytes = Zeroizing::new(Vec::with_capacity(
+            HEADER_LEN + 2 + path.len() + PUBKEY_LEN + self.data.len() + CHECKSUM_LEN,
+        ));
+        bytes.push(SHARE_VERSION);
+        bytes.extend_from_slice(&self.group_id.to_be_bytes());
+        bytes.push(self.threshold);
+        bytes.push(self.index);
+        bytes.push(self.kind.code());
+        bytes.push(path.len() as u8);
+        bytes.extend_from_slice(path);
+        bytes.extend_from_slice(&pubkey);
+        bytes.extend_from_slice(&self.data);
+        let sum = checksum(&bytes);
+        bytes.extend_from_slice(&sum);
+        Zeroizing::new(format!("{}{}", SHARE_PREFIX, bs58::encode(bytes.as_slice()).into_string()))
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, ShamirError> {
+        let encoded = text
+            .trim()
+            .strip_prefix(SHARE_PREFIX)
+            .ok_or_else(|| ShamirError::InvalidShare(format!("expected it to start with {}", SHARE_PREFIX)))?;
+        let bytes = Zeroizing::new(
+            bs58::decode(encoded)
+                .into_vec()
+                .map_err(|e| ShamirError::InvalidShare(e.to_string()))?,
+        );
+        if bytes.len() < HEADER_LEN + PUBKEY_LEN + CHECKSUM_LEN {
+            return Err(truncated());
+        }
+        let (body, sum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+        if checksum(body) != sum {
+            return Err(ShamirError::InvalidShare("checksum mismatch, check for typos".to_string()));
+        }
+
+        let (kind, derivation_path, rest) = match body[0] {
+            LEGACY_SHARE_VERSION if bytes.len() == LEGACY_SHARE_LEN => (ShareKind::PrivateKey, None, &body[HEADER_LEN..]),
+            LEGACY_SHARE_VERSION => return Err(truncated()),
+            SHARE_VERSION => {
+                let (&kind, rest) = body[HEADER_LEN..].split_first().ok_or_else(truncated)?;
+                let (&path_len, rest) = rest.split_first().ok_or_else(truncated)?;
+                if rest.len() < path_len as usize {
+                    return Err(truncated());
+                }
+                let (path, rest) = rest.split_at(path_len as usize);
+
+                let kind = ShareKind::from_code(kind)
+                    .ok_or_else(|| ShamirError::InvalidShare(format!("unknown secret kind {}", kind)))?;
+                let path = match path {
+                    [] => None,
+                    path => {
+                        let path = std::str::from_utf8(path)
+                            .map_err(|_| ShamirError::InvalidShare("unreadable derivation path".to_string()))?;
+                        DerivationPath::parse(path).map_err(ShamirError::InvalidShare)?;
+                        Some(path.to_string())
+                    }
+                };
+                (kind, path, rest)
+            }
+            version => return Err(ShamirError::InvalidShare(format!("unsupported version {}", version))),
+        };
+        if rest.len() < PUBKEY_LEN || !kind.secret_len_ok(rest.len() - PUBKEY_LEN) {
+            return Err(truncated());
+        }
+        if kind == ShareKind::SeedEntropy && derivation_path.is_none() {
+            return Err(ShamirError::InvalidShare("recovery phrase share without a derivation path".to_string()));
+        }
+
+        let (threshold, index) = (body[3], body[4]);
+        if index == 0 || threshold == 0 {
+            return Err(ShamirError::InvalidShare("bad share number".to_string()));
+        }
+        Ok(Self {
+            group_id: u16::from_be_bytes([body[1], body[2]]),
+            threshold,
+            index,
+            kind,
+            derivation_path,
+            address: bs58::encode(&rest[..PUBKEY_LEN]).into_string(),
+            data: Zeroizing::new(rest[PUBKEY_LEN..].to_vec()),
+        })
+    }
+}
+
+fn check_parameters(threshold: u8, count: u8) -> Result<(), ShamirError> {
+    if threshold < 2 {
+        return Err(ShamirError::InvalidParameters("Threshold must be at least 2".to_string()));
+    }
+    if count < threshold {
+        return Err(ShamirError::InvalidParameters(
+            "Number of shares must be at least the threshold".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The path a wallet made from a recovery phrase was derived along
+fn derivation_path(wallet: &Wallet) -> Option<String> {
+    match &wallet.seed_source {
+        SeedSource::Mnemonic { derivation_path } => Some(derivation_path.clone()),
+        _ => None,
+    }
+}
+
+fn make_shares(
+    wallet: &Wallet,
+    kind: ShareKind,
+    secret: &[u8],
+    threshold: u8,
+    count: u8,
+) -> Result<Vec<KeyShare>, ShamirError> {
+    check_parameters(threshold, count)?;
+    let derivation_path = derivation_path(wallet);
+    if derivation_path.as_ref().is_some_and(|path| path.len() > u8::MAX as usize) {
+        return Err(ShamirError::InvalidParameters("Derivation path is too long to store in a share".to_string()));
+    }
+
+    let mut group_id = [0u8; 2];
+    OsRng.fill_bytes(&mut group_id);
+    let address = wallet.get_public_key();
+
+    log::info!("🧩 Splitting {} into {} shares (threshold {}, {:?})", address, count, threshold, kind);
+    Ok(split_secret(secret, threshold, count)
+        .into_iter()
+        .map(|(index, data)| KeyShare {
+            group_id: u16::from_be_bytes(group_id),
+            threshold,
+            index,
+            kind,
+            derivation_path: derivation_path.clone(),
+            address: address.clone(),
+            data,
+        })
+        .collect())
+}
+
+/// Split a wallet's private key into `count` shares, any `threshold` of which recover it
+pub fn split_wallet(wallet: &Wallet, threshold: u8, count: u8) -> Result<Vec<KeyShare>, ShamirError> {
+    make_shares(wallet, ShareKind::PrivateKey, wallet.secret_key_bytes().as_slice(), threshold, count)
+}
+
+/// Split the recovery phrase behind a wallet instead of its key, so the shares also
+/// restore the phrase's other accounts. The app doesn't keep phrases, so the user
+/// enters it again; it must derive this wallet with `passphrase` along the wallet's path.
+pub fn split_recovery_phrase(
+    wallet: &Wallet,
+    phrase: &str,
+    passphrase: &str,
+    threshold: u8,
+    count: u8,
+) -> Result<Vec<KeyShare>, ShamirError> {
+    let path = derivation_path(wallet)
+        .ok_or_else(|| ShamirError::InvalidPhrase("This wallet was not made from a recovery phrase".to_string()))?;
+    let path = DerivationPath::parse(&path).map_err(ShamirError::InvalidPhrase)?;
+    let seed = derivation::mnemonic_to_seed(phrase, passphrase).map_err(ShamirError::InvalidPhrase)?;
+    let derived = Wallet::from_seed(&seed[..], &path, None, wallet.name.clone());
+    if derived.get_public_key() != wallet.get_public_key() {
+        return Err(ShamirError::InvalidPhrase(format!(
+            "This recovery phrase and passphrase do not derive {} at {}",
+            wallet.get_public_key(),
+            path
+        )));
+    }
+
+    let entropy = derivation::mnemonic_entropy(phrase).map_err(ShamirError::InvalidPhrase)?;
+    make_shares(wallet, ShareKind::SeedEntropy, &entropy, threshold, count)
+}
+
+/// Rebuild the wallet from at least `threshold` shares of one split.
+/// `passphrase` is the recovery phrase's BIP39 passphrase, if it had one; key shares ignore it.
+pub fn recover_wallet(shares: &[KeyShare], passphrase: &str, name: String) -> Result<Wallet, ShamirError> {
+    let first = shares.first().ok_or(ShamirError::NotEnoughShares { needed: 2, given: 0 })?;
+    if shares.iter().any(|s| {
+        s.group_id != first.group_id
+            || s.threshold != first.threshold
+            || s.kind != first.kind
+            || s.derivation_path != first.derivation_path
+            || s.address != first.address
+    }) {
+        return Err(ShamirError::Mismatched);
+    }
+
+    // The same share entered twice only counts once
+    let mut points: Vec<(u8, &[u8])> = Vec::new();
+    for share in shares {
+        if !points.iter().any(|(x, _)| *x == share.index) {
+            points.push((share.index, share.data.as_slice()));
+        }
+    }
+    if points.len() < first.threshold as usize {
+        return Err(ShamirError::NotEnoughShares {
+            needed: first.threshold,
+            given: points.len(),
+        });
+    }
+    points.truncate(first.threshold as usize);
+
+    let secret = combine_secret(&points);
+    let path = first
+        .derivation_path
+        .as_deref()
+        .map(DerivationPath::parse)
+        .transpose()
+        .map_err(ShamirError::InvalidShare)?;
+    let wallet = match (first.kind, path) {
+        (ShareKind::SeedEntropy, Some(path)) => {
+            let seed = derivation::entropy_to_seed(&secret, passphrase).map_err(ShamirError::InvalidShare)?;
+            Wallet::from_seed(&seed[..], &path, path.solana_account_index(), name)
+        }
+        (ShareKind::SeedEntropy, None) => {
+            return Err(ShamirError::InvalidShare("recovery phrase share without a derivation path".to_string()))
+        }
+        (ShareKind::PrivateKey, path) => {
+            let mut wallet = Wallet::from_private_key(&secret, name).map_err(|_| ShamirError::WrongKey)?;
+            if let Some(path) = path {
+                wallet.account_index = path.solana_account_index();
+                wallet.seed_source = SeedSource::Mnemonic { derivation_path: path.to_string() };
+            }
+            wallet
+        }
+    };
+    if wallet.get_public_key() != first.address {
+        return Err(ShamirError::WrongKey);
+    }
+    log::info!("✅ Recovered {} from {} shares", first.address, points.len());
+    Ok(wallet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_arithmetic() {
+        assert_eq!(gf_mul(0x57, 0x83), 0xc1);
+        for a in 1..=255u8 {
+            assert_eq!(gf_mul(a, gf_inv(a)), 1);
+        }
+    }
+
+    #[test]
+    fn test_any_threshold_subset_recovers() {
+        let wallet = Wallet::new("Treasury".to_string());
+        let shares = split_wallet(&wallet, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        for subset in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let chosen: Vec<KeyShare> = subset.iter().map(|&i| shares[i].clone()).collect();
+            let recovered = recover_wallet(&chosen, "", "Treasury".to_string()).unwrap();
+            assert_eq!(recovered.get_public_key(), wallet.get_public_key());
+        }
+
+        // Below the threshold, even with a duplicate
+        let too_few = vec![shares[0].clone(), shares[1].clone(), shares[1].clone()];
+        assert_eq!(
+            recover_wallet(&too_few, "", "x".to_string()).err(),
+            Some(ShamirError::NotEnoughShares { needed: 3, given: 2 })
+        );
+    }
+
+    #[test]
+    fn test_share_text_round_trip() {
+        let wallet = Wallet::new("Treasury".to_string());
+        let shares = split_wallet(&wallet, 2, 3).unwrap();
+        let text = shares[1].to_text();
+        assert!(text.starts_with(SHARE_PREFIX));
+
+        let parsed = KeyShare::from_text(&format!("  {}\n", text.as_str())).unwrap();
+        assert_eq!(parsed, shares[1]);
+        assert_eq!(parsed.address, wallet.get_public_key());
+
+        // A single changed character fails the checksum
+        let mut corrupted = text.to_string();
+        let last = corrupted.pop().unwrap();
+        corrupted.push(if last == '2' { '3' } else { '2' });
+        assert!(KeyShare::from_text(&corrupted).is_err());
+        assert!(KeyShare::from_text("hello").is_err());
+
+        // Shares from before the kind and path were stored still parse as key shares
+        let mut legacy = vec![LEGACY_SHARE_VERSION, 0x12, 0x34, 2, 1];
+        legacy.extend_from_slice(&bs58::decode(wallet.get_public_key()).into_vec().unwrap());
+        legacy.extend_from_slice(&[9u8; SECRET_LEN]);
+        let sum = checksum(&legacy);
+        legacy.extend_from_slice(&sum);
+        let parsed = KeyShare::from_text(&format!("{}{}", SHARE_PREFIX, bs58::encode(&legacy).into_string())).unwrap();
+        assert_eq!((parsed.kind, parsed.derivation_path, parsed.group_id), (ShareKind::PrivateKey, None, 0x1234));
+    }
+
+    #[test]
+    fn test_recovery_phrase_shares() {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let wallet = Wallet::from_mnemonic(phrase, "pass", 2, "Savings".to_string()).unwrap();
+
+        // The phrase has to belong to the wallet
+        assert!(matches!(
+            split_recovery_phrase(&wallet, phrase, "", 2, 3),
+            Err(ShamirError::InvalidPhrase(_))
+        ));
+        assert!(split_recovery_phrase(&Wallet::new("x".to_string()), phrase, "pass", 2, 3).is_err());
+
+        let shares = split_recovery_phrase(&wallet, phrase, "pass", 2, 3).unwrap();
+        let parsed: Vec<KeyShare> = shares.iter().map(|s| KeyShare::from_text(&s.to_text()).unwrap()).collect();
+        assert_eq!(parsed, shares);
+        assert_eq!(parsed[0].kind, ShareKind::SeedEntropy);
+        assert_eq!(parsed[0].derivation_path.as_deref(), Some("m/44'/501'/2'/0'"));
+
+        let recovered = recover_wallet(&parsed[1..], "pass", "Savings".to_string()).unwrap();
+        assert_eq!(recovered.get_public_key(), wallet.get_public_key());
+        assert_eq!(recovered.seed_source, wallet.seed_source);
+        assert_eq!(recovered.account_index, Some(2));
+        // Without the phrase's passphrase the seed derives a different wallet
+        assert_eq!(recover_wallet(&parsed[1..], "", "x".to_string()).err(), Some(ShamirError::WrongKey));
+
+        // Key shares of the same wallet keep its derivation path too
+        let key_shares = split_wallet(&wallet, 2, 2).unwrap();
+        assert_eq!(key_shares[0].derivation_path.as_deref(), Some("m/44'/501'/2'/0'"));
+        let recovered = recover_wallet(&key_shares, "", "Savings".to_string()).unwrap();
+        assert_eq!(recovered.seed_source, wallet.seed_source);
+    }
+
+    #[test]
+    fn test_mixed_splits_rejected() {
+        let wallet = Wallet::new("Treasury".to_string());
+        let first = split_wallet(&wallet, 2, 3).unwrap();
+        let second = split_wallet(&Wallet::new("Other".to_string()), 2, 3).unwrap();
+        assert_eq!(
+            recover_wallet(&[first[0].clone(), second[1].clone()], "", "x".to_string()).err(),
+            Some(ShamirError::Mismatched)
+        );
+        assert!(split_wallet(&wallet, 1, 3).is_err());
+        assert!(split_wallet(&wallet, 4, 3).is_err());
+    }
+}
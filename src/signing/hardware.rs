@@ -43,6 +43,16 @@ impl TransactionSigner for HardwareSigner {
         
         Ok(signature)
     }
+
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        // One button press on the device covers the whole ordered batch
+        let signatures = self.wallet.sign_messages(messages).await?;
+
+        if let Some(bad) = signatures.iter().find(|s| s.len() != 64) {
+            return Err(format!("Invalid signature length: expected 64, got {}", bad.len()).into());
+        }
+        Ok(signatures)
+    }
     
     fn get_name(&self) -> String {
         "Hardware Wallet".to_string()
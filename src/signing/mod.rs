@@ -1,13 +1,20 @@
-// src/signing/mod.rs - Fixed to remove MWA references
-use crate::wallet::Wallet;
+// src/signing/mod.rs
+use crate::wallet::{Wallet, WalletInfo};
 use std::error::Error;
 use async_trait::async_trait;
 
 pub mod software;
 pub mod hardware;
+pub mod mwa;
+pub mod offchain;
+pub mod partial;
+pub mod policy;
+pub mod siws;
 
 use software::SoftwareSigner;
 use hardware::HardwareSigner;
+use mwa::MwaSigner;
+use offchain::OffchainMessage;
 
 /// Trait for different transaction signing methods
 #[async_trait]
@@ -17,6 +24,22 @@ pub trait TransactionSigner: Send + Sync {
     
     /// Sign a message/transaction
     async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>>;
+
+    /// Sign several messages, in order, with as few user approvals as the signer allows.
+    /// The default asks for each one separately.
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        let mut signatures = Vec::with_capacity(messages.len());
+        for message in messages {
+            signatures.push(self.sign_message(message).await?);
+        }
+        Ok(signatures)
+    }
+
+    /// Sign a human-readable message in the off-chain message format.
+    /// Use this instead of `sign_message` for anything that is not a transaction.
+    async fn sign_offchain_message(&self, message: &OffchainMessage) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.sign_message(&message.serialize()).await
+    }
     
     /// Get a display name for the signing method
     fn get_name(&self) -> String;
@@ -30,7 +53,8 @@ pub trait TransactionSigner: Send + Sync {
 pub enum SignerType {
     Software(SoftwareSigner),
     Hardware(HardwareSigner),
-    // Note: MWA signing will be handled directly via FFI calls, not through this trait system
+    /// Another wallet app on the device, over Mobile Wallet Adapter
+    Mwa(MwaSigner),
 }
 
 impl SignerType {
@@ -39,6 +63,29 @@ impl SignerType {
         SignerType::Software(SoftwareSigner::new(wallet))
     }
     
+    /// Create a signer from stored wallet info: Mobile Wallet Adapter for
+    /// wallets connected that way, software otherwise.
+    /// Watch-only wallets are refused because they hold no key.
+    pub fn from_wallet_info(info: &WalletInfo) -> Result<Self, String> {
+        if info.is_mobile_wallet_adapter() {
+            return Self::mwa(&info.address);
+        }
+        Ok(Self::from_wallet(Wallet::from_wallet_info(info)?))
+    }
+
+    /// Create a signer for a wallet connected through Mobile Wallet Adapter
+    pub fn mwa(address: &str) -> Result<Self, String> {
+        #[cfg(target_os = "android")]
+        {
+            Ok(SignerType::Mwa(MwaSigner::new(mwa::android_bridge(), Some(address.to_string()))))
+        }
+        #[cfg(not(target_os = "android"))]
+        {
+            let _ = address;
+            Err("Mobile Wallet Adapter is only available on Android".to_string())
+        }
+    }
+    
     /// Create a hardware signer (attempts to connect)
     pub async fn hardware() -> Result<Self, Box<dyn Error>> {
         let signer = HardwareSigner::new().await?;
@@ -50,6 +97,7 @@ impl SignerType {
         match self {
             SignerType::Software(_) => "Software Wallet",
             SignerType::Hardware(_) => "Hardware Wallet",
+            SignerType::Mwa(_) => "Mobile Wallet Adapter",
         }
     }
 }
@@ -60,6 +108,7 @@ impl TransactionSigner for SignerType {
         match self {
             SignerType::Software(s) => s.get_public_key().await,
             SignerType::Hardware(h) => h.get_public_key().await,
+            SignerType::Mwa(m) => m.get_public_key().await,
         }
     }
     
@@ -67,6 +116,15 @@ impl TransactionSigner for SignerType {
         match self {
             SignerType::Software(s) => s.sign_message(message).await,
             SignerType::Hardware(h) => h.sign_message(message).await,
+            SignerType::Mwa(m) => m.sign_message(message).await,
+        }
+    }
+
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        match self {
+            SignerType::Software(s) => s.sign_messages(messages).await,
+            SignerType::Hardware(h) => h.sign_messages(messages).await,
+            SignerType::Mwa(m) => m.sign_messages(messages).await,
         }
     }
     
@@ -74,6 +132,7 @@ impl TransactionSigner for SignerType {
         match self {
             SignerType::Software(s) => s.get_name(),
             SignerType::Hardware(h) => h.get_name(),
+            SignerType::Mwa(m) => m.get_name(),
         }
     }
     
@@ -81,6 +140,35 @@ impl TransactionSigner for SignerType {
         match self {
             SignerType::Software(s) => s.is_available().await,
             SignerType::Hardware(h) => h.is_available().await,
+            SignerType::Mwa(m) => m.is_available().await,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Lets a borrowed signer be wrapped, e.g. in a `PolicySigner`
+#[async_trait]
+impl<T: TransactionSigner + ?Sized> TransactionSigner for &T {
+    async fn get_public_key(&self) -> Result<String, Box<dyn Error>> {
+        (**self).get_public_key().await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        (**self).sign_message(message).await
+    }
+
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        (**self).sign_messages(messages).await
+    }
+
+    async fn sign_offchain_message(&self, message: &OffchainMessage) -> Result<Vec<u8>, Box<dyn Error>> {
+        (**self).sign_offchain_message(message).await
+    }
+
+    fn get_name(&self) -> String {
+        (**self).get_name()
+    }
+
+    async fn is_available(&self) -> bool {
+        (**self).is_available().await
+    }
+}
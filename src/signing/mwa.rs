@@ -0,0 +1,675 @@
+// src/signing/mwa.rs
+use crate::signing::TransactionSigner;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::signature::Signature as SolanaSignature;
+use solana_sdk::transaction::VersionedTransaction;
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How long the user gets to approve a request in the wallet app
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MwaError {
+    /// The wallet app could not be reached
+    Unavailable(String),
+    /// Another request is still waiting on the wallet app
+    Busy,
+    Timeout,
+    Cancelled,
+    /// The wallet app answered with something that is not a valid signature
+    InvalidReply(String),
+}
+
+impl fmt::Display for MwaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MwaError::Unavailable(reason) => write!(f, "Wallet app unavailable: {}", reason),
+            MwaError::Busy => write!(f, "The wallet app is already handling a request"),
+            MwaError::Timeout => write!(f, "Timed out waiting for the wallet app"),
+            MwaError::Cancelled => write!(f, "Request was cancelled"),
+            MwaError::InvalidReply(reason) => write!(f, "Invalid reply from the wallet app: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for MwaError {}
+
+impl From<MwaError> for String {
+    fn from(e: MwaError) -> Self {
+        e.to_string()
+    }
+}
+
+/// A reply from the wallet app, as forwarded from `MsgFromKotlin`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MwaReply {
+    Pubkey(String),
+    /// Base64 signed transaction
+    SignedTransaction(String),
+    /// Base64 signed transactions, in request order
+    SignedTransactions(Vec<String>),
+    /// Base64 or base58 signature, or the message with its signature appended
+    SignedMessage(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequestKind {
+    Authorize,
+    SignTransaction,
+    SignTransactions,
+    SignMessage,
+}
+
+impl MwaReply {
+    fn kind(&self) -> RequestKind {
+        match self {
+            MwaReply::Pubkey(_) => RequestKind::Authorize,
+            MwaReply::SignedTransaction(_) => RequestKind::SignTransaction,
+            MwaReply::SignedTransactions(_) => RequestKind::SignTransactions,
+            MwaReply::SignedMessage(_) => RequestKind::SignMessage,
+        }
+    }
+}
+
+/// Starts requests in the wallet app. Each call returns once the request is launched;
+/// the answer comes back later through `MwaBridge::deliver`.
+pub trait MwaLauncher: Send + Sync {
+    fn authorize(&self) -> Result<(), String>;
+    fn sign_transaction(&self, transaction: &[u8]) -> Result<(), String>;
+    /// Several transactions under one approval in the wallet app
+    fn sign_transactions(&self, transactions: &[Vec<u8>]) -> Result<(), String>;
+    fn sign_message(&self, message: &[u8]) -> Result<(), String>;
+
+    fn is_available(&self) -> bool {
+        true
+    }
+}
+
+struct Pending {
+    id: u64,
+    kind: RequestKind,
+    reply: async_channel::Sender<Result<MwaReply, MwaError>>,
+}
+
+/// Turns launcher calls and the replies that arrive on the Kotlin channel into awaitable requests.
+/// The wallet app handles one request at a time, so a second request while one is waiting is refused.
+pub struct MwaBridge {
+    launcher: Arc<dyn MwaLauncher>,
+    pending: Mutex<Option<Pending>>,
+    next_id: AtomicU64,
+    public_key: Mutex<Option<String>>,
+    timeout: Duration,
+}
+
+impl MwaBridge {
+    pub fn new(launcher: Arc<dyn MwaLauncher>) -> Self {
+        Self {
+            launcher,
+            pending: Mutex::new(None),
+            next_id: AtomicU64::new(0),
+            public_key: Mutex::new(None),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Last public key the wallet app authorized, if any
+    pub fn public_key(&self) -> Option<String> {
+        self.public_key.lock().unwrap().clone()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.pending.lock().unwrap().is_some()
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.launcher.is_available()
+    }
+
+    /// Hand a reply from the wallet app to the request waiting for it.
+    /// Public keys are remembered even when nobody asked, since the wallet sends one on every connect.
+    pub fn deliver(&self, reply: MwaReply) {
+        if let MwaReply::Pubkey(key) = &reply {
+            *self.public_key.lock().unwrap() = Some(key.clone());
+        }
+
+        let mut pending = self.pending.lock().unwrap();
+        if pending.as_ref().is_some_and(|p| p.kind == reply.kind()) {
+            if let Some(waiting) = pending.take() {
+                let _ = waiting.reply.try_send(Ok(reply));
+            }
+        } else if reply.kind() != RequestKind::Authorize {
+            log::warn!("⚠️ MWA: dropping {:?} reply nobody is waiting for", reply.kind());
+        }
+    }
+
+    /// Fail the waiting request, e.g. when the user backs out of the approval screen
+    pub fn cancel(&self) {
+        if let Some(waiting) = self.pending.lock().unwrap().take() {
+            log::info!("🛑 MWA: cancelled {:?} request", waiting.kind);
+            let _ = waiting.reply.try_send(Err(MwaError::Cancelled));
+        }
+    }
+
+    async fn request(
+        &self,
+        kind: RequestKind,
+        launch: impl FnOnce(&dyn MwaLauncher) -> Result<(), String>,
+    ) -> Result<MwaReply, MwaError> {
+        let (sender, receiver) = async_channel::bounded(1);
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if pending.is_some() {
+                return Err(MwaError::Busy);
+            }
+            *pending = Some(Pending { id, kind, reply: sender });
+        }
+        // Frees the slot on every exit, including when the caller drops this future
+        let _guard = PendingGuard { bridge: self, id };
+
+        launch(self.launcher.as_ref()).map_err(MwaError::Unavailable)?;
+        match tokio::time::timeout(self.timeout, receiver.recv()).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(MwaError::Cancelled),
+            Err(_) => {
+                log::warn!("⏰ MWA: {:?} request timed out", kind);
+                Err(MwaError::Timeout)
+            }
+        }
+    }
+
+    /// Connect to the wallet app and return the authorized public key
+    pub async fn authorize(&self) -> Result<String, MwaError> {
+        match self.request(RequestKind::Authorize, |l| l.authorize()).await? {
+            MwaReply::Pubkey(key) => Ok(key),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Returns the base64 signed transaction
+    pub async fn sign_transaction(&self, transaction: &[u8]) -> Result<String, MwaError> {
+        match self
+            .request(RequestKind::SignTransaction, |l| l.sign_transaction(transaction))
+            .await?
+        {
+            MwaReply::SignedTransaction(signed) => Ok(signed),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Returns the base64 signed transactions in request order
+    pub async fn sign_transactions(&self, transactions: &[Vec<u8>]) -> Result<Vec<String>, MwaError> {
+        match self
+            .request(RequestKind::SignTransactions, |l| l.sign_transactions(transactions))
+            .await?
+        {
+            MwaReply::SignedTransactions(signed) if signed.len() == transactions.len() => Ok(signed),
+            MwaReply::SignedTransactions(signed) => Err(MwaError::InvalidReply(format!(
+                "{} transactions signed out of {}",
+                signed.len(),
+                transactions.len()
+            ))),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub async fn sign_message(&self, message: &[u8]) -> Result<String, MwaError> {
+        match self.request(RequestKind::SignMessage, |l| l.sign_message(message)).await? {
+            MwaReply::SignedMessage(signature) => Ok(signature),
+            other => Err(unexpected(other)),
+        }
+    }
+}
+
+fn unexpected(reply: MwaReply) -> MwaError {
+    MwaError::InvalidReply(format!("unexpected {:?} reply", reply.kind()))
+}
+
+struct PendingGuard<'a> {
+    bridge: &'a MwaBridge,
+    id: u64,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        let mut pending = self.bridge.pending.lock().unwrap();
+        if pending.as_ref().is_some_and(|p| p.id == self.id) {
+            pending.take();
+        }
+    }
+}
+
+/// `bytes` as a transaction message, if that is exactly what they are
+fn transaction_message(bytes: &[u8]) -> Option<VersionedMessage> {
+    bincode::deserialize::<VersionedMessage>(bytes)
+        .ok()
+        .filter(|message| message.serialize() == bytes)
+}
+
+/// Wallet apps return either a bare signature or the message with its signature appended
+fn decode_message_signature(reply: &str, message: &[u8]) -> Result<Vec<u8>, MwaError> {
+    let reply = reply.trim();
+    let decoded = STANDARD
+        .decode(reply)
+        .ok()
+        .filter(|bytes| bytes.len() == 64 || bytes.len() == message.len() + 64)
+        .or_else(|| bs58::decode(reply).into_vec().ok())
+        .ok_or_else(|| MwaError::InvalidReply("signature is neither base64 nor base58".to_string()))?;
+
+    match decoded.len() {
+        64 => Ok(decoded),
+        len if len == message.len() + 64 && decoded.starts_with(message) => Ok(decoded[message.len()..].to_vec()),
+        len => Err(MwaError::InvalidReply(format!("expected a 64-byte signature, got {} bytes", len))),
+    }
+}
+
+/// Signs through another wallet app on the device over Mobile Wallet Adapter.
+/// Transaction messages go out as unsigned transactions, everything else as a message signing request.
+#[derive(Clone)]
+pub struct MwaSigner {
+    bridge: Arc<MwaBridge>,
+    address: Option<String>,
+}
+
+impl MwaSigner {
+    /// `address` is the wallet's public key when already known from a previous connect
+    pub fn new(bridge: Arc<MwaBridge>, address: Option<String>) -> Self {
+        Self { bridge, address }
+    }
+
+    /// Stop waiting for the wallet app
+    pub fn cancel(&self) {
+        self.bridge.cancel();
+    }
+
+    /// One wallet app request per message
+    async fn sign_each(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        let mut signatures = Vec::with_capacity(messages.len());
+        for message in messages {
+            signatures.push(self.sign_message(message).await?);
+        }
+        Ok(signatures)
+    }
+}
+
+/// An unsigned transaction for `message`, and which signature slot belongs to `public_key`
+fn unsigned_transaction(message: VersionedMessage, public_key: &str) -> Result<(VersionedTransaction, usize), Box<dyn Error>> {
+    let required = message.header().num_required_signatures as usize;
+    let signer_index = message
+        .static_account_keys()
+        .iter()
+        .take(required)
+        .position(|key| key.to_string() == public_key)
+        .ok_or("Transaction does not need a signature from the connected wallet")?;
+
+    let unsigned = VersionedTransaction {
+        signatures: vec![SolanaSignature::default(); required],
+        message,
+    };
+    Ok((unsigned, signer_index))
+}
+
+/// Pull our signature out of the base64 transaction the wallet app returned
+fn signature_from_reply(reply: &str, unsigned: &VersionedTransaction, signer_index: usize) -> Result<Vec<u8>, MwaError> {
+    let bytes = STANDARD
+        .decode(reply.trim())
+        .map_err(|e| MwaError::InvalidReply(e.to_string()))?;
+    let signed: VersionedTransaction =
+        bincode::deserialize(&bytes).map_err(|e| MwaError::InvalidReply(e.to_string()))?;
+    // The signature only covers the message the wallet saw; refuse one it rewrote
+    if signed.message.serialize() != unsigned.message.serialize() {
+        return Err(MwaError::InvalidReply("the wallet app changed the transaction".to_string()));
+    }
+    signed
+        .signatures
+        .get(signer_index)
+        .map(|signature| signature.as_ref().to_vec())
+        .ok_or_else(|| MwaError::InvalidReply("signature missing".to_string()))
+}
+
+fn verifying_key(public_key: &str) -> Result<VerifyingKey, String> {
+    bs58::decode(public_key)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| format!("Invalid wallet public key: {}", public_key))
+}
+
+/// Check the wallet app signed `message` with the key we expect
+fn check_signature(key: &VerifyingKey, message: &[u8], signature: &[u8]) -> Result<(), MwaError> {
+    let valid = Signature::from_slice(signature)
+        .map(|s| key.verify(message, &s).is_ok())
+        .unwrap_or(false);
+    if !valid {
+        return Err(MwaError::InvalidReply("signature does not match the connected wallet".to_string()));
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl TransactionSigner for MwaSigner {
+    async fn get_public_key(&self) -> Result<String, Box<dyn Error>> {
+        if let Some(address) = self.address.clone().or_else(|| self.bridge.public_key()) {
+            return Ok(address);
+        }
+        Ok(self.bridge.authorize().await?)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        let public_key = self.get_public_key().await?;
+        let key = verifying_key(&public_key)?;
+
+        let signature = match transaction_message(message) {
+            Some(transaction) => {
+                let (unsigned, signer_index) = unsigned_transaction(transaction, &public_key)?;
+                let reply = self.bridge.sign_transaction(&bincode::serialize(&unsigned)?).await?;
+                signature_from_reply(&reply, &unsigned, signer_index)?
+            }
+            None => {
+                let reply = self.bridge.sign_message(message).await?;
+                decode_message_signature(&reply, message)?
+            }
+        };
+        check_signature(&key, message, &signature)?;
+        Ok(signature)
+    }
+
+    /// Transactions go to the wallet app as one request, so the user approves them together.
+    /// When the batch request can't be launched, each transaction is signed on its own.
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        let transactions: Option<Vec<VersionedMessage>> = messages.iter().map(|m| transaction_message(m)).collect();
+        let transactions = match transactions {
+            Some(transactions) if transactions.len() > 1 => transactions,
+            // Single messages and off-chain messages are signed one request at a time
+            _ => return self.sign_each(messages).await,
+        };
+
+        let public_key = self.get_public_key().await?;
+        let key = verifying_key(&public_key)?;
+        let unsigned = transactions
+            .into_iter()
+            .map(|message| unsigned_transaction(message, &public_key))
+            .collect::<Result<Vec<_>, _>>()?;
+        let serialized = unsigned
+            .iter()
+            .map(|(transaction, _)| bincode::serialize(transaction))
+            .collect::<Result<Vec<_>, _>>()?;
+        let replies = match self.bridge.sign_transactions(&serialized).await {
+            Ok(replies) => replies,
+            Err(MwaError::Unavailable(reason)) => {
+                log::warn!("⚠️ MWA: batch signing unavailable ({}), signing one at a time", reason);
+                return self.sign_each(messages).await;
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut signatures = Vec::with_capacity(messages.len());
+        for ((reply, (transaction, signer_index)), message) in replies.iter().zip(&unsigned).zip(messages) {
+            let signature = signature_from_reply(reply, transaction, *signer_index)?;
+            check_signature(&key, message, &signature)?;
+            signatures.push(signature);
+        }
+        Ok(signatures)
+    }
+
+    fn get_name(&self) -> String {
+        "Mobile Wallet Adapter".to_string()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.bridge.is_available()
+    }
+}
+
+/// Launches requests through the Kotlin layer. The ffi calls must run on the Dioxus thread,
+/// which is where component tasks awaiting an `MwaSigner` are polled.
+#[cfg(target_os = "android")]
+struct AndroidLauncher;
+
+/// The ffi helpers report failures as text rather than a Result
+#[cfg(target_os = "android")]
+fn launch_status(status: String) -> Result<(), String> {
+    if status.starts_with("Error") || status.starts_with("JNI call") {
+        Err(status)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "android")]
+impl MwaLauncher for AndroidLauncher {
+    fn authorize(&self) -> Result<(), String> {
+        launch_status(crate::ffi::initiate_mwa_session_from_dioxus())
+    }
+
+    fn sign_transaction(&self, transaction: &[u8]) -> Result<(), String> {
+        launch_status(crate::ffi::initiate_sign_transaction_from_dioxus(transaction))
+    }
+
+    fn sign_transactions(&self, transactions: &[Vec<u8>]) -> Result<(), String> {
+        launch_status(crate::ffi::initiate_sign_transactions_from_dioxus(transactions))
+    }
+
+    fn sign_message(&self, message: &[u8]) -> Result<(), String> {
+        launch_status(crate::ffi::initiate_sign_message_from_dioxus(message))
+    }
+
+    fn is_available(&self) -> bool {
+        crate::ffi::is_activity_available()
+    }
+}
+
+#[cfg(target_os = "android")]
+static ANDROID_BRIDGE: once_cell::sync::Lazy<Arc<MwaBridge>> =
+    once_cell::sync::Lazy::new(|| Arc::new(MwaBridge::new(Arc::new(AndroidLauncher))));
+
+/// The app-wide bridge that `MsgFromKotlin` replies are delivered to
+#[cfg(target_os = "android")]
+pub fn android_bridge() -> Arc<MwaBridge> {
+    ANDROID_BRIDGE.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::Wallet;
+    use solana_sdk::{hash::Hash, message::Message, pubkey::Pubkey, system_instruction};
+    use std::str::FromStr;
+    use std::sync::{OnceLock, Weak};
+
+    /// Stands in for the Kotlin layer and the wallet app: records each launch and,
+    /// when holding a wallet, answers straight away through the bridge.
+    #[derive(Default)]
+    struct FakeWalletApp {
+        wallet: Option<Wallet>,
+        /// Behave like a Kotlin layer without `signTransactions`
+        no_batch: bool,
+        bridge: OnceLock<Weak<MwaBridge>>,
+        launched: Mutex<Vec<&'static str>>,
+    }
+
+    impl FakeWalletApp {
+        fn reply(&self, reply: MwaReply) {
+            if let Some(bridge) = self.bridge.get().and_then(Weak::upgrade) {
+                bridge.deliver(reply);
+            }
+        }
+    }
+
+    impl MwaLauncher for FakeWalletApp {
+        fn authorize(&self) -> Result<(), String> {
+            self.launched.lock().unwrap().push("authorize");
+            if let Some(wallet) = &self.wallet {
+                self.reply(MwaReply::Pubkey(wallet.get_public_key()));
+            }
+            Ok(())
+        }
+
+        fn sign_transaction(&self, transaction: &[u8]) -> Result<(), String> {
+            self.launched.lock().unwrap().push("sign_transaction");
+            if let Some(wallet) = &self.wallet {
+                let mut tx: VersionedTransaction = bincode::deserialize(transaction).unwrap();
+                tx.signatures[0] = SolanaSignature::from(wallet.sign_message(&tx.message.serialize()).to_bytes());
+                self.reply(MwaReply::SignedTransaction(STANDARD.encode(bincode::serialize(&tx).unwrap())));
+            }
+            Ok(())
+        }
+
+        fn sign_transactions(&self, transactions: &[Vec<u8>]) -> Result<(), String> {
+            self.launched.lock().unwrap().push("sign_transactions");
+            if self.no_batch {
+                return Err("Error: signTransactions not found".to_string());
+            }
+            if let Some(wallet) = &self.wallet {
+                let signed = transactions
+                    .iter()
+                    .map(|transaction| {
+                        let mut tx: VersionedTransaction = bincode::deserialize(transaction).unwrap();
+                        tx.signatures[0] = SolanaSignature::from(wallet.sign_message(&tx.message.serialize()).to_bytes());
+                        STANDARD.encode(bincode::serialize(&tx).unwrap())
+                    })
+                    .collect();
+                self.reply(MwaReply::SignedTransactions(signed));
+            }
+            Ok(())
+        }
+
+        fn sign_message(&self, message: &[u8]) -> Result<(), String> {
+            self.launched.lock().unwrap().push("sign_message");
+            if let Some(wallet) = &self.wallet {
+                // Payload form: message followed by the signature
+                let mut payload = message.to_vec();
+                payload.extend_from_slice(&wallet.sign_message(message).to_bytes());
+                self.reply(MwaReply::SignedMessage(bs58::encode(payload).into_string()));
+            }
+            Ok(())
+        }
+    }
+
+    fn bridge_for(app: Arc<FakeWalletApp>) -> Arc<MwaBridge> {
+        let bridge = Arc::new(MwaBridge::new(app.clone()).with_timeout(Duration::from_millis(200)));
+        app.bridge.set(Arc::downgrade(&bridge)).unwrap();
+        bridge
+    }
+
+    #[tokio::test]
+    async fn test_bridge_delivers_timeouts_and_cancels() {
+        let app = Arc::new(FakeWalletApp::default());
+        let bridge = bridge_for(app.clone());
+
+        let (result, _) = tokio::join!(bridge.sign_message(b"hello"), async {
+            tokio::task::yield_now().await;
+            // A reply of the wrong kind is not mistaken for the answer
+            bridge.deliver(MwaReply::SignedTransaction("AAAA".to_string()));
+            bridge.deliver(MwaReply::SignedMessage("sig".to_string()));
+        });
+        assert_eq!(result, Ok("sig".to_string()));
+        assert!(!bridge.is_busy());
+
+        let (first, second) = tokio::join!(bridge.sign_message(b"one"), async {
+            tokio::task::yield_now().await;
+            let second = bridge.sign_message(b"two").await;
+            bridge.cancel();
+            second
+        });
+        assert_eq!(second, Err(MwaError::Busy));
+        assert_eq!(first, Err(MwaError::Cancelled));
+
+        assert_eq!(bridge.authorize().await, Err(MwaError::Timeout));
+        assert!(!bridge.is_busy());
+
+        // Dropping a waiting request frees the bridge for the next one
+        let abandoned = tokio::time::timeout(Duration::from_millis(10), bridge.sign_message(b"late")).await;
+        assert!(abandoned.is_err());
+        assert!(!bridge.is_busy());
+        assert_eq!(
+            *app.launched.lock().unwrap(),
+            vec!["sign_message", "sign_message", "authorize", "sign_message"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_signer_signs_transactions_and_messages() {
+        let wallet = Wallet::new("Phone wallet".to_string());
+        let address = wallet.get_public_key();
+        let app = Arc::new(FakeWalletApp {
+            wallet: Some(wallet),
+            ..Default::default()
+        });
+        let signer = MwaSigner::new(bridge_for(app.clone()), None);
+        assert_eq!(signer.get_public_key().await.unwrap(), address);
+
+        let from = Pubkey::from_str(&address).unwrap();
+        let mut message = Message::new(&[system_instruction::transfer(&from, &Pubkey::new_unique(), 1)], Some(&from));
+        message.recent_blockhash = Hash::new_unique();
+        let message_bytes = VersionedMessage::Legacy(message).serialize();
+        let signature = signer.sign_message(&message_bytes).await.unwrap();
+        assert_eq!(signature.len(), 64);
+
+        let text = b"\xffsolana offchain not a transaction";
+        assert_eq!(signer.sign_message(text).await.unwrap().len(), 64);
+
+        // Several transactions go to the wallet app as one request
+        let batch: Vec<Vec<u8>> = (1..=3)
+            .map(|lamports| {
+                let mut message =
+                    Message::new(&[system_instruction::transfer(&from, &Pubkey::new_unique(), lamports)], Some(&from));
+                message.recent_blockhash = Hash::new_unique();
+                VersionedMessage::Legacy(message).serialize()
+            })
+            .collect();
+        let signatures = signer.sign_messages(&batch).await.unwrap();
+        assert_eq!(signatures.len(), 3);
+        assert_eq!(signatures[0].len(), 64);
+        assert_ne!(signatures[0], signatures[1]);
+        assert_eq!(
+            *app.launched.lock().unwrap(),
+            vec!["authorize", "sign_transaction", "sign_message", "sign_transactions"]
+        );
+
+        // A wallet app answering for a different key is caught
+        let other = MwaSigner::new(signer.bridge.clone(), Some(Wallet::new("Other".to_string()).get_public_key()));
+        assert!(other.sign_message(text).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_falls_back_to_single_requests() {
+        let wallet = Wallet::new("Phone wallet".to_string());
+        let address = wallet.get_public_key();
+        let app = Arc::new(FakeWalletApp {
+            wallet: Some(wallet),
+            no_batch: true,
+            ..Default::default()
+        });
+        let signer = MwaSigner::new(bridge_for(app.clone()), Some(address.clone()));
+
+        let from = Pubkey::from_str(&address).unwrap();
+        let batch: Vec<Vec<u8>> = (1..=2)
+            .map(|lamports| {
+                let mut message =
+                    Message::new(&[system_instruction::transfer(&from, &Pubkey::new_unique(), lamports)], Some(&from));
+                message.recent_blockhash = Hash::new_unique();
+                VersionedMessage::Legacy(message).serialize()
+            })
+            .collect();
+        let signatures = signer.sign_messages(&batch).await.unwrap();
+        assert_eq!(signatures.len(), 2);
+        assert_eq!(
+            *app.launched.lock().unwrap(),
+            vec!["sign_transactions", "sign_transaction", "sign_transaction"]
+        );
+    }
+}
@@ -0,0 +1,257 @@
+// src/signing/offchain.rs
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use std::fmt;
+
+/// Prefix that makes an off-chain message impossible to mistake for a transaction
+pub const SIGNING_DOMAIN: &[u8; 16] = b"\xffsolana offchain";
+pub const HEADER_VERSION: u8 = 0;
+/// Domain, version, format and little-endian u16 length
+const HEADER_LEN: usize = SIGNING_DOMAIN.len() + 1 + 1 + 2;
+/// Longest message that still fits in a transaction-sized packet, which Ledger-style devices require
+pub const MAX_LEN_LEDGER: usize = 1232 - HEADER_LEN;
+pub const MAX_LEN: usize = u16::MAX as usize - HEADER_LEN;
+
+/// Message encodings defined by the off-chain message format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Printable ASCII, up to `MAX_LEN_LEDGER` bytes
+    RestrictedAscii = 0,
+    /// UTF-8, up to `MAX_LEN_LEDGER` bytes
+    LimitedUtf8 = 1,
+    /// UTF-8, up to `MAX_LEN` bytes
+    ExtendedUtf8 = 2,
+}
+
+impl MessageFormat {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(MessageFormat::RestrictedAscii),
+            1 => Some(MessageFormat::LimitedUtf8),
+            2 => Some(MessageFormat::ExtendedUtf8),
+            _ => None,
+        }
+    }
+
+    /// The most restrictive format that can carry `text`
+    fn for_text(text: &str) -> Self {
+        if text.len() > MAX_LEN_LEDGER {
+            MessageFormat::ExtendedUtf8
+        } else if text.bytes().all(|b| (0x20..=0x7e).contains(&b)) {
+            MessageFormat::RestrictedAscii
+        } else {
+            MessageFormat::LimitedUtf8
+        }
+    }
+
+    fn allows(&self, text: &str) -> bool {
+        match self {
+            MessageFormat::RestrictedAscii => {
+                text.len() <= MAX_LEN_LEDGER && text.bytes().all(|b| (0x20..=0x7e).contains(&b))
+            }
+            MessageFormat::LimitedUtf8 => text.len() <= MAX_LEN_LEDGER,
+            MessageFormat::ExtendedUtf8 => text.len() <= MAX_LEN,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OffchainMessageError {
+    Empty,
+    TooLong { len: usize, max: usize },
+    /// Bytes are not a serialized off-chain message
+    InvalidHeader(String),
+    /// Body does not match the declared format
+    InvalidEncoding,
+    InvalidSignature(String),
+    InvalidPublicKey(String),
+}
+
+impl fmt::Display for OffchainMessageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OffchainMessageError::Empty => write!(f, "Message cannot be empty"),
+            OffchainMessageError::TooLong { len, max } => {
+                write!(f, "Message is {} bytes, the limit is {}", len, max)
+            }
+            OffchainMessageError::InvalidHeader(reason) => write!(f, "Not an off-chain message: {}", reason),
+            OffchainMessageError::InvalidEncoding => write!(f, "Message text does not match its declared format"),
+            OffchainMessageError::InvalidSignature(reason) => write!(f, "Invalid signature: {}", reason),
+            OffchainMessageError::InvalidPublicKey(reason) => write!(f, "Invalid public key: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for OffchainMessageError {}
+
+impl From<OffchainMessageError> for String {
+    fn from(e: OffchainMessageError) -> Self {
+        e.to_string()
+    }
+}
+
+/// A human-readable message in the Solana off-chain message format (header version 0).
+///
+/// Signers sign `serialize()`, never the raw text, so a signature over a message
+/// can't be replayed as a transaction signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffchainMessage {
+    format: MessageFormat,
+    text: String,
+}
+
+impl OffchainMessage {
+    pub fn new(text: &str) -> Result<Self, OffchainMessageError> {
+        if text.is_empty() {
+            return Err(OffchainMessageError::Empty);
+        }
+        if text.len() > MAX_LEN {
+            return Err(OffchainMessageError::TooLong { len: text.len(), max: MAX_LEN });
+        }
+        Ok(Self {
+            format: MessageFormat::for_text(text),
+            text: text.to_string(),
+        })
+    }
+
+    pub fn format(&self) -> MessageFormat {
+        self.format
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Whether hardware signers limited to one packet can sign it
+    pub fn fits_ledger(&self) -> bool {
+        self.format != MessageFormat::ExtendedUtf8
+    }
+
+    /// The exact bytes that get signed
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_LEN + self.text.len());
+        bytes.extend_from_slice(SIGNING_DOMAIN);
+        bytes.push(HEADER_VERSION);
+        bytes.push(self.format as u8);
+        bytes.extend_from_slice(&(self.text.len() as u16).to_le_bytes());
+        bytes.extend_from_slice(self.text.as_bytes());
+        bytes
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, OffchainMessageError> {
+        if bytes.len() < HEADER_LEN || &bytes[..SIGNING_DOMAIN.len()] != SIGNING_DOMAIN {
+            return Err(OffchainMessageError::InvalidHeader("missing signing domain".to_string()));
+        }
+        let header = &bytes[SIGNING_DOMAIN.len()..HEADER_LEN];
+        if header[0] != HEADER_VERSION {
+            return Err(OffchainMessageError::InvalidHeader(format!("unsupported version {}", header[0])));
+        }
+        let format = MessageFormat::from_byte(header[1])
+            .ok_or_else(|| OffchainMessageError::InvalidHeader(format!("unknown format {}", header[1])))?;
+        let len = u16::from_le_bytes([header[2], header[3]]) as usize;
+        let body = &bytes[HEADER_LEN..];
+        if body.len() != len {
+            return Err(OffchainMessageError::InvalidHeader(format!(
+                "length says {} bytes but {} follow",
+                len,
+                body.len()
+            )));
+        }
+
+        let text = std::str::from_utf8(body).map_err(|_| OffchainMessageError::InvalidEncoding)?;
+        if text.is_empty() {
+            return Err(OffchainMessageError::Empty);
+        }
+        if !format.allows(text) {
+            return Err(OffchainMessageError::InvalidEncoding);
+        }
+        Ok(Self {
+            format,
+            text: text.to_string(),
+        })
+    }
+
+    /// Check a 64-byte signature over this message against a base58 public key
+    pub fn verify(&self, signature: &[u8], public_key: &str) -> Result<bool, OffchainMessageError> {
+        let key_bytes: [u8; 32] = bs58::decode(public_key.trim())
+            .into_vec()
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| OffchainMessageError::InvalidPublicKey(public_key.to_string()))?;
+        let verifying_key = VerifyingKey::from_bytes(&key_bytes)
+            .map_err(|e| OffchainMessageError::InvalidPublicKey(e.to_string()))?;
+        let signature = Signature::from_slice(signature)
+            .map_err(|e| OffchainMessageError::InvalidSignature(e.to_string()))?;
+
+        Ok(verifying_key.verify(&self.serialize(), &signature).is_ok())
+    }
+}
+
+/// Verify a (message text, base58 signature, base58 public key) triple as pasted by a user
+pub fn verify_text(text: &str, signature: &str, public_key: &str) -> Result<bool, OffchainMessageError> {
+    let signature = bs58::decode(signature.trim())
+        .into_vec()
+        .map_err(|e| OffchainMessageError::InvalidSignature(e.to_string()))?;
+    OffchainMessage::new(text)?.verify(&signature, public_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::Wallet;
+
+    #[test]
+    fn test_serialize_header() {
+        let message = OffchainMessage::new("Hello").unwrap();
+        assert_eq!(message.format(), MessageFormat::RestrictedAscii);
+
+        let bytes = message.serialize();
+        assert_eq!(&bytes[..16], b"\xffsolana offchain");
+        assert_eq!(&bytes[16..20], &[0, 0, 5, 0]);
+        assert_eq!(&bytes[20..], b"Hello");
+        assert_eq!(OffchainMessage::deserialize(&bytes).unwrap(), message);
+
+        assert_eq!(OffchainMessage::new("Grüße").unwrap().format(), MessageFormat::LimitedUtf8);
+        let long = "a".repeat(MAX_LEN_LEDGER + 1);
+        assert_eq!(OffchainMessage::new(&long).unwrap().format(), MessageFormat::ExtendedUtf8);
+        assert!(!OffchainMessage::new(&long).unwrap().fits_ledger());
+        assert_eq!(OffchainMessage::new(""), Err(OffchainMessageError::Empty));
+        assert!(OffchainMessage::new(&"a".repeat(MAX_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_malformed() {
+        let mut bytes = OffchainMessage::new("Hello").unwrap().serialize();
+        assert!(OffchainMessage::deserialize(&bytes[1..]).is_err());
+        assert!(OffchainMessage::deserialize(&bytes[..bytes.len() - 1]).is_err());
+
+        // Non-ASCII text declared as restricted ASCII
+        let mut mislabeled = OffchainMessage::new("Grüße").unwrap().serialize();
+        mislabeled[17] = MessageFormat::RestrictedAscii as u8;
+        assert_eq!(OffchainMessage::deserialize(&mislabeled), Err(OffchainMessageError::InvalidEncoding));
+
+        bytes[16] = 1;
+        assert!(OffchainMessage::deserialize(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let wallet = Wallet::new("Signer".to_string());
+        let message = OffchainMessage::new("I control this address").unwrap();
+        let signature = wallet.sign_offchain_message(&message).to_bytes();
+        let address = wallet.get_public_key();
+
+        assert!(message.verify(&signature, &address).unwrap());
+        let encoded = bs58::encode(signature).into_string();
+        assert!(verify_text("I control this address", &encoded, &address).unwrap());
+        assert!(!verify_text("I control that address", &encoded, &address).unwrap());
+
+        // A plain signature over the raw text is not an off-chain message signature
+        let raw = wallet.sign_message(message.text().as_bytes()).to_bytes();
+        assert!(!message.verify(&raw, &address).unwrap());
+
+        let other = Wallet::new("Other".to_string()).get_public_key();
+        assert!(!message.verify(&signature, &other).unwrap());
+        assert!(message.verify(&signature[..10], &address).is_err());
+        assert!(message.verify(&signature, "not-a-key").is_err());
+    }
+}
@@ -0,0 +1,283 @@
+// src/signing/partial.rs
+use crate::signing::policy::{PolicyError, PolicySigner};
+use crate::signing::TransactionSigner;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_sdk::{
+    message::VersionedMessage,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature as SolanaSignature, Signer},
+    transaction::VersionedTransaction,
+};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PartialSignError {
+    /// Bytes are not a transaction, or its signature count does not match the message
+    Malformed(String),
+    /// The key does not sign this transaction
+    NotRequired(String),
+    InvalidSignature(String),
+    /// Transaction is still waiting on these signers
+    Incomplete(Vec<String>),
+    /// Refused by the device's signing policy
+    Policy(PolicyError),
+    Signer(String),
+}
+
+impl fmt::Display for PartialSignError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PartialSignError::Malformed(reason) => write!(f, "Not a valid transaction: {}", reason),
+            PartialSignError::NotRequired(key) => write!(f, "{} does not need to sign this transaction", key),
+            PartialSignError::InvalidSignature(key) => write!(f, "Signature from {} does not match the transaction", key),
+            PartialSignError::Incomplete(missing) => {
+                write!(f, "Transaction still needs signatures from: {}", missing.join(", "))
+            }
+            PartialSignError::Policy(e) => write!(f, "{}", e),
+            PartialSignError::Signer(reason) => write!(f, "Signing failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for PartialSignError {}
+
+impl From<PartialSignError> for String {
+    fn from(e: PartialSignError) -> Self {
+        e.to_string()
+    }
+}
+
+/// A transaction collecting signatures from several signers, possibly over time.
+///
+/// Works out the required signers from the message header, accepts signatures from
+/// any `TransactionSigner` or a local keypair, and can be shared as base64 while incomplete.
+/// Missing signatures are serialized as all zeroes, like `--sign-only` output.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialTransaction {
+    transaction: VersionedTransaction,
+}
+
+impl PartialTransaction {
+    pub fn new(message: VersionedMessage) -> Self {
+        let required = message.header().num_required_signatures as usize;
+        Self {
+            transaction: VersionedTransaction {
+                signatures: vec![SolanaSignature::default(); required],
+                message,
+            },
+        }
+    }
+
+    /// Resume from a transaction that may already carry some signatures.
+    /// Every signature present must be valid.
+    pub fn from_transaction(transaction: VersionedTransaction) -> Result<Self, PartialSignError> {
+        let required = transaction.message.header().num_required_signatures as usize;
+        if transaction.signatures.len() != required {
+            return Err(PartialSignError::Malformed(format!(
+                "{} signatures for {} signers",
+                transaction.signatures.len(),
+                required
+            )));
+        }
+        if transaction.message.static_account_keys().len() < required {
+            return Err(PartialSignError::Malformed("fewer accounts than signers".to_string()));
+        }
+
+        let partial = Self { transaction };
+        let message_bytes = partial.transaction.message.serialize();
+        for (signature, key) in partial.transaction.signatures.iter().zip(partial.required_signers()) {
+            if *signature != SolanaSignature::default() && !signature.verify(key.as_ref(), &message_bytes) {
+                return Err(PartialSignError::InvalidSignature(key.to_string()));
+            }
+        }
+        Ok(partial)
+    }
+
+    pub fn message(&self) -> &VersionedMessage {
+        &self.transaction.message
+    }
+
+    /// Keys that must sign, fee payer first
+    pub fn required_signers(&self) -> &[Pubkey] {
+        let required = self.transaction.signatures.len();
+        &self.transaction.message.static_account_keys()[..required]
+    }
+
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        self.required_signers()
+            .iter()
+            .zip(&self.transaction.signatures)
+            .filter(|(_, signature)| **signature == SolanaSignature::default())
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.missing_signers().is_empty()
+    }
+
+    /// The signature collected so far from `signer`, if any
+    pub fn signature(&self, signer: &Pubkey) -> Option<SolanaSignature> {
+        self.required_signers()
+            .iter()
+            .position(|key| key == signer)
+            .map(|index| self.transaction.signatures[index])
+            .filter(|signature| *signature != SolanaSignature::default())
+    }
+
+    /// Add a signature made elsewhere, after checking it against the message
+    pub fn add_signature(&mut self, signer: &Pubkey, signature: &[u8]) -> Result<(), PartialSignError> {
+        let index = self
+            .required_signers()
+            .iter()
+            .position(|key| key == signer)
+            .ok_or_else(|| PartialSignError::NotRequired(signer.to_string()))?;
+        let signature = SolanaSignature::try_from(signature)
+            .map_err(|_| PartialSignError::InvalidSignature(signer.to_string()))?;
+        if !signature.verify(signer.as_ref(), &self.transaction.message.serialize()) {
+            return Err(PartialSignError::InvalidSignature(signer.to_string()));
+        }
+
+        self.transaction.signatures[index] = signature;
+        Ok(())
+    }
+
+    /// Sign with any signer type; returns the key it signed for
+    pub async fn sign_with<S: TransactionSigner + ?Sized>(&mut self, signer: &S) -> Result<Pubkey, PartialSignError> {
+        let address = signer
+            .get_public_key()
+            .await
+            .map_err(|e| PartialSignError::Signer(e.to_string()))?;
+        let key = Pubkey::from_str(&address).map_err(|e| PartialSignError::Signer(e.to_string()))?;
+        // Check before prompting, so nobody approves a transaction they can't sign
+        if !self.required_signers().contains(&key) {
+            return Err(PartialSignError::NotRequired(address));
+        }
+
+        // Every transaction signature goes through the device's signing policy
+        let signature = PolicySigner::shared(signer)
+            .sign_message(&self.transaction.message.serialize())
+            .await
+            .map_err(|e| match e.downcast::<PolicyError>() {
+                Ok(policy) => PartialSignError::Policy(*policy),
+                Err(e) => PartialSignError::Signer(e.to_string()),
+            })?;
+        self.add_signature(&key, &signature)?;
+        Ok(key)
+    }
+
+    /// Sign with a keypair generated for this transaction, such as a new stake account
+    pub fn sign_with_keypair(&mut self, keypair: &Keypair) -> Result<(), PartialSignError> {
+        let signature = keypair.sign_message(&self.transaction.message.serialize());
+        self.add_signature(&keypair.pubkey(), signature.as_ref())
+    }
+
+    /// Bytes to hand to the next signer
+    pub fn serialize(&self) -> Vec<u8> {
+        bincode::serialize(&self.transaction).expect("transaction serializes")
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> Result<Self, PartialSignError> {
+        let transaction: VersionedTransaction =
+            bincode::deserialize(bytes).map_err(|e| PartialSignError::Malformed(e.to_string()))?;
+        Self::from_transaction(transaction)
+    }
+
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.serialize())
+    }
+
+    pub fn from_base64(encoded: &str) -> Result<Self, PartialSignError> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .map_err(|e| PartialSignError::Malformed(e.to_string()))?;
+        Self::deserialize(&bytes)
+    }
+
+    /// The fully signed transaction, ready to send
+    pub fn finish(self) -> Result<VersionedTransaction, PartialSignError> {
+        let missing = self.missing_signers();
+        if !missing.is_empty() {
+            return Err(PartialSignError::Incomplete(missing.iter().map(|k| k.to_string()).collect()));
+        }
+        Ok(self.transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::SignerType;
+    use crate::wallet::Wallet;
+    use solana_sdk::{hash::Hash, message::Message, system_instruction};
+
+    fn key(wallet: &Wallet) -> Pubkey {
+        Pubkey::from_str(&wallet.get_public_key()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_collects_signatures_across_signers() {
+        let fee_payer = Wallet::new("Fee payer".to_string());
+        let treasury = Wallet::new("Treasury".to_string());
+        let new_account = Keypair::new();
+
+        let instructions = [
+            system_instruction::transfer(&key(&treasury), &Pubkey::new_unique(), 1_000),
+            system_instruction::create_account(&key(&fee_payer), &new_account.pubkey(), 1_000, 0, &Pubkey::new_unique()),
+        ];
+        let mut message = Message::new(&instructions, Some(&key(&fee_payer)));
+        message.recent_blockhash = Hash::new_unique();
+        let mut partial = PartialTransaction::new(VersionedMessage::Legacy(message));
+
+        assert_eq!(partial.required_signers().len(), 3);
+        assert_eq!(partial.required_signers()[0], key(&fee_payer));
+        assert!(!partial.is_complete());
+
+        let treasury_key = key(&treasury);
+        assert_eq!(partial.sign_with(&SignerType::from_wallet(treasury)).await.unwrap(), treasury_key);
+        partial.sign_with_keypair(&new_account).unwrap();
+        assert_eq!(partial.missing_signers(), vec![key(&fee_payer)]);
+        assert!(matches!(partial.clone().finish(), Err(PartialSignError::Incomplete(_))));
+
+        // Handed over as text and finished by the fee payer later
+        let shared = partial.to_base64();
+        let mut resumed = PartialTransaction::from_base64(&shared).unwrap();
+        assert_eq!(resumed, partial);
+        resumed.sign_with(&SignerType::from_wallet(fee_payer)).await.unwrap();
+        let transaction = resumed.finish().unwrap();
+        assert!(transaction.verify_with_results().iter().all(|ok| *ok));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_signers_and_signatures() {
+        let payer = Wallet::new("Payer".to_string());
+        let mut message = Message::new(
+            &[system_instruction::transfer(&key(&payer), &Pubkey::new_unique(), 1)],
+            Some(&key(&payer)),
+        );
+        message.recent_blockhash = Hash::new_unique();
+        let mut partial = PartialTransaction::new(VersionedMessage::Legacy(message));
+
+        let stranger = SignerType::from_wallet(Wallet::new("Stranger".to_string()));
+        assert!(matches!(partial.sign_with(&stranger).await, Err(PartialSignError::NotRequired(_))));
+
+        let forged = payer.sign_message(b"something else").to_bytes();
+        assert_eq!(
+            partial.add_signature(&key(&payer), &forged),
+            Err(PartialSignError::InvalidSignature(payer.get_public_key()))
+        );
+        assert!(partial.add_signature(&key(&payer), &forged[..10]).is_err());
+
+        // A tampered signature in shared bytes is caught on load
+        let mut transaction = partial.clone().transaction;
+        transaction.signatures[0] = SolanaSignature::from(forged);
+        assert!(PartialTransaction::from_transaction(transaction.clone()).is_err());
+        transaction.signatures.push(SolanaSignature::default());
+        assert!(matches!(
+            PartialTransaction::from_transaction(transaction),
+            Err(PartialSignError::Malformed(_))
+        ));
+        assert!(PartialTransaction::from_base64("not a transaction").is_err());
+    }
+}
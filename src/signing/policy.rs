@@ -0,0 +1,977 @@
+// src/signing/policy.rs
+use crate::keystore;
+use crate::preview::{InstructionSummary, TransactionPreview, TOKEN_2022_PROGRAM};
+use crate::schema::Document;
+use crate::signing::offchain::OffchainMessage;
+use crate::signing::TransactionSigner;
+use crate::storage;
+use crate::transaction::JITO_TIP_ACCOUNTS;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use solana_sdk::{pubkey::Pubkey, system_program};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+/// Length of the rolling window for daily limits
+const WINDOW_SECS: i64 = 24 * 60 * 60;
+
+/// How long an approved override stays usable
+pub const OVERRIDE_TTL_SECS: i64 = 15 * 60;
+
+const POLICY_FILE_NAME: &str = "signing_policy.json";
+const POLICY_LOG_FILE_NAME: &str = "signing_policy_log.json";
+
+/// Instruction types a policy can refuse outright
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InstructionKind {
+    /// Token `SetAuthority`, stake `Authorize` and nonce `AuthorizeNonceAccount`
+    SetAuthority,
+    /// Token `Approve` and `ApproveChecked`, which let a delegate spend
+    Approve,
+    CloseAccount,
+    /// System `Assign`, which hands an account to another program
+    Assign,
+}
+
+impl fmt::Display for InstructionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            InstructionKind::SetAuthority => "changing an authority",
+            InstructionKind::Approve => "approving a delegate",
+            InstructionKind::CloseAccount => "closing a token account",
+            InstructionKind::Assign => "assigning an account to another program",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Spending limits for one token, in base units (lamports for SOL)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenLimit {
+    /// Mint address; SOL uses the native mint
+    pub mint: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_transaction: Option<u64>,
+    /// Total allowed over any rolling 24 hours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily: Option<u64>,
+}
+
+/// Rules every transaction must pass before this device signs it.
+/// The default allows everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SigningPolicy {
+    pub limits: Vec<TokenLimit>,
+    /// When not empty, transfers may only go to these wallets or their token accounts.
+    /// Jito tips are always allowed.
+    pub allowed_recipients: Vec<String>,
+    pub denied_recipients: Vec<String>,
+    pub blocked_instructions: Vec<InstructionKind>,
+    pub block_unknown_programs: bool,
+}
+
+impl SigningPolicy {
+    fn limit(&self, mint: &Pubkey) -> Option<&TokenLimit> {
+        let mint = mint.to_string();
+        self.limits.iter().find(|limit| limit.mint == mint)
+    }
+
+    fn checks_recipients(&self) -> bool {
+        !self.allowed_recipients.is_empty() || !self.denied_recipients.is_empty()
+    }
+
+    fn limits_tokens(&self) -> bool {
+        let native = spl_token::native_mint::id().to_string();
+        self.limits.iter().any(|limit| limit.mint != native)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+    TransactionLimit { mint: String, amount: u64, limit: u64 },
+    DailyLimit { mint: String, spent: u64, amount: u64, limit: u64 },
+    RecipientNotAllowed(String),
+    RecipientDenied(String),
+    BlockedInstruction(InstructionKind),
+    UnknownProgram(String),
+    /// A transfer whose token or recipient can't be read from the message alone
+    Unverifiable(String),
+    /// The stored policy or spending log couldn't be read, so nothing is signed
+    PolicyUnavailable(String),
+}
+
+fn token_name(mint: &str) -> String {
+    if mint == spl_token::native_mint::id().to_string() {
+        "SOL".to_string()
+    } else {
+        mint.to_string()
+    }
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolicyViolation::TransactionLimit { mint, amount, limit } => write!(
+                f,
+                "{} units of {} is over the per-transaction limit of {}",
+                amount, token_name(mint), limit
+            ),
+            PolicyViolation::DailyLimit { mint, spent, amount, limit } => write!(
+                f,
+                "{} units of {} on top of {} sent in the last 24 hours is over the daily limit of {}",
+                amount, token_name(mint), spent, limit
+            ),
+            PolicyViolation::RecipientNotAllowed(address) => write!(f, "{} is not an allowed recipient", address),
+            PolicyViolation::RecipientDenied(address) => write!(f, "{} is a denied recipient", address),
+            PolicyViolation::BlockedInstruction(kind) => write!(f, "{} is not allowed", kind),
+            PolicyViolation::UnknownProgram(program) => write!(f, "unknown program {}", program),
+            PolicyViolation::Unverifiable(reason) => write!(f, "can't verify {}", reason),
+            PolicyViolation::PolicyUnavailable(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+/// Returned instead of a signature when a transaction breaks the policy.
+/// Pass it to `PolicyEngine::approve_override` to let the same request through once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyError {
+    /// sha256 of the signer and what the refused message(s) do, hex
+    pub effects_hash: String,
+    pub violations: Vec<PolicyViolation>,
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reasons: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+        write!(f, "Blocked by signing policy: {}", reasons.join("; "))
+    }
+}
+
+impl std::error::Error for PolicyError {}
+
+impl From<PolicyError> for String {
+    fn from(e: PolicyError) -> Self {
+        e.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendRecord {
+    pub mint: String,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+/// A policy violation someone chose to let through
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverrideRecord {
+    #[serde(alias = "message_hash")]
+    pub effects_hash: String,
+    pub approved_by: String,
+    pub reason: String,
+    /// What the override let through
+    pub violations: Vec<String>,
+    pub approved_at: i64,
+    /// The override can't be used after this time
+    #[serde(default)]
+    pub expires_at: i64,
+    /// Set once the approved transaction has been signed; each override works once
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub used_at: Option<i64>,
+}
+
+/// A saved change to the policy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyChange {
+    pub previous: SigningPolicy,
+    pub policy: SigningPolicy,
+    pub changed_at: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+struct PolicyLog {
+    spends: Vec<SpendRecord>,
+    overrides: Vec<OverrideRecord>,
+    changes: Vec<PolicyChange>,
+}
+
+/// What a checked signing request is allowed to do, applied once it is signed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Approval {
+    spends: Vec<SpendRecord>,
+    override_hash: Option<String>,
+}
+
+/// A token or SOL movement out of the signer's control
+struct Transfer {
+    /// `None` for a plain token `Transfer`, which doesn't name its mint
+    mint: Option<Pubkey>,
+    token_program: Pubkey,
+    amount: u64,
+    /// `None` when the account comes from an address lookup table
+    recipient: Option<Pubkey>,
+}
+
+/// What a message does that the policy cares about
+#[derive(Default)]
+struct Effects {
+    transfers: Vec<Transfer>,
+    blocked: Vec<InstructionKind>,
+    unknown_programs: Vec<Pubkey>,
+}
+
+fn effects(preview: &TransactionPreview, signer: &Pubkey) -> Effects {
+    let mut effects = Effects::default();
+    for instruction in &preview.instructions {
+        match instruction {
+            InstructionSummary::SolTransfer { to, lamports, authority, .. }
+            | InstructionSummary::StakeWithdraw { to, lamports, authority, .. }
+                if authority.is(signer) =>
+            {
+                effects.transfers.push(Transfer::sol(*lamports, to.key()));
+            }
+            // New accounts co-sign their creation, so they only count towards limits
+            InstructionSummary::CreateAccount { funder, lamports, .. } if funder.is(signer) => {
+                effects.transfers.push(Transfer::sol(*lamports, Some(*signer)));
+            }
+            InstructionSummary::TokenTransfer { program, destination, authority, mint, amount, .. }
+                if authority.is(signer) =>
+            {
+                effects.transfers.push(Transfer {
+                    mint: mint.and_then(|m| m.key()),
+                    token_program: *program,
+                    amount: *amount,
+                    recipient: destination.key(),
+                });
+            }
+            InstructionSummary::Assign { .. } => effects.blocked.push(InstructionKind::Assign),
+            InstructionSummary::AuthorizeNonce { .. }
+            | InstructionSummary::TokenSetAuthority { .. }
+            | InstructionSummary::StakeAuthorize { .. } => effects.blocked.push(InstructionKind::SetAuthority),
+            InstructionSummary::TokenApprove { .. } => effects.blocked.push(InstructionKind::Approve),
+            InstructionSummary::CloseTokenAccount { .. } => effects.blocked.push(InstructionKind::CloseAccount),
+            InstructionSummary::Unknown { program, .. } => effects.unknown_programs.push(*program),
+            _ => {}
+        }
+    }
+    effects
+}
+
+impl Transfer {
+    fn sol(amount: u64, recipient: Option<Pubkey>) -> Self {
+        Self {
+            mint: Some(spl_token::native_mint::id()),
+            token_program: system_program::id(),
+            amount,
+            recipient,
+        }
+    }
+
+    /// Whether the recipient is `wallet` itself or its associated token account
+    fn goes_to(&self, wallet: &Pubkey) -> bool {
+        match (self.recipient, self.mint) {
+            (Some(recipient), _) if recipient == *wallet => true,
+            (Some(recipient), Some(mint)) if self.token_program != system_program::id() => {
+                recipient == get_associated_token_address_with_program_id(wallet, &mint, &self.token_program)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// sha256 of `signer` and what each message does. Blockhashes and instruction order are
+/// left out, so a request rebuilt before signing keeps the hash its override was approved for.
+fn effects_hash(signer: &Pubkey, messages: &[Effects]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(signer.as_ref());
+    for effects in messages {
+        let mut lines: Vec<String> = effects
+            .transfers
+            .iter()
+            .map(|t| format!("transfer {:?} {} {} {:?}", t.mint, t.token_program, t.amount, t.recipient))
+            .collect();
+        lines.extend(effects.blocked.iter().map(|kind| format!("blocked {:?}", kind)));
+        lines.extend(effects.unknown_programs.iter().map(|program| format!("program {}", program)));
+        lines.sort();
+        hasher.update(lines.join("\n"));
+        hasher.update([0u8]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn token_2022_id() -> Pubkey {
+    Pubkey::from_str(TOKEN_2022_PROGRAM).unwrap()
+}
+
+fn parse_addresses(addresses: &[String]) -> Vec<Pubkey> {
+    addresses.iter().filter_map(|a| Pubkey::from_str(a.trim()).ok()).collect()
+}
+
+/// Applies the device's signing policy and keeps its spending and override log.
+/// The policy is stored in `signing_policy.json`, the log in `signing_policy_log.json`.
+pub struct PolicyEngine {
+    policy: SigningPolicy,
+    log: PolicyLog,
+    /// The most recent refusal, kept so it can be approved from the policy screen
+    last_refusal: Option<PolicyError>,
+    /// Whether changes are written back to storage
+    persist: bool,
+    /// Why the stored policy couldn't be read; nothing is signed until it is replaced
+    policy_error: Option<String>,
+    /// Why the stored spending log couldn't be read
+    log_error: Option<String>,
+}
+
+impl PolicyEngine {
+    /// An engine that keeps its log in memory only
+    pub fn new(policy: SigningPolicy) -> Self {
+        Self {
+            policy,
+            log: PolicyLog::default(),
+            last_refusal: None,
+            persist: false,
+            policy_error: None,
+            log_error: None,
+        }
+    }
+
+    /// Load the policy and log from app storage. A file that exists but can't be read
+    /// is reported by `load_errors` and blocks signing instead of counting as no policy.
+    pub fn load() -> Self {
+        #[cfg(feature = "web")]
+        let (policy, log) = (
+            storage::try_read_web_document(POLICY_FILE_NAME, Document::SigningPolicy),
+            storage::try_read_web_document(POLICY_LOG_FILE_NAME, Document::PolicyLog),
+        );
+
+        #[cfg(not(feature = "web"))]
+        let (policy, log) = {
+            let dir = storage::get_storage_dir_simple();
+            (
+                storage::try_read_document(&format!("{}/{}", dir, POLICY_FILE_NAME), Document::SigningPolicy),
+                storage::try_read_document(&format!("{}/{}", dir, POLICY_LOG_FILE_NAME), Document::PolicyLog),
+            )
+        };
+
+        let mut engine = Self::new(SigningPolicy::default());
+        engine.persist = true;
+        match policy {
+            Ok(policy) => engine.policy = policy.unwrap_or_default(),
+            Err(e) => engine.policy_error = Some(e),
+        }
+        match log {
+            Ok(log) => engine.log = log.unwrap_or_default(),
+            Err(e) => engine.log_error = Some(e),
+        }
+        engine
+    }
+
+    /// Why the stored policy or log couldn't be read, if it couldn't
+    pub fn load_errors(&self) -> Vec<String> {
+        self.policy_error.iter().chain(self.log_error.iter()).cloned().collect()
+    }
+
+    pub fn policy(&self) -> &SigningPolicy {
+        &self.policy
+    }
+
+    /// Replace the policy once `passphrase` matches the keystore, and log the change.
+    /// This also replaces a policy or log that couldn't be read; the unreadable files
+    /// were already copied aside when they were loaded.
+    pub fn set_policy(&mut self, policy: SigningPolicy, passphrase: &str, now: i64) -> Result<(), String> {
+        keystore::verify_passphrase(passphrase).map_err(|e| e.to_string())?;
+        if self.log_error.is_some() {
+            log::warn!("⚠️ Starting a new signing policy log in place of the unreadable one");
+            self.log = PolicyLog::default();
+            self.log_error = None;
+        }
+        log::info!("📝 Signing policy changed");
+        let previous = std::mem::replace(&mut self.policy, policy);
+        self.log.changes.push(PolicyChange {
+            previous,
+            policy: self.policy.clone(),
+            changed_at: now,
+        });
+        self.save(POLICY_LOG_FILE_NAME, Document::PolicyLog, &self.log)?;
+        self.save(POLICY_FILE_NAME, Document::SigningPolicy, &self.policy)?;
+        self.policy_error = None;
+        Ok(())
+    }
+
+    pub fn changes(&self) -> &[PolicyChange] {
+        &self.log.changes
+    }
+
+    pub fn overrides(&self) -> &[OverrideRecord] {
+        &self.log.overrides
+    }
+
+    pub fn last_refusal(&self) -> Option<&PolicyError> {
+        self.last_refusal.as_ref()
+    }
+
+    /// Amount of `mint` sent in the 24 hours before `now`
+    pub fn spent_since(&self, mint: &Pubkey, now: i64) -> u64 {
+        let mint = mint.to_string();
+        self.log
+            .spends
+            .iter()
+            .filter(|spend| spend.mint == mint && spend.timestamp > now - WINDOW_SECS)
+            .map(|spend| spend.amount)
+            .sum()
+    }
+
+    /// Check messages `signer` is about to sign together, as one request
+    pub fn check(&self, signer: &Pubkey, messages: &[Vec<u8>], now: i64) -> Result<Approval, PolicyError> {
+        let load_errors = self.load_errors();
+        if !load_errors.is_empty() {
+            return Err(PolicyError {
+                effects_hash: String::new(),
+                violations: load_errors.into_iter().map(PolicyViolation::PolicyUnavailable).collect(),
+            });
+        }
+
+        let allowed = parse_addresses(&self.policy.allowed_recipients);
+        let denied = parse_addresses(&self.policy.denied_recipients);
+        let jito_tips: Vec<Pubkey> = JITO_TIP_ACCOUNTS.iter().filter_map(|a| Pubkey::from_str(a).ok()).collect();
+        let mut violations = Vec::new();
+        let mut totals: HashMap<Pubkey, u64> = HashMap::new();
+        let mut all_effects = Vec::new();
+
+        // Anything that isn't a transaction, such as an off-chain message, can't move funds
+        for preview in messages.iter().filter_map(|bytes| TransactionPreview::from_bytes(bytes)) {
+            let effects = effects(&preview, signer);
+            let mut per_transaction: HashMap<Pubkey, u64> = HashMap::new();
+
+            for transfer in &effects.transfers {
+                let Some(mint) = transfer.mint else {
+                    if self.policy.limits_tokens() || self.policy.checks_recipients() {
+                        violations.push(PolicyViolation::Unverifiable(
+                            "a token transfer that doesn't name its mint".to_string(),
+                        ));
+                    }
+                    continue;
+                };
+                *per_transaction.entry(mint).or_default() += transfer.amount;
+
+                if transfer.recipient == Some(*signer) {
+                    continue;
+                }
+                let Some(recipient) = transfer.recipient else {
+                    if self.policy.checks_recipients() {
+                        violations.push(PolicyViolation::Unverifiable(
+                            "a recipient loaded from an address lookup table".to_string(),
+                        ));
+                    }
+                    continue;
+                };
+                if denied.iter().any(|wallet| transfer.goes_to(wallet)) {
+                    violations.push(PolicyViolation::RecipientDenied(recipient.to_string()));
+                } else if !allowed.is_empty()
+                    && !jito_tips.contains(&recipient)
+                    && !allowed.iter().any(|wallet| transfer.goes_to(wallet))
+                {
+                    violations.push(PolicyViolation::RecipientNotAllowed(recipient.to_string()));
+                }
+            }
+
+            for (mint, amount) in per_transaction {
+                if let Some(limit) = self.policy.limit(&mint).and_then(|l| l.per_transaction) {
+                    if amount > limit {
+                        violations.push(PolicyViolation::TransactionLimit { mint: mint.to_string(), amount, limit });
+                    }
+                }
+                *totals.entry(mint).or_default() += amount;
+            }
+
+            for kind in &effects.blocked {
+                if self.policy.blocked_instructions.contains(kind) {
+                    violations.push(PolicyViolation::BlockedInstruction(*kind));
+                }
+            }
+            if self.policy.block_unknown_programs {
+                violations.extend(effects.unknown_programs.iter().map(|p| PolicyViolation::UnknownProgram(p.to_string())));
+            }
+            // An undecoded token instruction, such as a confidential transfer, may move tokens
+            let token_programs = [spl_token::id(), token_2022_id()];
+            if (self.policy.limits_tokens() || self.policy.checks_recipients())
+                && effects.unknown_programs.iter().any(|p| token_programs.contains(p))
+            {
+                violations.push(PolicyViolation::Unverifiable(
+                    "a token instruction this wallet can't decode".to_string(),
+                ));
+            }
+            all_effects.push(effects);
+        }
+
+        let mut spends = Vec::new();
+        for (mint, amount) in totals {
+            let Some(limit) = self.policy.limit(&mint).and_then(|l| l.daily) else { continue };
+            let spent = self.spent_since(&mint, now);
+            if spent.saturating_add(amount) > limit {
+                violations.push(PolicyViolation::DailyLimit { mint: mint.to_string(), spent, amount, limit });
+            }
+            spends.push(SpendRecord { mint: mint.to_string(), amount, timestamp: now });
+        }
+        violations.dedup();
+
+        if violations.is_empty() {
+            return Ok(Approval { spends, override_hash: None });
+        }
+        let effects_hash = effects_hash(signer, &all_effects);
+        let approved = self
+            .log
+            .overrides
+            .iter()
+            .any(|o| o.effects_hash == effects_hash && o.used_at.is_none() && now <= o.expires_at);
+        if approved {
+            log::warn!("⚠️ Signing under an approved policy override: {}", effects_hash);
+            Ok(Approval { spends, override_hash: Some(effects_hash) })
+        } else {
+            Err(PolicyError { effects_hash, violations })
+        }
+    }
+
+    /// Let a request doing the same as the one refused with `error` be signed once within
+    /// `OVERRIDE_TTL_SECS`, and record who allowed it. `passphrase` must match the keystore.
+    pub fn approve_override(
+        &mut self,
+        error: &PolicyError,
+        approved_by: &str,
+        reason: &str,
+        passphrase: &str,
+        now: i64,
+    ) -> Result<(), String> {
+        if approved_by.trim().is_empty() || reason.trim().is_empty() {
+            return Err("An override needs an approver and a reason".to_string());
+        }
+        keystore::verify_passphrase(passphrase).map_err(|e| e.to_string())?;
+        if error.violations.iter().any(|v| matches!(v, PolicyViolation::PolicyUnavailable(_))) {
+            return Err("The signing policy couldn't be read. Save a policy before approving overrides".to_string());
+        }
+        log::info!("📝 Policy override approved by {}: {}", approved_by.trim(), reason.trim());
+        self.log.overrides.push(OverrideRecord {
+            effects_hash: error.effects_hash.clone(),
+            approved_by: approved_by.trim().to_string(),
+            reason: reason.trim().to_string(),
+            violations: error.violations.iter().map(|v| v.to_string()).collect(),
+            approved_at: now,
+            expires_at: now + OVERRIDE_TTL_SECS,
+            used_at: None,
+        });
+        if self.last_refusal.as_ref() == Some(error) {
+            self.last_refusal = None;
+        }
+        self.save(POLICY_LOG_FILE_NAME, Document::PolicyLog, &self.log)
+    }
+
+    /// Count a checked request against the limits and use up its override before it is
+    /// signed, so requests signed at the same time can't each pass the same check.
+    /// Follow with `record` once it is signed, or `release` if signing fails.
+    pub fn reserve(&mut self, approval: &Approval, now: i64) {
+        self.log.spends.retain(|spend| spend.timestamp > now - WINDOW_SECS);
+        self.log.spends.extend(approval.spends.iter().cloned());
+        if let Some(hash) = &approval.override_hash {
+            if let Some(record) = self
+                .log
+                .overrides
+                .iter_mut()
+                .find(|o| &o.effects_hash == hash && o.used_at.is_none())
+            {
+                record.used_at = Some(now);
+            }
+        }
+    }
+
+    /// Undo `reserve` for a request that wasn't signed
+    pub fn release(&mut self, approval: &Approval) {
+        for spend in &approval.spends {
+            if let Some(index) = self.log.spends.iter().rposition(|s| s == spend) {
+                self.log.spends.remove(index);
+            }
+        }
+        if let Some(hash) = &approval.override_hash {
+            if let Some(record) = self
+                .log
+                .overrides
+                .iter_mut()
+                .rev()
+                .find(|o| &o.effects_hash == hash && o.used_at.is_some())
+            {
+                record.used_at = None;
+            }
+        }
+    }
+
+    /// Save a reserved request once it has actually been signed
+    pub fn record(&mut self, approval: &Approval) {
+        if approval.spends.is_empty() && approval.override_hash.is_none() {
+            return;
+        }
+        if let Err(e) = self.save(POLICY_LOG_FILE_NAME, Document::PolicyLog, &self.log) {
+            log::error!("❌ {}", e);
+        }
+    }
+
+    fn save<T: Serialize>(&self, name: &str, document: Document, value: &T) -> Result<(), String> {
+        if !self.persist {
+            return Ok(());
+        }
+
+        #[cfg(feature = "web")]
+        {
+            storage::write_web_document(name, document, value)
+        }
+
+        #[cfg(not(feature = "web"))]
+        {
+            let path = format!("{}/{}", storage::get_storage_dir_simple(), name);
+            storage::write_document(&path, document, value)
+        }
+    }
+}
+
+static SHARED_ENGINE: once_cell::sync::Lazy<Arc<Mutex<PolicyEngine>>> =
+    once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(PolicyEngine::load())));
+
+/// The policy engine for this device, shared by every signer
+pub fn shared_engine() -> Arc<Mutex<PolicyEngine>> {
+    SHARED_ENGINE.clone()
+}
+
+/// Wraps any signer and refuses to sign transactions that break the policy.
+/// Off-chain messages are passed through, since they can't move funds.
+pub struct PolicySigner<S> {
+    inner: S,
+    engine: Arc<Mutex<PolicyEngine>>,
+}
+
+impl<S: TransactionSigner> PolicySigner<S> {
+    pub fn new(inner: S, engine: Arc<Mutex<PolicyEngine>>) -> Self {
+        Self { inner, engine }
+    }
+
+    /// Guard `inner` with this device's stored policy
+    pub fn shared(inner: S) -> Self {
+        Self::new(inner, shared_engine())
+    }
+
+    async fn sign_checked(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        let signer = Pubkey::from_str(&self.inner.get_public_key().await?)?;
+        let approval = {
+            let mut engine = self.engine.lock().map_err(|_| "Signing policy is unavailable")?;
+            let now = chrono::Utc::now().timestamp();
+            match engine.check(&signer, messages, now) {
+                Ok(approval) => {
+                    engine.reserve(&approval, now);
+                    approval
+                }
+                Err(e) => {
+                    log::warn!("🛡️ {}", e);
+                    engine.last_refusal = Some(e.clone());
+                    return Err(e.into());
+                }
+            }
+        };
+
+        let signed = self.inner.sign_messages(messages).await;
+
+        let mut engine = self.engine.lock().map_err(|_| "Signing policy is unavailable")?;
+        match signed {
+            Ok(signatures) => {
+                engine.record(&approval);
+                Ok(signatures)
+            }
+            Err(e) => {
+                engine.release(&approval);
+                Err(e)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<S: TransactionSigner> TransactionSigner for PolicySigner<S> {
+    async fn get_public_key(&self) -> Result<String, Box<dyn Error>> {
+        self.inner.get_public_key().await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.sign_checked(&[message.to_vec()])
+            .await?
+            .pop()
+            .ok_or_else(|| "Signer returned no signature".into())
+    }
+
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        self.sign_checked(messages).await
+    }
+
+    async fn sign_offchain_message(&self, message: &OffchainMessage) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.inner.sign_offchain_message(message).await
+    }
+
+    fn get_name(&self) -> String {
+        self.inner.get_name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::SignerType;
+    use crate::wallet::Wallet;
+    use solana_sdk::{
+        hash::Hash,
+        instruction::{AccountMeta, Instruction},
+        message::{Message, VersionedMessage},
+        system_instruction,
+    };
+
+    const SOL: u64 = 1_000_000_000;
+    /// What `keystore::unlock_test_session` unlocks with
+    const PASSPHRASE: &str = "test passphrase";
+
+    fn message(payer: &Pubkey, instructions: &[Instruction]) -> Vec<u8> {
+        let mut message = Message::new(instructions, Some(payer));
+        message.recent_blockhash = Hash::new_unique();
+        VersionedMessage::Legacy(message).serialize()
+    }
+
+    fn sol_limit(per_transaction: u64, daily: u64) -> TokenLimit {
+        TokenLimit {
+            mint: spl_token::native_mint::id().to_string(),
+            per_transaction: Some(per_transaction),
+            daily: Some(daily),
+        }
+    }
+
+    #[test]
+    fn test_limits_lists_and_blocked_instructions() {
+        let signer = Pubkey::new_unique();
+        let friend = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let engine = PolicyEngine::new(SigningPolicy {
+            limits: vec![sol_limit(2 * SOL, 3 * SOL)],
+            allowed_recipients: vec![friend.to_string()],
+            blocked_instructions: vec![InstructionKind::Assign],
+            block_unknown_programs: true,
+            ..Default::default()
+        });
+
+        let ok = message(&signer, &[system_instruction::transfer(&signer, &friend, SOL)]);
+        assert!(engine.check(&signer, &[ok], 0).is_ok());
+
+        let too_much = message(&signer, &[system_instruction::transfer(&signer, &friend, 5 * SOL / 2)]);
+        let violations = engine.check(&signer, &[too_much], 0).unwrap_err().violations;
+        assert!(matches!(violations[0], PolicyViolation::TransactionLimit { amount, .. } if amount == 5 * SOL / 2));
+
+        let unknown = Pubkey::new_unique();
+        let bad = message(
+            &signer,
+            &[
+                system_instruction::transfer(&signer, &stranger, 1),
+                system_instruction::assign(&signer, &unknown),
+                Instruction::new_with_bytes(unknown, &[1, 2, 3], vec![]),
+            ],
+        );
+        assert_eq!(
+            engine.check(&signer, &[bad], 0).unwrap_err().violations,
+            vec![
+                PolicyViolation::RecipientNotAllowed(stranger.to_string()),
+                PolicyViolation::BlockedInstruction(InstructionKind::Assign),
+                PolicyViolation::UnknownProgram(unknown.to_string()),
+            ]
+        );
+
+        // Off-chain messages and transfers made by other signers are not this signer's business
+        assert!(engine.check(&signer, &[b"\xffsolana offchain hello".to_vec()], 0).is_ok());
+        let theirs = message(&signer, &[system_instruction::transfer(&stranger, &stranger, 10 * SOL)]);
+        assert!(engine.check(&signer, &[theirs], 0).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_daily_limit_and_override() {
+        let wallet = Wallet::new("Team phone".to_string());
+        let key = Pubkey::from_str(&wallet.get_public_key()).unwrap();
+        let friend = Pubkey::new_unique();
+        let engine = Arc::new(Mutex::new(PolicyEngine::new(SigningPolicy {
+            limits: vec![sol_limit(2 * SOL, 3 * SOL)],
+            ..Default::default()
+        })));
+        let signer = PolicySigner::new(SignerType::from_wallet(wallet), engine.clone());
+
+        let first = message(&key, &[system_instruction::transfer(&key, &friend, 2 * SOL)]);
+        signer.sign_message(&first).await.unwrap();
+        assert_eq!(engine.lock().unwrap().spent_since(&spl_token::native_mint::id(), chrono::Utc::now().timestamp()), 2 * SOL);
+
+        let second = message(&key, &[system_instruction::transfer(&key, &friend, 2 * SOL)]);
+        let error = signer.sign_message(&second).await.unwrap_err();
+        let error = error.downcast::<PolicyError>().unwrap();
+        assert!(matches!(error.violations[0], PolicyViolation::DailyLimit { spent, .. } if spent == 2 * SOL));
+        assert_eq!(engine.lock().unwrap().last_refusal(), Some(&*error));
+
+        // Spends fall out of the rolling window after a day
+        let tomorrow = chrono::Utc::now().timestamp() + WINDOW_SECS + 1;
+        assert!(engine.lock().unwrap().check(&key, &[second.clone()], tomorrow).is_ok());
+
+        keystore::unlock_test_session();
+        let now = chrono::Utc::now().timestamp();
+        assert!(engine.lock().unwrap().approve_override(&error, "Ops lead", "", PASSPHRASE, now).is_err());
+        assert!(engine.lock().unwrap().approve_override(&error, "Ops lead", "Payroll run", "guess", now).is_err());
+        engine.lock().unwrap().approve_override(&error, "Ops lead", "Payroll run", PASSPHRASE, now).unwrap();
+        signer.sign_message(&second).await.unwrap();
+        {
+            let engine = engine.lock().unwrap();
+            assert_eq!(engine.overrides().len(), 1);
+            assert_eq!(engine.overrides()[0].approved_by, "Ops lead");
+            assert!(engine.overrides()[0].used_at.is_some());
+            assert!(engine.last_refusal().is_none());
+        }
+
+        // Each override covers one signing
+        assert!(signer.sign_message(&second).await.is_err());
+    }
+
+    #[test]
+    fn test_override_survives_rebuild_but_not_changes() {
+        let signer = Pubkey::new_unique();
+        let friend = Pubkey::new_unique();
+        let mut engine = PolicyEngine::new(SigningPolicy {
+            limits: vec![sol_limit(SOL, 10 * SOL)],
+            ..Default::default()
+        });
+        let payment = |lamports| message(&signer, &[system_instruction::transfer(&signer, &friend, lamports)]);
+
+        keystore::unlock_test_session();
+        let error = engine.check(&signer, &[payment(2 * SOL)], 0).unwrap_err();
+        engine.approve_override(&error, "Ops lead", "Supplier invoice", PASSPHRASE, 0).unwrap();
+
+        // The send flow rebuilds the message with a fresh blockhash before signing
+        let rebuilt = payment(2 * SOL);
+        let approval = engine.check(&signer, &[rebuilt], 60).unwrap();
+        assert_eq!(approval.override_hash.as_deref(), Some(error.effects_hash.as_str()));
+
+        // A different amount, recipient or signer is not covered
+        assert!(engine.check(&signer, &[payment(3 * SOL)], 60).is_err());
+        let stranger = message(&signer, &[system_instruction::transfer(&signer, &Pubkey::new_unique(), 2 * SOL)]);
+        assert!(engine.check(&signer, &[stranger], 60).is_err());
+        let other = Pubkey::new_unique();
+        let theirs = message(&other, &[system_instruction::transfer(&other, &friend, 2 * SOL)]);
+        assert!(engine.check(&other, &[theirs], 60).is_err());
+
+        // Overrides expire
+        assert!(engine.check(&signer, &[payment(2 * SOL)], OVERRIDE_TTL_SECS + 1).is_err());
+    }
+
+    #[test]
+    fn test_allowlist_lets_jito_tips_through() {
+        let signer = Pubkey::new_unique();
+        let friend = Pubkey::new_unique();
+        let engine = PolicyEngine::new(SigningPolicy {
+            limits: vec![sol_limit(SOL, 10 * SOL)],
+            allowed_recipients: vec![friend.to_string()],
+            ..Default::default()
+        });
+
+        // A transfer as built with Jito enabled: the payment plus a tip to each tip account
+        let mut instructions = vec![system_instruction::transfer(&signer, &friend, SOL / 2)];
+        for tip_account in JITO_TIP_ACCOUNTS {
+            let tip_account = Pubkey::from_str(tip_account).unwrap();
+            instructions.push(system_instruction::transfer(&signer, &tip_account, 100_000));
+        }
+        assert!(engine.check(&signer, &[message(&signer, &instructions)], 0).is_ok());
+
+        // Tips still count towards limits
+        instructions[0] = system_instruction::transfer(&signer, &friend, SOL);
+        let violations = engine.check(&signer, &[message(&signer, &instructions)], 0).unwrap_err().violations;
+        assert!(matches!(violations[0], PolicyViolation::TransactionLimit { amount, .. } if amount == SOL + 200_000));
+    }
+
+    #[test]
+    fn test_unreadable_policy_blocks_signing() {
+        let signer = Pubkey::new_unique();
+        let mut engine = PolicyEngine::new(SigningPolicy::default());
+        engine.policy_error = Some("Your signing policy could not be read".to_string());
+        let payment = message(&signer, &[system_instruction::transfer(&signer, &Pubkey::new_unique(), 1)]);
+
+        keystore::unlock_test_session();
+        let error = engine.check(&signer, &[payment.clone()], 0).unwrap_err();
+        assert!(matches!(error.violations[0], PolicyViolation::PolicyUnavailable(_)));
+        assert!(engine.approve_override(&error, "Ops lead", "Urgent", PASSPHRASE, 0).is_err());
+
+        // Saving a policy replaces the unreadable one, and only with the passphrase
+        assert!(engine.set_policy(SigningPolicy::default(), "guess", 0).is_err());
+        assert_eq!(engine.load_errors().len(), 1);
+        engine.set_policy(SigningPolicy::default(), PASSPHRASE, 0).unwrap();
+        assert!(engine.load_errors().is_empty());
+        assert_eq!(engine.changes().len(), 1);
+        assert!(engine.check(&signer, &[payment], 0).is_ok());
+    }
+
+    #[test]
+    fn test_reserved_spend_blocks_concurrent_request_until_released() {
+        let signer = Pubkey::new_unique();
+        let friend = Pubkey::new_unique();
+        let mut engine = PolicyEngine::new(SigningPolicy {
+            limits: vec![sol_limit(2 * SOL, 3 * SOL)],
+            ..Default::default()
+        });
+        let payment = message(&signer, &[system_instruction::transfer(&signer, &friend, 2 * SOL)]);
+
+        // A second request checked while the first is still being signed sees its spend
+        let approval = engine.check(&signer, &[payment.clone()], 0).unwrap();
+        engine.reserve(&approval, 0);
+        assert!(engine.check(&signer, &[payment.clone()], 0).is_err());
+
+        // If signing fails the spend is given back
+        engine.release(&approval);
+        assert_eq!(engine.spent_since(&spl_token::native_mint::id(), 0), 0);
+        assert!(engine.check(&signer, &[payment], 0).is_ok());
+    }
+
+    #[test]
+    fn test_token_2022_fee_transfers_count_against_limits() {
+        let signer = Pubkey::new_unique();
+        let friend = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_2022 = token_2022_id();
+        let engine = PolicyEngine::new(SigningPolicy {
+            limits: vec![TokenLimit { mint: mint.to_string(), per_transaction: Some(1_000), daily: None }],
+            ..Default::default()
+        });
+        let source = get_associated_token_address_with_program_id(&signer, &mint, &token_2022);
+        let destination = get_associated_token_address_with_program_id(&friend, &mint, &token_2022);
+        let accounts = vec![
+            AccountMeta::new(source, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(signer, true),
+        ];
+
+        // TransferCheckedWithFee: amount, decimals, fee
+        let mut data = vec![26, 1];
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+        data.push(6);
+        data.extend_from_slice(&50u64.to_le_bytes());
+        let with_fee = Instruction::new_with_bytes(token_2022, &data, accounts.clone());
+        let violations = engine.check(&signer, &[message(&signer, &[with_fee])], 0).unwrap_err().violations;
+        assert!(matches!(violations[0], PolicyViolation::TransactionLimit { amount: 5_000, .. }));
+
+        // Token instructions that can't be decoded are refused while token limits apply
+        let confidential = Instruction::new_with_bytes(token_2022, &[27, 7], accounts);
+        let violations = engine.check(&signer, &[message(&signer, &[confidential])], 0).unwrap_err().violations;
+        assert!(matches!(violations[0], PolicyViolation::Unverifiable(_)));
+    }
+}
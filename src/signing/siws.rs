@@ -0,0 +1,511 @@
+// src/signing/siws.rs
+use crate::signing::TransactionSigner;
+use crate::wallet::WalletInfo;
+use chrono::{DateTime, SecondsFormat, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use rand::{distributions::Alphanumeric, Rng};
+use std::fmt;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+pub const SIWS_VERSION: &str = "1";
+const MIN_NONCE_LEN: usize = 8;
+/// How long a message built by `SiwsMessage::new` stays valid
+const DEFAULT_VALIDITY_MINUTES: i64 = 10;
+
+/// Field labels in the order they must appear
+const FIELDS: [&str; 8] = [
+    "URI",
+    "Version",
+    "Chain ID",
+    "Nonce",
+    "Issued At",
+    "Expiration Time",
+    "Not Before",
+    "Request ID",
+];
+const RESOURCES: &str = "Resources:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SiwsError {
+    /// Text is not a Sign-In With Solana message
+    Malformed(String),
+    InvalidField { field: &'static str, reason: String },
+    AddressMismatch { expected: String, actual: String },
+    DomainMismatch { expected: String, actual: String },
+    NonceMismatch,
+    Expired,
+    NotYetValid,
+    InvalidSignature,
+    Signer(String),
+}
+
+impl fmt::Display for SiwsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SiwsError::Malformed(reason) => write!(f, "Not a sign-in message: {}", reason),
+            SiwsError::InvalidField { field, reason } => write!(f, "Invalid {}: {}", field, reason),
+            SiwsError::AddressMismatch { expected, actual } => {
+                write!(f, "Message is for {} but the signer is {}", actual, expected)
+            }
+            SiwsError::DomainMismatch { expected, actual } => {
+                write!(f, "Message is for {} but this is {}", actual, expected)
+            }
+            SiwsError::NonceMismatch => write!(f, "Nonce does not match the one that was issued"),
+            SiwsError::Expired => write!(f, "Sign-in message has expired"),
+            SiwsError::NotYetValid => write!(f, "Sign-in message is not valid yet"),
+            SiwsError::InvalidSignature => write!(f, "Signature does not match the message and address"),
+            SiwsError::Signer(reason) => write!(f, "Signing failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SiwsError {}
+
+impl From<SiwsError> for String {
+    fn from(e: SiwsError) -> Self {
+        e.to_string()
+    }
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> SiwsError {
+    SiwsError::InvalidField { field, reason: reason.into() }
+}
+
+fn parse_time(field: &'static str, value: &str) -> Result<DateTime<Utc>, SiwsError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| invalid(field, e.to_string()))
+}
+
+fn format_time(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_address(field: &'static str, value: &str) -> Result<VerifyingKey, SiwsError> {
+    let bytes: [u8; 32] = bs58::decode(value)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid(field, "not a base58 public key"))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| invalid(field, e.to_string()))
+}
+
+/// A random alphanumeric nonce for a server to hand out before sign-in
+pub fn generate_nonce() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect()
+}
+
+/// A Sign-In With Solana message.
+///
+/// `to_string()` gives the exact text that gets signed; `parse` reads it back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SiwsMessage {
+    /// Host (and optional port) of the site asking for the sign-in, e.g. `dash.example.com`
+    pub domain: String,
+    pub address: String,
+    /// Human-readable line shown to the user; cannot contain newlines
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub nonce: Option<String>,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    pub not_before: Option<DateTime<Utc>>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+impl SiwsMessage {
+    /// A message issued now, with a fresh nonce and a short expiry.
+    /// Fill in `statement`, `uri` and `resources` before signing.
+    pub fn new(domain: &str, address: &str) -> Self {
+        let now = Utc::now();
+        Self {
+            domain: domain.to_string(),
+            address: address.to_string(),
+            statement: None,
+            uri: None,
+            version: Some(SIWS_VERSION.to_string()),
+            chain_id: None,
+            nonce: Some(generate_nonce()),
+            issued_at: Some(now),
+            expiration_time: Some(now + chrono::Duration::minutes(DEFAULT_VALIDITY_MINUTES)),
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, SiwsError> {
+        let mut lines = text.split('\n').peekable();
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .ok_or_else(|| SiwsError::Malformed("missing sign-in header".to_string()))?;
+        let address = lines
+            .next()
+            .ok_or_else(|| SiwsError::Malformed("missing address".to_string()))?;
+
+        let mut message = Self {
+            domain: domain.to_string(),
+            address: address.to_string(),
+            statement: None,
+            uri: None,
+            version: None,
+            chain_id: None,
+            nonce: None,
+            issued_at: None,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        };
+
+        let is_field = |line: &str| {
+            line == RESOURCES || FIELDS.iter().any(|f| line.starts_with(&format!("{}: ", f)))
+        };
+
+        // Each section (statement, then fields) is preceded by a blank line
+        if lines.peek().is_some() {
+            if lines.next() != Some("") {
+                return Err(SiwsError::Malformed("expected a blank line after the address".to_string()));
+            }
+            let next = lines.peek().copied().unwrap_or_default();
+            if !is_field(next) {
+                message.statement = Some(next.to_string());
+                lines.next();
+                if lines.peek().is_some() && lines.next() != Some("") {
+                    return Err(SiwsError::Malformed("statement must be a single line".to_string()));
+                }
+            }
+        }
+
+        let mut last_field = None;
+        while let Some(line) = lines.next() {
+            if line == RESOURCES {
+                while let Some(resource) = lines.next_if(|l| l.starts_with("- ")) {
+                    message.resources.push(resource[2..].to_string());
+                }
+                if lines.peek().is_some() {
+                    return Err(SiwsError::Malformed("Resources must be the last field".to_string()));
+                }
+                break;
+            }
+
+            let (label, value) = line
+                .split_once(": ")
+                .ok_or_else(|| SiwsError::Malformed(format!("unexpected line '{}'", line)))?;
+            let index = FIELDS
+                .iter()
+                .position(|f| *f == label)
+                .ok_or_else(|| SiwsError::Malformed(format!("unknown field '{}'", label)))?;
+            if last_field.is_some_and(|last| index <= last) {
+                return Err(SiwsError::Malformed(format!("'{}' is out of order or repeated", label)));
+            }
+            last_field = Some(index);
+
+            let value = value.to_string();
+            match index {
+                0 => message.uri = Some(value),
+                1 => message.version = Some(value),
+                2 => message.chain_id = Some(value),
+                3 => message.nonce = Some(value),
+                4 => message.issued_at = Some(parse_time("issued-at", &value)?),
+                5 => message.expiration_time = Some(parse_time("expiration time", &value)?),
+                6 => message.not_before = Some(parse_time("not-before", &value)?),
+                _ => message.request_id = Some(value),
+            }
+        }
+
+        message.validate()?;
+        Ok(message)
+    }
+
+    /// Check each field on its own; does not look at the clock or any wallet
+    pub fn validate(&self) -> Result<(), SiwsError> {
+        if self.domain.is_empty() || self.domain.contains(char::is_whitespace) || self.domain.contains('/') {
+            return Err(invalid("domain", "must be a host such as example.com"));
+        }
+        parse_address("address", &self.address)?;
+        if let Some(statement) = &self.statement {
+            if statement.is_empty() || statement.contains('\n') {
+                return Err(invalid("statement", "must be a single non-empty line"));
+            }
+        }
+        if let Some(version) = &self.version {
+            if version != SIWS_VERSION {
+                return Err(invalid("version", format!("unsupported version {}", version)));
+            }
+        }
+        if let Some(nonce) = &self.nonce {
+            if nonce.len() < MIN_NONCE_LEN || !nonce.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(invalid(
+                    "nonce",
+                    format!("must be at least {} letters or digits", MIN_NONCE_LEN),
+                ));
+            }
+        }
+        if let (Some(issued), Some(expires)) = (self.issued_at, self.expiration_time) {
+            if expires <= issued {
+                return Err(invalid("expiration time", "must be after issued-at"));
+            }
+        }
+        let no_spaces = |field: &'static str, value: &str| {
+            if value.is_empty() || value.contains(char::is_whitespace) {
+                Err(invalid(field, "cannot be empty or contain spaces"))
+            } else {
+                Ok(())
+            }
+        };
+        self.uri.as_deref().map_or(Ok(()), |uri| no_spaces("URI", uri))?;
+        self.chain_id.as_deref().map_or(Ok(()), |id| no_spaces("chain ID", id))?;
+        if self.request_id.as_deref().is_some_and(|r| r.contains('\n')) {
+            return Err(invalid("request ID", "must be a single line"));
+        }
+        for resource in &self.resources {
+            no_spaces("resource", resource)?;
+        }
+        Ok(())
+    }
+
+    /// Check the message is valid at `now`
+    pub fn check_time(&self, now: DateTime<Utc>) -> Result<(), SiwsError> {
+        if self.expiration_time.is_some_and(|t| now >= t) {
+            return Err(SiwsError::Expired);
+        }
+        if self.not_before.is_some_and(|t| now < t) {
+            return Err(SiwsError::NotYetValid);
+        }
+        Ok(())
+    }
+
+    /// Check the message asks the given wallet to sign in
+    pub fn check_wallet(&self, wallet: &WalletInfo) -> Result<(), SiwsError> {
+        if self.address != wallet.address {
+            return Err(SiwsError::AddressMismatch {
+                expected: wallet.address.clone(),
+                actual: self.address.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Sign the message text as `wallet`.
+    /// Fails if the message is for another address or the signer holds a different key.
+    pub async fn sign<S: TransactionSigner + ?Sized>(
+        &self,
+        wallet: &WalletInfo,
+        signer: &S,
+    ) -> Result<SignedSiws, SiwsError> {
+        self.validate()?;
+        self.check_wallet(wallet)?;
+        self.check_time(Utc::now())?;
+
+        let signer_key = signer
+            .get_public_key()
+            .await
+            .map_err(|e| SiwsError::Signer(e.to_string()))?;
+        if signer_key != self.address {
+            return Err(SiwsError::AddressMismatch {
+                expected: signer_key,
+                actual: self.address.clone(),
+            });
+        }
+
+        let text = self.to_string();
+        let signature = signer
+            .sign_message(text.as_bytes())
+            .await
+            .map_err(|e| SiwsError::Signer(e.to_string()))?;
+        log::info!("✍️ Signed sign-in message for {}", self.domain);
+
+        Ok(SignedSiws {
+            message: text,
+            signature: bs58::encode(signature).into_string(),
+            address: self.address.clone(),
+        })
+    }
+}
+
+impl fmt::Display for SiwsMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}\n{}", self.domain, HEADER_SUFFIX, self.address)?;
+        if let Some(statement) = &self.statement {
+            write!(f, "\n\n{}", statement)?;
+        }
+
+        let times = [self.issued_at, self.expiration_time, self.not_before].map(|t| t.as_ref().map(format_time));
+        let values = [
+            self.uri.as_ref(),
+            self.version.as_ref(),
+            self.chain_id.as_ref(),
+            self.nonce.as_ref(),
+            times[0].as_ref(),
+            times[1].as_ref(),
+            times[2].as_ref(),
+            self.request_id.as_ref(),
+        ];
+        let mut fields: Vec<String> = FIELDS
+            .iter()
+            .zip(values)
+            .filter_map(|(label, value)| value.map(|v| format!("{}: {}", label, v)))
+            .collect();
+        if !self.resources.is_empty() {
+            fields.push(RESOURCES.to_string());
+            fields.extend(self.resources.iter().map(|r| format!("- {}", r)));
+        }
+        if !fields.is_empty() {
+            write!(f, "\n\n{}", fields.join("\n"))?;
+        }
+        Ok(())
+    }
+}
+
+/// What gets sent back to the site: the exact signed text, its base58 signature and the signer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedSiws {
+    pub message: String,
+    pub signature: String,
+    pub address: String,
+}
+
+/// Server-side check of a signed sign-in message
+pub struct SiwsVerifier {
+    domain: String,
+}
+
+impl SiwsVerifier {
+    /// `domain` is the host this server answers on; messages for any other domain are refused
+    pub fn new(domain: &str) -> Self {
+        Self { domain: domain.to_string() }
+    }
+
+    pub fn verify(&self, message: &str, signature: &str, expected_nonce: Option<&str>) -> Result<SiwsMessage, SiwsError> {
+        self.verify_at(message, signature, expected_nonce, Utc::now())
+    }
+
+    /// Verify against the exact signed text and return the parsed message.
+    /// Pass the nonce that was handed out for this sign-in so old messages cannot be replayed.
+    pub fn verify_at(
+        &self,
+        message: &str,
+        signature: &str,
+        expected_nonce: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<SiwsMessage, SiwsError> {
+        let parsed = SiwsMessage::parse(message)?;
+        if parsed.domain != self.domain {
+            return Err(SiwsError::DomainMismatch {
+                expected: self.domain.clone(),
+                actual: parsed.domain,
+            });
+        }
+        if let Some(expected) = expected_nonce {
+            if parsed.nonce.as_deref() != Some(expected) {
+                return Err(SiwsError::NonceMismatch);
+            }
+        }
+        parsed.check_time(now)?;
+
+        let key = parse_address("address", &parsed.address)?;
+        let signature = bs58::decode(signature.trim())
+            .into_vec()
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or(SiwsError::InvalidSignature)?;
+        key.verify(message.as_bytes(), &signature)
+            .map_err(|_| SiwsError::InvalidSignature)?;
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::SignerType;
+    use crate::wallet::Wallet;
+
+    const SAMPLE: &str = "dash.example.com wants you to sign in with your Solana account:
+FnTQJvQGSrUXed97ncP8BNJm7rTXe2SJ9heR6Jv42sk7
+
+Sign in to the treasury dashboard
+
+URI: https://dash.example.com/login
+Version: 1
+Chain ID: mainnet
+Nonce: k3Jd8sLq2mZp
+Issued At: 2026-01-01T12:00:00.000Z
+Expiration Time: 2026-01-01T12:10:00.000Z
+Resources:
+- https://dash.example.com/treasury
+- https://dash.example.com/payroll";
+
+    #[test]
+    fn test_parse_and_build() {
+        let message = SiwsMessage::parse(SAMPLE).unwrap();
+        assert_eq!(message.domain, "dash.example.com");
+        assert_eq!(message.statement.as_deref(), Some("Sign in to the treasury dashboard"));
+        assert_eq!(message.nonce.as_deref(), Some("k3Jd8sLq2mZp"));
+        assert_eq!(message.resources.len(), 2);
+        assert_eq!(message.to_string(), SAMPLE);
+
+        // Header and address only is a complete message
+        let minimal = SiwsMessage::parse(SAMPLE.split("\n\n").next().unwrap()).unwrap();
+        assert_eq!(minimal.statement, None);
+
+        let swapped = SAMPLE.replace("Version: 1\nChain ID: mainnet", "Chain ID: mainnet\nVersion: 1");
+        assert!(matches!(SiwsMessage::parse(&swapped), Err(SiwsError::Malformed(_))));
+        assert!(SiwsMessage::parse(&SAMPLE.replace("Nonce: k3Jd8sLq2mZp", "Nonce: short")).is_err());
+        assert!(SiwsMessage::parse(&SAMPLE.replace("FnTQJvQG", "0OIl")).is_err());
+        assert!(SiwsMessage::parse("Sign in please").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_verify() {
+        let wallet = Wallet::new("Dashboard".to_string());
+        let info = WalletInfo::watch_only("Dashboard".to_string(), wallet.get_public_key());
+        let signer = SignerType::from_wallet(wallet);
+
+        let mut message = SiwsMessage::new("dash.example.com", &info.address);
+        message.statement = Some("Sign in to the treasury dashboard".to_string());
+        message.uri = Some("https://dash.example.com/login".to_string());
+        let nonce = message.nonce.clone().unwrap();
+        let signed = message.sign(&info, &signer).await.unwrap();
+
+        let verifier = SiwsVerifier::new("dash.example.com");
+        let verified = verifier.verify(&signed.message, &signed.signature, Some(&nonce)).unwrap();
+        assert_eq!(verified.address, info.address);
+
+        assert_eq!(
+            verifier.verify(&signed.message, &signed.signature, Some("AnotherNonce1")),
+            Err(SiwsError::NonceMismatch)
+        );
+        assert!(matches!(
+            SiwsVerifier::new("evil.example.com").verify(&signed.message, &signed.signature, None),
+            Err(SiwsError::DomainMismatch { .. })
+        ));
+        let later = Utc::now() + chrono::Duration::hours(1);
+        assert_eq!(
+            verifier.verify_at(&signed.message, &signed.signature, None, later),
+            Err(SiwsError::Expired)
+        );
+        let tampered = signed.message.replace("treasury", "payroll");
+        assert_eq!(
+            verifier.verify(&tampered, &signed.signature, None),
+            Err(SiwsError::InvalidSignature)
+        );
+
+        // A message for another wallet is refused before anything is signed
+        let other = Wallet::new("Other".to_string()).get_public_key();
+        let foreign = SiwsMessage::new("dash.example.com", &other);
+        assert!(matches!(
+            foreign.sign(&info, &signer).await,
+            Err(SiwsError::AddressMismatch { .. })
+        ));
+    }
+}
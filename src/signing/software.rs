@@ -3,14 +3,20 @@ use crate::wallet::Wallet;
 use crate::signing::TransactionSigner;
 use async_trait::async_trait;
 use std::error::Error;
+use std::sync::Arc;
 
+/// Signs with an in-memory wallet. Clones share the same key instead of copying it.
 #[derive(Clone)]
 pub struct SoftwareSigner {
-    wallet: Wallet,
+    wallet: Arc<Wallet>,
 }
 
 impl SoftwareSigner {
     pub fn new(wallet: Wallet) -> Self {
+        Self::from_arc(Arc::new(wallet))
+    }
+
+    pub fn from_arc(wallet: Arc<Wallet>) -> Self {
         Self { wallet }
     }
 }
@@ -25,6 +31,13 @@ impl TransactionSigner for SoftwareSigner {
         let signature = self.wallet.sign_message(message);
         Ok(signature.to_bytes().to_vec())
     }
+
+    async fn sign_messages(&self, messages: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, Box<dyn Error>> {
+        Ok(messages
+            .iter()
+            .map(|message| self.wallet.sign_message(message).to_bytes().to_vec())
+            .collect())
+    }
     
     fn get_name(&self) -> String {
         format!("Software Wallet: {}", self.wallet.name)
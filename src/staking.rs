@@ -2,19 +2,16 @@
 use solana_sdk::{
     pubkey::Pubkey,
     system_instruction,
-    transaction::VersionedTransaction,
-    message::{Message, VersionedMessage},
-    signature::{Signature as SolanaSignature, Keypair, Signer}, // Add Signer trait
+    message::VersionedMessage,
+    signature::{Keypair, Signer}, // Add Signer trait
     hash::Hash,
     commitment_config::CommitmentConfig,
 };
-use crate::wallet::{Wallet, WalletInfo};
-use crate::hardware::HardwareWallet;
-use crate::signing::{TransactionSigner, software::SoftwareSigner, hardware::HardwareSigner};
+use crate::signing::TransactionSigner;
+use crate::signing::partial::PartialTransaction;
 use crate::storage::get_current_jito_settings;
-use crate::transaction::TransactionClient;
+use crate::transaction::{TransactionClient, JITO_TIP_ACCOUNTS};
 use crate::rpc::{ get_balance, get_minimum_balance_for_rent_exemption };
-use std::sync::Arc;
 use std::str::FromStr;
 use std::error::Error;
 use bincode;
@@ -110,6 +107,12 @@ impl StakingClient {
         }
     }
 
+    /// Build staking transactions against a durable nonce account instead of a recent blockhash
+    pub fn with_durable_nonce(mut self, nonce_account: Pubkey) -> Self {
+        self.transaction_client = self.transaction_client.with_durable_nonce(nonce_account);
+        self
+    }
+
     /// Apply Jito modifications to staking instructions (same as transfer logic)
     fn apply_jito_modifications(
         &self,
@@ -117,10 +120,10 @@ impl StakingClient {
         instructions: &mut Vec<solana_sdk::instruction::Instruction>,
     ) -> Result<(), Box<dyn Error>> {
         // First Jito address (as per your existing implementation)
-        let jito_address1 = Pubkey::from_str("juLesoSmdTcRtzjCzYzRoHrnF8GhVu6KCV7uxq7nJGp")?;
+        let jito_address1 = Pubkey::from_str(JITO_TIP_ACCOUNTS[0])?;
         
         // Second Jito address (as per your existing implementation)
-        let jito_address2 = Pubkey::from_str("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL")?;
+        let jito_address2 = Pubkey::from_str(JITO_TIP_ACCOUNTS[1])?;
 
         // Add two transfer instructions as tips to Jito (same as transfers)
         let tip_instruction1 = system_instruction::transfer(
@@ -258,10 +261,6 @@ impl StakingClient {
         let stake_account_keypair = Keypair::new();
         let stake_account_pubkey = stake_account_keypair.pubkey(); // This should work now with Signer trait
 
-        // Get recent blockhash
-        let recent_blockhash = self.transaction_client.get_recent_blockhash().await
-            .map_err(|e| StakingError::RpcError(format!("Failed to get recent blockhash: {}", e)))?;
-
         // Create the base staking instructions
         let mut instructions = vec![
             // 1. Create stake account
@@ -298,55 +297,23 @@ impl StakingClient {
                 .map_err(|e| StakingError::TransactionFailed(format!("Failed to apply Jito modifications: {}", e)))?;
         }
 
-        // Create a message with all instructions
-        let mut message = Message::new(&instructions, Some(&authority_pubkey));
-        message.recent_blockhash = recent_blockhash;
-        
-        // Create a VersionedTransaction with empty signatures
-        let mut transaction = VersionedTransaction {
-            signatures: vec![SolanaSignature::default(); message.header.num_required_signatures as usize],
-            message: VersionedMessage::Legacy(message),
-        };
-        
-        println!("Number of signatures expected for staking transaction: {}", transaction.message.header().num_required_signatures);
+        // Create a message with all instructions, on a recent blockhash or the durable nonce
+        let message = self.transaction_client.build_message(&authority_pubkey, &instructions).await
+            .map_err(|e| StakingError::RpcError(format!("Failed to build transaction: {}", e)))?;
         
-        // Serialize the transaction message for signing
-        let message_bytes = transaction.message.serialize();
-        
-        // Sign the message with our signer (wallet or hardware wallet)
-        let signature_bytes = signer.sign_message(&message_bytes).await
+        // The new stake account and the wallet (fee payer) both sign
+        let mut transaction = PartialTransaction::new(VersionedMessage::Legacy(message));
+        println!("Number of signatures expected for staking transaction: {}", transaction.required_signers().len());
+
+        transaction.sign_with_keypair(&stake_account_keypair)
+            .map_err(|e| StakingError::TransactionFailed(format!("Failed to sign with stake account: {}", e)))?;
+        transaction.sign_with(signer).await
             .map_err(|e| StakingError::WalletError(format!("Failed to sign transaction: {}", e)))?;
-        
-        // Convert to solana signature (expect exactly 64 bytes)
-        if signature_bytes.len() != 64 {
-            return Err(StakingError::WalletError(format!("Invalid signature length: expected 64, got {}", signature_bytes.len())));
-        }
-        
-        let mut sig_array = [0u8; 64];
-        sig_array.copy_from_slice(&signature_bytes);
-        let solana_signature = SolanaSignature::from(sig_array);
-        
-        // We need to handle the stake account keypair separately since it's generated locally
-        // Create a transaction and sign with BOTH the wallet signer AND the stake account keypair
-        let legacy_message = match &transaction.message {
-            VersionedMessage::Legacy(msg) => msg.clone(),
-            _ => return Err(StakingError::TransactionFailed("Expected legacy message".to_string())),
-        };
-        
-        let mut legacy_transaction = solana_sdk::transaction::Transaction {
-            signatures: vec![SolanaSignature::default(); legacy_message.header.num_required_signatures as usize],
-            message: legacy_message,
-        };
-        
-        // Sign with the stake account keypair first
-        legacy_transaction.partial_sign(&[&stake_account_keypair], recent_blockhash);
-        
-        // Then manually add the wallet signature
-        // The wallet signature should be the first signature since the wallet is the fee payer
-        legacy_transaction.signatures[0] = solana_signature;
-        
+        let transaction = transaction.finish()
+            .map_err(|e| StakingError::TransactionFailed(e.to_string()))?;
+
         // Serialize the entire transaction with signatures
-        let serialized_transaction = bincode::serialize(&legacy_transaction)
+        let serialized_transaction = bincode::serialize(&transaction)
             .map_err(|e| StakingError::TransactionFailed(format!("Failed to serialize transaction: {}", e)))?;
         let encoded_transaction = bs58::encode(serialized_transaction).into_string();
         
@@ -365,32 +332,6 @@ impl StakingClient {
     }
 }
 
-/// Create and delegate a stake account (updated to use Jito)
-pub async fn create_stake_account(
-    wallet_info: Option<&WalletInfo>,
-    hardware_wallet: Option<Arc<HardwareWallet>>,
-    validator_vote_account: &str,
-    stake_amount_sol: f64,
-    rpc_url: Option<&str>,
-) -> Result<StakeAccountInfo, StakingError> {
-    let staking_client = StakingClient::new(rpc_url);
-    
-    // Create the appropriate signer based on what's provided
-    let signer: Box<dyn TransactionSigner> = if let Some(hw) = hardware_wallet {
-        // Create HardwareSigner from the HardwareWallet
-        Box::new(HardwareSigner::from_wallet(hw))
-    } else if let Some(w) = wallet_info {
-        let wallet = Wallet::from_wallet_info(w)
-            .map_err(|e| StakingError::WalletError(format!("Failed to create wallet: {}", e)))?;
-        // Create SoftwareSigner from the Wallet
-        Box::new(SoftwareSigner::new(wallet))
-    } else {
-        return Err(StakingError::WalletError("No wallet or hardware wallet provided".to_string()));
-    };
-
-    staking_client.create_stake_account_with_jito(signer.as_ref(), validator_vote_account, stake_amount_sol).await
-}
-
 /// Scan for stake accounts (placeholder function to satisfy stake_modal.rs)
 pub async fn scan_stake_accounts(
     _wallet_address: &str,
@@ -1,6 +1,13 @@
-use crate::wallet::{Wallet, WalletInfo};
-use serde::{Deserialize, Serialize};
+use crate::wallet::{SecretExportFormat, Wallet, WalletInfo};
+use crate::keystore;
+use crate::derivation;
+use crate::discovery::{self, DiscoveredAccount};
+use crate::key_format;
+use crate::qr;
+use crate::schema::{self, Document};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::path::Path;
+use zeroize::{Zeroize, Zeroizing};
 
 // Android-specific imports
 #[cfg(target_os = "android")]
@@ -126,7 +133,7 @@ fn get_storage_dir() -> String {
 }
 
 // Simplified storage directory function
-fn get_storage_dir_simple() -> String {
+pub(crate) fn get_storage_dir_simple() -> String {
     #[cfg(target_os = "android")]
     {
         if let Some(ref dir) = *get_android_files_dir_cached() {
@@ -145,10 +152,28 @@ fn get_storage_dir_simple() -> String {
     }
 }
 
+pub(crate) const WALLETS_FILE_NAME: &str = "wallets.json";
+pub(crate) const WATCH_ONLY_FILE_NAME: &str = "watch_only.json";
+pub(crate) const CONTACTS_FILE_NAME: &str = "contacts.json";
+#[cfg(feature = "web")]
+pub(crate) const CONTACTS_WEB_KEY: &str = "contacts";
+pub(crate) const NOTES_FILE_NAME: &str = "notes.json";
+#[cfg(feature = "web")]
+pub(crate) const NOTES_WEB_KEY: &str = "notes";
+#[cfg(feature = "web")]
+pub(crate) const WALLETS_WEB_KEY: &str = "wallets";
+#[cfg(feature = "web")]
+pub(crate) const WATCH_ONLY_WEB_KEY: &str = "watch_only";
+
 // Get file paths
 fn get_wallets_file_path() -> String {
     let storage_dir = get_storage_dir_simple();
-    format!("{storage_dir}/wallets.json")
+    format!("{storage_dir}/{WALLETS_FILE_NAME}")
+}
+
+fn get_watch_only_file_path() -> String {
+    let storage_dir = get_storage_dir_simple();
+    format!("{storage_dir}/{WATCH_ONLY_FILE_NAME}")
 }
 
 fn get_rpc_file_path() -> String {
@@ -161,6 +186,11 @@ fn get_jito_settings_file_path() -> String {
     format!("{storage_dir}/jito_settings.json")
 }
 
+fn get_background_file_path() -> String {
+    let storage_dir = get_storage_dir_simple();
+    format!("{storage_dir}/background.json")
+}
+
 // Ensure storage directory exists with logging
 fn ensure_storage_dir() -> Result<(), std::io::Error> {
     let storage_dir = get_storage_dir_simple();
@@ -221,65 +251,272 @@ pub fn ensure_android_storage_works() -> Result<(), String> {
     }
 }
 
-pub fn save_wallet_to_storage(wallet_info: &WalletInfo) {
-    log::info!("🔄 Attempting to save wallet: {}", wallet_info.name);
-    
-    let mut wallets = load_wallets_from_storage();
-    wallets.push(wallet_info.clone());
-    
+/// Write `contents` to a temporary file next to `path`, flush it to disk and
+/// rename it over `path`, so a crash mid-write never leaves a truncated file
+#[cfg(not(feature = "web"))]
+pub(crate) fn write_atomic(path: &str, contents: &str) -> std::io::Result<()> {
+    let tmp_path = stage_file(path, contents)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+/// Write `contents` to `<path>.tmp` and flush it to disk, returning the temporary path
+#[cfg(not(feature = "web"))]
+fn stage_file(path: &str, contents: &str) -> std::io::Result<String> {
+    use std::io::Write;
+
+    if let Some(parent) = Path::new(path).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp_path = format!("{}.tmp", path);
+    let mut file = std::fs::File::create(&tmp_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    Ok(tmp_path)
+}
+
+/// Replace several files as one change. Every file is staged before any is renamed,
+/// and if a rename fails the files already replaced get their old contents back.
+#[cfg(not(feature = "web"))]
+fn write_atomic_all(files: &[(String, String)]) -> std::io::Result<()> {
+    let mut staged = Vec::new();
+    for (path, contents) in files {
+        match stage_file(path, contents) {
+            Ok(tmp_path) => staged.push(tmp_path),
+            Err(e) => {
+                staged.iter().for_each(|tmp_path| { let _ = std::fs::remove_file(tmp_path); });
+                return Err(e);
+            }
+        }
+    }
+
+    let previous: Vec<Option<Vec<u8>>> = files.iter().map(|(path, _)| std::fs::read(path).ok()).collect();
+    for (index, ((path, _), tmp_path)) in files.iter().zip(&staged).enumerate() {
+        if let Err(e) = std::fs::rename(tmp_path, path) {
+            log::error!("❌ Failed to replace {}, restoring the files already written: {}", path, e);
+            for ((path, _), old) in files[..index].iter().zip(&previous) {
+                let restored = match old {
+                    Some(old) => std::fs::write(path, old),
+                    None => std::fs::remove_file(path),
+                };
+                if let Err(e) = restored {
+                    log::error!("❌ Failed to restore {}: {}", path, e);
+                }
+            }
+            staged[index..].iter().for_each(|tmp_path| { let _ = std::fs::remove_file(tmp_path); });
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// A document ready to be written by `write_all`: a file path and its contents natively,
+/// a localStorage key and its value on web
+#[cfg(not(feature = "web"))]
+pub(crate) type PendingWrite = (String, String);
+#[cfg(feature = "web")]
+pub(crate) type PendingWrite = (&'static str, String);
+
+/// Write several documents as one change, so either all of them are replaced or none
+pub(crate) fn write_all(writes: &[PendingWrite]) -> Result<(), String> {
     #[cfg(feature = "web")]
     {
-        use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        let serialized = serde_json::to_string(&wallets).unwrap();
-        storage.set_item("wallets", &serialized).unwrap();
-        log::info!("✅ Wallet saved to web storage");
+        write_web_items_all(writes)
     }
-    
+
     #[cfg(not(feature = "web"))]
     {
-        match ensure_storage_dir() {
-            Ok(_) => {
-                let wallet_file = get_wallets_file_path();
-                log::info!("📁 Saving to file: {}", wallet_file);
-                
-                match serde_json::to_string_pretty(&wallets) {
-                    Ok(serialized) => {
-                        match std::fs::write(&wallet_file, &serialized) {
-                            Ok(_) => {
-                                log::info!("✅ Wallet successfully saved to: {}", wallet_file);
-                                log::info!("📊 Saved {} wallets total", wallets.len());
-                                
-                                // Verify the save by reading it back
-                                match std::fs::read_to_string(&wallet_file) {
-                                    Ok(read_back) => {
-                                        if read_back == serialized {
-                                            log::info!("✅ Write verification successful");
-                                        } else {
-                                            log::error!("❌ Write verification failed - content mismatch");
-                                        }
-                                    }
-                                    Err(e) => {
-                                        log::error!("❌ Write verification failed - cannot read back: {}", e);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                log::error!("❌ Failed to write wallets to {}: {}", wallet_file, e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::error!("❌ Failed to serialize wallets: {}", e);
-                    }
+        write_atomic_all(writes).map_err(|e| {
+            let paths: Vec<&str> = writes.iter().map(|(path, _)| path.as_str()).collect();
+            format!("Failed to save {}: {}", paths.join(", "), e)
+        })
+    }
+}
+
+/// Read a versioned document, upgrading older schema versions. A missing file is `Ok(None)`.
+///
+/// A file that exists but cannot be understood is copied aside and reported as an
+/// error, so callers can tell the user instead of treating it as empty.
+#[cfg(not(feature = "web"))]
+pub(crate) fn try_read_document<T: DeserializeOwned>(path: &str, document: Document) -> Result<Option<T>, String> {
+    let data = match std::fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            log::error!("❌ Failed to read {} from {}: {}", document.label(), path, e);
+            return Err(format!("Your {} could not be read: {}", document.label(), e));
+        }
+    };
+
+    match schema::decode(document, &data) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            log::error!("❌ {} ({})", e, path);
+            match quarantine(path, &data) {
+                Ok(backup) => {
+                    Err(format!("Your {} could not be read ({}). A copy was kept at {}", document.label(), e, backup))
+                }
+                Err(copy_error) => {
+                    log::error!("❌ Failed to back up {}: {}", path, copy_error);
+                    Err(format!("Your {} could not be read ({}) and are still at {}", document.label(), e, path))
                 }
             }
-            Err(e) => {
-                log::error!("❌ Failed to ensure storage directory: {}", e);
+        }
+    }
+}
+
+/// Copy an unreadable file to `<path>.unreadable-<timestamp>`, unless an earlier read
+/// already kept an identical copy, and return where the copy is
+#[cfg(not(feature = "web"))]
+fn quarantine(path: &str, data: &str) -> std::io::Result<String> {
+    let file = Path::new(path);
+    let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let copy_prefix = format!("{}.unreadable-", file.file_name().unwrap_or_default().to_string_lossy());
+    for entry in std::fs::read_dir(dir)?.filter_map(|entry| entry.ok()) {
+        if entry.file_name().to_string_lossy().starts_with(&copy_prefix)
+            && std::fs::read(entry.path()).is_ok_and(|copy| copy == data.as_bytes())
+        {
+            return Ok(entry.path().to_string_lossy().into_owned());
+        }
+    }
+
+    let backup = format!("{}.unreadable-{}", path, chrono::Utc::now().timestamp());
+    std::fs::copy(path, &backup)?;
+    log::warn!("⚠️ Kept a copy of the unreadable file at {}", backup);
+    Ok(backup)
+}
+
+/// `try_read_document` for files where falling back to the default is fine.
+/// A file that exists but cannot be understood is still copied aside, so the
+/// next save can never destroy data this build failed to read.
+#[cfg(not(feature = "web"))]
+pub(crate) fn read_document<T: DeserializeOwned>(path: &str, document: Document) -> Option<T> {
+    try_read_document(path, document).unwrap_or(None)
+}
+
+/// Atomically replace a document, written at the current schema version
+#[cfg(not(feature = "web"))]
+pub(crate) fn write_document<T: Serialize>(path: &str, document: Document, value: &T) -> Result<(), String> {
+    let serialized = schema::encode(document, value)?;
+    write_atomic(path, &serialized)
+        .map_err(|e| format!("Failed to write {} to {}: {}", document.label(), path, e))
+}
+
+/// localStorage counterpart of `try_read_document`
+#[cfg(feature = "web")]
+pub(crate) fn try_read_web_document<T: DeserializeOwned>(key: &str, document: Document) -> Result<Option<T>, String> {
+    let window = web_sys::window().unwrap();
+    let storage = window.local_storage().unwrap().unwrap();
+    let Some(data) = storage.get_item(key).unwrap() else {
+        return Ok(None);
+    };
+
+    match schema::decode(document, &data) {
+        Ok(value) => Ok(Some(value)),
+        Err(e) => {
+            log::error!("❌ {} (localStorage {})", e, key);
+            // Keep one copy per distinct unreadable value, however often it is read
+            let prefix = format!("{}.unreadable-", key);
+            let existing = (0..storage.length().unwrap_or(0))
+                .filter_map(|i| storage.key(i).ok().flatten())
+                .find(|other| other.starts_with(&prefix) && storage.get_item(other).ok().flatten().as_ref() == Some(&data));
+            let backup = match existing {
+                Some(backup) => backup,
+                None => {
+                    let backup = format!("{}{}", prefix, chrono::Utc::now().timestamp());
+                    let _ = storage.set_item(&backup, &data);
+                    backup
+                }
+            };
+            Err(format!("Your {} could not be read ({}). A copy was kept in {}", document.label(), e, backup))
+        }
+    }
+}
+
+/// localStorage counterpart of `read_document`
+#[cfg(feature = "web")]
+pub(crate) fn read_web_document<T: DeserializeOwned>(key: &str, document: Document) -> Option<T> {
+    try_read_web_document(key, document).unwrap_or(None)
+}
+
+/// localStorage counterpart of `write_document`
+#[cfg(feature = "web")]
+pub(crate) fn write_web_document<T: Serialize>(key: &str, document: Document, value: &T) -> Result<(), String> {
+    let window = web_sys::window().unwrap();
+    let storage = window.local_storage().unwrap().unwrap();
+    let serialized = schema::encode(document, value)?;
+    storage
+        .set_item(key, &serialized)
+        .map_err(|_| format!("Failed to save {} to localStorage", document.label()))
+}
+
+/// Set several localStorage items as one change; if one can't be set, the items
+/// already set get their old values back
+#[cfg(feature = "web")]
+fn write_web_items_all(items: &[(&str, String)]) -> Result<(), String> {
+    let window = web_sys::window().unwrap();
+    let storage = window.local_storage().unwrap().unwrap();
+    let previous: Vec<Option<String>> = items.iter().map(|(key, _)| storage.get_item(key).ok().flatten()).collect();
+
+    for (index, (key, value)) in items.iter().enumerate() {
+        if storage.set_item(key, value).is_err() {
+            for ((key, _), old) in items[..index].iter().zip(&previous) {
+                let _ = match old {
+                    Some(old) => storage.set_item(key, old),
+                    None => storage.remove_item(key),
+                };
             }
+            return Err(format!("Failed to save {} to localStorage", key));
         }
     }
+    Ok(())
+}
+
+/// Read a wallet list; a missing file is an empty list, an unreadable one an error
+#[cfg(not(feature = "web"))]
+pub(crate) fn try_read_wallet_file(path: &str, document: Document) -> Result<Vec<WalletInfo>, String> {
+    let wallets: Vec<WalletInfo> = try_read_document(path, document)?.unwrap_or_default();
+    log::info!("✅ Loaded {} {} from {}", wallets.len(), document.label(), path);
+    Ok(wallets)
+}
+
+/// Read a wallet list; a missing or unreadable file is an empty list
+#[cfg(not(feature = "web"))]
+pub(crate) fn read_wallet_file(path: &str, document: Document) -> Vec<WalletInfo> {
+    try_read_wallet_file(path, document).unwrap_or_default()
+}
+
+/// Atomically replace a wallet list
+#[cfg(not(feature = "web"))]
+pub(crate) fn write_wallet_file(path: &str, document: Document, wallets: &[WalletInfo]) -> Result<(), String> {
+    write_document(path, document, &wallets)?;
+    log::info!("✅ Saved {} {} to {}", wallets.len(), document.label(), path);
+    Ok(())
+}
+
+/// Overwrite the stored wallet list
+pub fn write_wallets_to_storage(wallets: &[WalletInfo]) {
+    #[cfg(feature = "web")]
+    let result = write_web_document(WALLETS_WEB_KEY, Document::Wallets, &wallets);
+
+    #[cfg(not(feature = "web"))]
+    let result = write_wallet_file(&get_wallets_file_path(), Document::Wallets, wallets);
+
+    if let Err(e) = result {
+        log::error!("❌ {}", e);
+    }
+}
+
+/// Like `load_wallets_from_storage`, but a file that exists and can't be read is an error
+pub fn try_load_wallets_from_storage() -> Result<Vec<WalletInfo>, String> {
+    #[cfg(feature = "web")]
+    {
+        Ok(try_read_web_document(WALLETS_WEB_KEY, Document::Wallets)?.unwrap_or_default())
+    }
+
+    #[cfg(not(feature = "web"))]
+    {
+        try_read_wallet_file(&get_wallets_file_path(), Document::Wallets)
+    }
 }
 
 pub fn load_wallets_from_storage() -> Vec<WalletInfo> {
@@ -287,60 +524,52 @@ pub fn load_wallets_from_storage() -> Vec<WalletInfo> {
     
     #[cfg(feature = "web")]
     {
-        use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        let result = storage.get_item("wallets")
-            .unwrap()
-            .and_then(|data| serde_json::from_str(&data).ok())
-            .unwrap_or_default();
-        log::info!("📱 Loaded {} wallets from web storage", result.len());
-        result
+        read_web_document(WALLETS_WEB_KEY, Document::Wallets).unwrap_or_default()
     }
     
     #[cfg(not(feature = "web"))]
     {
-        let wallet_file = get_wallets_file_path();
-        log::info!("📁 Loading from file: {}", wallet_file);
-        
-        // Check if file exists
-        if !Path::new(&wallet_file).exists() {
-            log::info!("ℹ️ Wallet file doesn't exist yet: {}", wallet_file);
-            return Vec::new();
-        }
-        
-        match std::fs::read_to_string(&wallet_file) {
-            Ok(data) => {
-                log::info!("📄 Read {} bytes from wallet file", data.len());
-                match serde_json::from_str::<Vec<WalletInfo>>(&data) {
-                    Ok(wallets) => {
-                        log::info!("✅ Successfully loaded {} wallets", wallets.len());
-                        for (i, wallet) in wallets.iter().enumerate() {
-                            log::info!("  Wallet {}: {}", i + 1, wallet.name);
-                        }
-                        wallets
-                    }
-                    Err(e) => {
-                        log::error!("❌ Failed to parse wallets from {}: {}", wallet_file, e);
-                        log::error!("📄 File contents: {}", data);
-                        Vec::new()
-                    }
-                }
-            }
-            Err(e) => {
-                log::error!("❌ Failed to read wallets from {}: {}", wallet_file, e);
-                Vec::new()
-            }
-        }
+        read_wallet_file(&get_wallets_file_path(), Document::Wallets)
     }
 }
 
+/// Watch-only wallets live in their own file so they never mix with keyed entries
+pub fn load_watch_only_from_storage() -> Vec<WalletInfo> {
+    #[cfg(feature = "web")]
+    {
+        read_web_document(WATCH_ONLY_WEB_KEY, Document::WatchOnly).unwrap_or_default()
+    }
+
+    #[cfg(not(feature = "web"))]
+    {
+        read_wallet_file(&get_watch_only_file_path(), Document::WatchOnly)
+    }
+}
+
+/// Create a watch-only entry from a pasted address or scanned QR payload
+pub fn create_watch_only_wallet(address_or_uri: &str, name: String) -> Result<WalletInfo, String> {
+    let address = qr::parse_address_payload(address_or_uri)?;
+
+    let already_tracked = load_wallets_from_storage()
+        .iter()
+        .chain(load_watch_only_from_storage().iter())
+        .any(|wallet| wallet.address == address);
+    if already_tracked {
+        return Err(format!("{} is already in your wallet list", address));
+    }
+
+    let wallet_name = if name.is_empty() {
+        "Watch-Only Wallet".to_string()
+    } else {
+        name
+    };
+    Ok(WalletInfo::watch_only(wallet_name, address))
+}
+
 pub fn import_wallet_from_key(private_key: &str, name: String) -> Result<WalletInfo, String> {
-    let private_key = private_key.trim();
-    
-    let key_bytes = bs58::decode(private_key)
-        .into_vec()
-        .map_err(|e| format!("Invalid base58 format: {}", e))?;
+    let (format, key_bytes) = key_format::parse_private_key(private_key)?;
+    let key_bytes = Zeroizing::new(key_bytes);
+    log::info!("🔑 Importing private key in {} format", format.label());
     
     let wallet_name = if name.is_empty() { 
         "Imported Wallet".to_string() 
@@ -350,7 +579,135 @@ pub fn import_wallet_from_key(private_key: &str, name: String) -> Result<WalletI
     
     let wallet = Wallet::from_private_key(&key_bytes, wallet_name)?;
     
-    Ok(wallet.to_wallet_info())
+    wallet.to_wallet_info()
+}
+
+/// Write the wallet's keypair as a Solana CLI `id.json` file under `exports/`
+/// and return the file path
+#[cfg(not(feature = "web"))]
+pub fn export_keypair_file(wallet_info: &WalletInfo) -> Result<String, String> {
+    let wallet = Wallet::from_wallet_info(wallet_info)?;
+    let export_dir = format!("{}/exports", get_storage_dir_simple());
+    std::fs::create_dir_all(&export_dir)
+        .map_err(|e| format!("Failed to create export directory: {}", e))?;
+
+    let export_file = format!("{}/{}.json", export_dir, wallet_info.address);
+    let keypair_json = wallet.export_secret(SecretExportFormat::CliJson);
+
+    // Keypair files are only readable by the owner, like solana-keygen output, from the
+    // moment they exist. An existing file (or a link planted in its place) is never followed.
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&export_file).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => format!("A keypair file already exists at {}", export_file),
+        _ => format!("Failed to create keypair file: {}", e),
+    })?;
+    let written = {
+        use std::io::Write;
+        file.write_all(keypair_json.as_bytes()).and_then(|_| file.sync_all())
+    };
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&export_file);
+        return Err(format!("Failed to write keypair file: {}", e));
+    }
+
+    log::info!("✅ Exported keypair file to {}", export_file);
+    Ok(export_file)
+}
+
+pub fn import_wallet_from_phrase(
+    phrase: &str,
+    passphrase: &str,
+    account_index: u32,
+    name: String,
+) -> Result<WalletInfo, String> {
+    let wallet_name = if name.is_empty() {
+        "Imported Wallet".to_string()
+    } else {
+        name
+    };
+
+    let wallet = Wallet::from_mnemonic(phrase.trim(), passphrase, account_index, wallet_name)?;
+
+    wallet.to_wallet_info()
+}
+
+/// Phrase-import mode: derive accounts under every known scheme and report their
+/// balances so the user can pick which ones to add
+pub async fn scan_phrase_import(
+    phrase: &str,
+    passphrase: &str,
+    gap_limit: u32,
+    name: &str,
+    rpc_url: Option<&str>,
+) -> Result<Vec<DiscoveredAccount>, String> {
+    derivation::validate_mnemonic(phrase)?;
+    discovery::discover_accounts(phrase.trim(), passphrase, gap_limit, name, rpc_url).await
+}
+
+/// Encrypt the candidates picked after `scan_phrase_import`
+pub fn import_selected_accounts(selected: &[DiscoveredAccount]) -> Result<Vec<WalletInfo>, String> {
+    selected
+        .iter()
+        .map(|account| account.wallet.to_wallet_info())
+        .collect()
+}
+
+/// True once at least one wallet secret is stored encrypted, i.e. a passphrase has been chosen.
+/// An unreadable wallet file is an error, so it is never mistaken for a first launch.
+pub fn keystore_initialized() -> Result<bool, String> {
+    Ok(try_load_wallets_from_storage()?
+        .iter()
+        .any(|wallet| wallet.keystore.is_some()))
+}
+
+/// Unlock the keystore session and return the stored wallets.
+///
+/// The passphrase must open every encrypted wallet. On the first unlock after
+/// upgrading, plaintext keys are encrypted and wallets.json is rewritten.
+pub fn unlock_wallets(passphrase: &str) -> Result<Vec<WalletInfo>, String> {
+    if passphrase.is_empty() {
+        return Err("Passphrase cannot be empty".to_string());
+    }
+
+    let mut wallets = try_load_wallets_from_storage()?;
+
+    // The session caches derived keys per salt, so this costs one Argon2 run per salt
+    keystore::unlock_session(passphrase)?;
+    for wallet in wallets.iter() {
+        let Some(secret) = wallet.keystore.as_ref() else { continue };
+        if let Err(e) = keystore::open(secret) {
+            keystore::lock_session();
+            return Err(format!("{} ({})", e, wallet.name));
+        }
+    }
+
+    let mut migrated = 0;
+    for wallet in wallets.iter_mut() {
+        if wallet.keystore.is_some() || wallet.encrypted_key.is_empty() {
+            continue;
+        }
+        let bytes = Zeroizing::new(
+            bs58::decode(&wallet.encrypted_key)
+                .into_vec()
+                .map_err(|e| format!("Failed to decode key for {}: {}", wallet.name, e))?,
+        );
+        wallet.keystore = Some(keystore::seal(&bytes)?);
+        wallet.encrypted_key.zeroize();
+        migrated += 1;
+    }
+
+    if migrated > 0 {
+        log::info!("🔐 Migrated {} plaintext wallets to the encrypted keystore", migrated);
+        write_wallets_to_storage(&wallets);
+    }
+
+    Ok(wallets)
 }
 
 pub fn save_rpc_to_storage(rpc_url: &str) {
@@ -358,19 +715,18 @@ pub fn save_rpc_to_storage(rpc_url: &str) {
     
     #[cfg(feature = "web")]
     {
-        use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        storage.set_item("custom_rpc", rpc_url).unwrap();
+        if let Err(e) = write_web_document("custom_rpc", Document::Rpc, &rpc_url) {
+            log::error!("❌ {}", e);
+        }
     }
     
     #[cfg(not(feature = "web"))]
     {
         if let Ok(_) = ensure_storage_dir() {
             let rpc_file = get_rpc_file_path();
-            match std::fs::write(&rpc_file, rpc_url) {
+            match write_document(&rpc_file, Document::Rpc, &rpc_url) {
                 Ok(_) => log::info!("✅ RPC URL saved to: {}", rpc_file),
-                Err(e) => log::error!("❌ Failed to write RPC to {}: {}", rpc_file, e),
+                Err(e) => log::error!("❌ {}", e),
             }
         }
     }
@@ -381,28 +737,16 @@ pub fn load_rpc_from_storage() -> Option<String> {
     
     #[cfg(feature = "web")]
     {
-        use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        storage.get_item("custom_rpc").unwrap()
+        read_web_document("custom_rpc", Document::Rpc)
     }
     
     #[cfg(not(feature = "web"))]
     {
-        let rpc_file = get_rpc_file_path();
-        match std::fs::read_to_string(&rpc_file) {
-            Ok(data) => {
-                let result = Some(data.trim().to_string());
-                log::info!("✅ RPC URL loaded from storage");
-                result
-            }
-            Err(e) => {
-                if e.kind() != std::io::ErrorKind::NotFound {
-                    log::error!("❌ Failed to read RPC from {}: {}", rpc_file, e);
-                }
-                None
-            }
+        let result = read_document(&get_rpc_file_path(), Document::Rpc);
+        if result.is_some() {
+            log::info!("✅ RPC URL loaded from storage");
         }
+        result
     }
 }
 
@@ -430,6 +774,7 @@ pub fn clear_rpc_storage() {
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct JitoSettings {
     pub jito_tx: bool,
     pub jito_bundles: bool,
@@ -449,25 +794,18 @@ pub fn save_jito_settings_to_storage(settings: &JitoSettings) {
     
     #[cfg(feature = "web")]
     {
-        use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        let serialized = serde_json::to_string(settings).unwrap();
-        storage.set_item("jito_settings", &serialized).unwrap();
+        if let Err(e) = write_web_document("jito_settings", Document::JitoSettings, settings) {
+            log::error!("❌ {}", e);
+        }
     }
     
     #[cfg(not(feature = "web"))]
     {
         if let Ok(_) = ensure_storage_dir() {
             let jito_file = get_jito_settings_file_path();
-            match serde_json::to_string_pretty(settings) {
-                Ok(serialized) => {
-                    match std::fs::write(&jito_file, serialized) {
-                        Ok(_) => log::info!("✅ Jito settings saved to: {}", jito_file),
-                        Err(e) => log::error!("❌ Failed to write Jito settings to {}: {}", jito_file, e),
-                    }
-                }
-                Err(e) => log::error!("❌ Failed to serialize Jito settings: {}", e),
+            match write_document(&jito_file, Document::JitoSettings, settings) {
+                Ok(_) => log::info!("✅ Jito settings saved to: {}", jito_file),
+                Err(e) => log::error!("❌ {}", e),
             }
         }
     }
@@ -478,39 +816,38 @@ pub fn load_jito_settings_from_storage() -> JitoSettings {
     
     #[cfg(feature = "web")]
     {
-        use wasm_bindgen::JsCast;
-        let window = web_sys::window().unwrap();
-        let storage = window.local_storage().unwrap().unwrap();
-        storage
-            .get_item("jito_settings")
-            .unwrap()
-            .and_then(|data| serde_json::from_str(&data).ok())
-            .unwrap_or_default()
+        read_web_document("jito_settings", Document::JitoSettings).unwrap_or_default()
     }
     
     #[cfg(not(feature = "web"))]
     {
-        let jito_file = get_jito_settings_file_path();
-        match std::fs::read_to_string(&jito_file) {
-            Ok(data) => {
-                match serde_json::from_str(&data) {
-                    Ok(settings) => {
-                        log::info!("✅ Jito settings loaded from storage");
-                        settings
-                    }
-                    Err(e) => {
-                        log::error!("❌ Failed to parse Jito settings from {}: {}", jito_file, e);
-                        JitoSettings::default()
-                    }
-                }
-            }
-            Err(e) => {
-                if e.kind() != std::io::ErrorKind::NotFound {
-                    log::error!("❌ Failed to read Jito settings from {}: {}", jito_file, e);
-                }
-                JitoSettings::default()
-            }
-        }
+        read_document(&get_jito_settings_file_path(), Document::JitoSettings).unwrap_or_default()
+    }
+}
+
+/// Remember the selected background theme by name
+pub fn save_background_to_storage(theme_name: &str) {
+    #[cfg(feature = "web")]
+    let result = write_web_document("background", Document::Background, &theme_name);
+
+    #[cfg(not(feature = "web"))]
+    let result = write_document(&get_background_file_path(), Document::Background, &theme_name);
+
+    match result {
+        Ok(_) => log::info!("✅ Background saved: {}", theme_name),
+        Err(e) => log::error!("❌ {}", e),
+    }
+}
+
+pub fn load_background_from_storage() -> Option<String> {
+    #[cfg(feature = "web")]
+    {
+        read_web_document("background", Document::Background)
+    }
+
+    #[cfg(not(feature = "web"))]
+    {
+        read_document(&get_background_file_path(), Document::Background)
     }
 }
 
@@ -1,16 +1,18 @@
 // src/transaction.rs
 use crate::wallet::Wallet;
 use crate::signing::{TransactionSigner, SignerType};
+use crate::signing::partial::PartialTransaction;
+use crate::signing::policy::PolicySigner;
+use crate::nonce::{NonceAccountInfo, NonceError};
 use crate::storage::get_current_jito_settings;
 use crate::components::modals::bulk_send_modal::SelectedTokenForBulkSend;
 use solana_sdk::{
     pubkey::Pubkey,
-    signature::{Signature as SolanaSignature},
-    transaction::VersionedTransaction,
     message::{Message, VersionedMessage},
     system_instruction,
     hash::Hash,
 };
+use base64::Engine;
 use bs58;
 use reqwest::Client;
 use std::error::Error;
@@ -28,10 +30,18 @@ const MAX_TRANSACTION_SIZE: usize = 1200; // Conservative limit (actual is ~1232
 const ESTIMATED_INSTRUCTION_SIZE: usize = 150; // Estimated bytes per instruction
 const HEADER_OVERHEAD: usize = 200; // Transaction header and signature overhead
 
+/// Accounts that receive the tips added when Jito is enabled
+pub(crate) const JITO_TIP_ACCOUNTS: [&str; 2] = [
+    "juLesoSmdTcRtzjCzYzRoHrnF8GhVu6KCV7uxq7nJGp",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+];
+
 /// Transaction client for sending transactions
 pub struct TransactionClient {
     client: Client,
     rpc_url: String,
+    /// Nonce account whose value replaces the recent blockhash, so transactions don't expire
+    durable_nonce: Option<Pubkey>,
 }
 
 /// Bulk transaction builder for atomic multi-token sends
@@ -143,13 +153,16 @@ impl BulkTransactionBuilder {
             let from_token_account = get_associated_token_address(&self.from_pubkey, &mint_pubkey);
             let to_token_account = get_associated_token_address(&self.to_pubkey, &mint_pubkey);
             
-            let transfer_instruction = token_instruction::transfer(
+            // Checked transfers name the mint, so the signing policy can see which token moves
+            let transfer_instruction = token_instruction::transfer_checked(
                 &spl_token::id(),
                 &from_token_account,
+                &mint_pubkey,
                 &to_token_account,
                 &self.from_pubkey,
                 &[&self.from_pubkey],
                 amount_units,
+                decimals,
             )?;
             instructions.push(transfer_instruction);
         }
@@ -210,7 +223,75 @@ impl TransactionClient {
         Self {
             client: Client::new(),
             rpc_url: url,
+            durable_nonce: None,
+        }
+    }
+
+    /// Build transactions against `nonce_account` instead of a recent blockhash.
+    /// They stay valid until the nonce is advanced, e.g. for offline or slow hardware signing.
+    pub fn with_durable_nonce(mut self, nonce_account: Pubkey) -> Self {
+        self.durable_nonce = Some(nonce_account);
+        self
+    }
+
+    /// Message paying from `payer`, with either the durable nonce or a recent blockhash.
+    /// In nonce mode `advance_nonce_account` is the first instruction.
+    pub async fn build_message(
+        &self,
+        payer: &Pubkey,
+        instructions: &[solana_sdk::instruction::Instruction],
+    ) -> Result<Message, Box<dyn Error>> {
+        if let Some(nonce_account) = self.durable_nonce {
+            let nonce = self.get_nonce_account(&nonce_account).await?;
+            println!("Using durable nonce {} from {}", nonce.nonce, nonce_account);
+            let mut message = Message::new_with_nonce(instructions.to_vec(), Some(payer), &nonce_account, &nonce.authority);
+            message.recent_blockhash = nonce.nonce;
+            return Ok(message);
         }
+
+        let recent_blockhash = self.get_recent_blockhash().await?;
+        println!("Using blockhash: {}", recent_blockhash);
+        let mut message = Message::new(instructions, Some(payer));
+        message.recent_blockhash = recent_blockhash;
+        Ok(message)
+    }
+
+    /// Fetch and decode a nonce account
+    pub async fn get_nonce_account(&self, address: &Pubkey) -> Result<NonceAccountInfo, NonceError> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getAccountInfo",
+            "params": [
+                address.to_string(),
+                {
+                    "encoding": "base64"
+                }
+            ]
+        });
+
+        let response = self.client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| NonceError::Rpc(e.to_string()))?;
+        let json: Value = response.json().await.map_err(|e| NonceError::Rpc(e.to_string()))?;
+
+        if let Some(error) = json.get("error") {
+            return Err(NonceError::Rpc(format!("{:?}", error)));
+        }
+        let value = &json["result"]["value"];
+        if value.is_null() {
+            return Err(NonceError::NotFound(address.to_string()));
+        }
+        let data = value["data"][0]
+            .as_str()
+            .and_then(|encoded| base64::engine::general_purpose::STANDARD.decode(encoded).ok())
+            .ok_or_else(|| NonceError::Rpc("missing account data".to_string()))?;
+        let balance = value["lamports"].as_u64().unwrap_or_default();
+
+        NonceAccountInfo::parse(*address, balance, &data)
     }
 
     /// Send bulk transaction with multiple tokens/SOL
@@ -261,74 +342,76 @@ impl TransactionClient {
         
         if transaction_batches.len() > 1 {
             println!("Transaction too large, splitting into {} batches", transaction_batches.len());
-            // For now, return an error - you could implement batch sending
-            return Err("Transaction too large for single batch. Multi-batch sending not yet implemented.".into());
         }
 
-        // Send as single transaction
-        self.send_bulk_transaction_single(signer, instructions).await
+        self.send_bulk_transaction_batches(signer, transaction_batches).await
     }
 
-    /// Send a single bulk transaction with all instructions
-    async fn send_bulk_transaction_single(
+    /// Sign every batch with one signer request, then send them in order.
+    /// Returns the signature of the last transaction.
+    async fn send_bulk_transaction_batches(
         &self,
         signer: &dyn TransactionSigner,
-        mut instructions: Vec<solana_sdk::instruction::Instruction>,
+        batches: Vec<Vec<solana_sdk::instruction::Instruction>>,
     ) -> Result<String, Box<dyn Error>> {
         // Check Jito settings and apply modifications if needed
         let jito_settings = get_current_jito_settings();
         let from_pubkey_str = signer.get_public_key().await?;
         let from_pubkey = Pubkey::from_str(&from_pubkey_str)?;
 
-        if jito_settings.jito_tx {
-            println!("JitoTx is enabled, applying Jito modifications to bulk transaction");
-            self.apply_jito_modifications(&from_pubkey, &mut instructions)?;
+        // A durable nonce can only be used once, so it can't cover several transactions
+        if self.durable_nonce.is_some() && batches.len() > 1 {
+            return Err(format!("A durable nonce covers one transaction, but this send needs {}", batches.len()).into());
         }
 
-        // Get recent blockhash
-        let recent_blockhash = self.get_recent_blockhash().await?;
-        println!("Using blockhash: {}", recent_blockhash);
-
-        // Create a message with all instructions
-        let mut message = Message::new(&instructions, Some(&from_pubkey));
-        message.recent_blockhash = recent_blockhash;
-
-        // Create a VersionedTransaction with empty signatures
-        let mut transaction = VersionedTransaction {
-            signatures: vec![SolanaSignature::default(); message.header.num_required_signatures as usize],
-            message: VersionedMessage::Legacy(message),
-        };
-
-        println!("Number of signatures expected: {}", transaction.message.header().num_required_signatures);
-
-        // Serialize the transaction message for signing
-        let message_bytes = transaction.message.serialize();
+        let mut messages = Vec::with_capacity(batches.len());
+        for mut instructions in batches {
+            if jito_settings.jito_tx {
+                println!("JitoTx is enabled, applying Jito modifications to bulk transaction");
+                self.apply_jito_modifications(&from_pubkey, &mut instructions)?;
+            }
+            let message = self.build_message(&from_pubkey, &instructions).await?;
+            messages.push(VersionedMessage::Legacy(message));
+        }
 
-        // Sign the message with our signer
-        let signature_bytes = signer.sign_message(&message_bytes).await?;
+        // One approval covers the whole batch on signers that support it.
+        // The signing policy checks the batch as a whole.
+        let message_bytes: Vec<Vec<u8>> = messages.iter().map(|m| m.serialize()).collect();
+        let signatures = PolicySigner::shared(signer).sign_messages(&message_bytes).await?;
+        if signatures.len() != messages.len() {
+            return Err(format!("Signer returned {} signatures for {} transactions", signatures.len(), messages.len()).into());
+        }
 
-        // Convert to solana signature (expect exactly 64 bytes)
-        if signature_bytes.len() != 64 {
-            return Err(format!("Invalid signature length: expected 64, got {}", signature_bytes.len()).into());
+        let total = messages.len();
+        let mut last_signature = String::new();
+        for (index, (message, signature_bytes)) in messages.into_iter().zip(signatures).enumerate() {
+            let mut transaction = PartialTransaction::new(message);
+            transaction.add_signature(&from_pubkey, &signature_bytes)?;
+            let transaction = transaction.finish()?;
+
+            // Serialize the entire transaction with signature
+            let serialized_transaction = bincode::serialize(&transaction)?;
+            let encoded_transaction = bs58::encode(serialized_transaction).into_string();
+            println!("Sending bulk transaction {}/{}: {} bytes", index + 1, total, encoded_transaction.len());
+
+            last_signature = self.send_transaction(&encoded_transaction).await.map_err(|e| {
+                format!("Sent {} of {} transactions, then failed: {}", index, total, e)
+            })?;
         }
 
-        let mut sig_array = [0u8; 64];
-        sig_array.copy_from_slice(&signature_bytes);
-        let solana_signature = SolanaSignature::from(sig_array);
+        Ok(last_signature)
+    }
 
-        // Assign the signature to the transaction
-        if transaction.signatures.len() != 1 {
-            return Err(format!("Expected 1 signature slot, found {}", transaction.signatures.len()).into());
-        }
-        transaction.signatures[0] = solana_signature;
+    /// Send a transaction once every required signer has signed it
+    pub async fn send_partial_transaction(&self, transaction: PartialTransaction) -> Result<String, Box<dyn Error>> {
+        let transaction = transaction.finish()?;
 
-        // Serialize the entire transaction with signature
+        // Serialize the entire transaction with signatures
         let serialized_transaction = bincode::serialize(&transaction)?;
         let encoded_transaction = bs58::encode(serialized_transaction).into_string();
 
-        println!("Serialized bulk transaction: {} bytes", encoded_transaction.len());
+        println!("Serialized transaction: {} bytes", encoded_transaction.len());
 
-        // Send the transaction
         self.send_transaction(&encoded_transaction).await
     }
 
@@ -444,14 +527,15 @@ impl TransactionClient {
         }
     }
 
-    /// Send SOL from one wallet to another (original method for backward compatibility)
+    /// Send SOL from one wallet to another (original method for backward compatibility).
+    /// Takes the wallet by value so the signer owns the only copy of the key.
     pub async fn send_sol(
         &self,
-        from_wallet: &Wallet,
+        from_wallet: Wallet,
         to_address: &str,
         amount_sol: f64,
     ) -> Result<String, Box<dyn Error>> {
-        let signer = SignerType::from_wallet(from_wallet.clone());
+        let signer = SignerType::from_wallet(from_wallet);
         self.send_sol_with_signer(&signer, to_address, amount_sol).await
     }
     
@@ -462,12 +546,27 @@ impl TransactionClient {
         to_address: &str,
         amount_sol: f64,
     ) -> Result<String, Box<dyn Error>> {
-        // Check Jito settings
-        let jito_settings = get_current_jito_settings();
-        
         // Get the public key from the signer
         let from_pubkey_str = signer.get_public_key().await?;
-        let from_pubkey = Pubkey::from_str(&from_pubkey_str)?;
+        let mut transaction = self.build_sol_transfer(&from_pubkey_str, to_address, amount_sol).await?;
+        println!("Number of signatures expected: {}", transaction.required_signers().len());
+        transaction.sign_with(signer).await?;
+
+        // Send the transaction
+        self.send_partial_transaction(transaction).await
+    }
+
+    /// Build an unsigned SOL transfer from `from_address`, e.g. to sign on another device
+    pub async fn build_sol_transfer(
+        &self,
+        from_address: &str,
+        to_address: &str,
+        amount_sol: f64,
+    ) -> Result<PartialTransaction, Box<dyn Error>> {
+        // Check Jito settings
+        let jito_settings = get_current_jito_settings();
+
+        let from_pubkey = Pubkey::from_str(from_address)?;
         let to_pubkey = Pubkey::from_str(to_address)?;
         
         // Convert SOL to lamports
@@ -476,9 +575,6 @@ impl TransactionClient {
         println!("Sending {} lamports ({} SOL) from {} to {}", 
             amount_lamports, amount_sol, from_pubkey, to_pubkey);
         
-        // Get recent blockhash
-        let recent_blockhash = self.get_recent_blockhash().await?;
-        println!("Using blockhash: {}", recent_blockhash);
         
         // Create the transfer instruction using Solana SDK
         let transfer_instruction = system_instruction::transfer(
@@ -497,57 +593,20 @@ impl TransactionClient {
         }
         
         // Create a message with all instructions
-        let mut message = Message::new(&instructions, Some(&from_pubkey));
-        message.recent_blockhash = recent_blockhash;
-        
-        // Create a VersionedTransaction with empty signatures
-        let mut transaction = VersionedTransaction {
-            signatures: vec![SolanaSignature::default(); message.header.num_required_signatures as usize],
-            message: VersionedMessage::Legacy(message),
-        };
-        
-        println!("Number of signatures expected: {}", transaction.message.header().num_required_signatures);
-        
-        // Serialize the transaction message for signing
-        let message_bytes = transaction.message.serialize();
-        
-        // Sign the message with our signer
-        let signature_bytes = signer.sign_message(&message_bytes).await?;
-        
-        // Convert to solana signature (expect exactly 64 bytes)
-        if signature_bytes.len() != 64 {
-            return Err(format!("Invalid signature length: expected 64, got {}", signature_bytes.len()).into());
-        }
-        
-        let mut sig_array = [0u8; 64];
-        sig_array.copy_from_slice(&signature_bytes);
-        let solana_signature = SolanaSignature::from(sig_array);
-        
-        // Assign the signature to the transaction
-        if transaction.signatures.len() != 1 {
-            return Err(format!("Expected 1 signature slot, found {}", transaction.signatures.len()).into());
-        }
-        transaction.signatures[0] = solana_signature;
-        
-        // Serialize the entire transaction with signature
-        let serialized_transaction = bincode::serialize(&transaction)?;
-        let encoded_transaction = bs58::encode(serialized_transaction).into_string();
-        
-        println!("Serialized transaction: {} bytes", encoded_transaction.len());
-        
-        // Send the transaction
-        self.send_transaction(&encoded_transaction).await
+        let message = self.build_message(&from_pubkey, &instructions).await?;
+
+        Ok(PartialTransaction::new(VersionedMessage::Legacy(message)))
     }
 
     // Send SPL token transaction using wallet
     pub async fn send_spl_token(
         &self,
-        from_wallet: &Wallet,
+        from_wallet: Wallet,
         to_address: &str,
         amount: f64,
         token_mint: &str,
     ) -> Result<String, Box<dyn Error>> {
-        let signer = SignerType::from_wallet(from_wallet.clone());
+        let signer = SignerType::from_wallet(from_wallet);
         self.send_spl_token_with_signer(&signer, to_address, amount, token_mint).await
     }
 
@@ -559,11 +618,27 @@ impl TransactionClient {
         amount: f64,
         token_mint: &str,
     ) -> Result<String, Box<dyn Error>> {
+        let from_pubkey_str = signer.get_public_key().await?;
+        let mut transaction = self.build_spl_transfer(&from_pubkey_str, to_address, amount, token_mint).await?;
+        println!("Number of signatures expected: {}", transaction.required_signers().len());
+        transaction.sign_with(signer).await?;
+
+        // Send the transaction
+        self.send_partial_transaction(transaction).await
+    }
+
+    /// Build an unsigned SPL token transfer from `from_address`, e.g. to sign on another device
+    pub async fn build_spl_transfer(
+        &self,
+        from_address: &str,
+        to_address: &str,
+        amount: f64,
+        token_mint: &str,
+    ) -> Result<PartialTransaction, Box<dyn Error>> {
         // Check Jito settings
         let jito_settings = get_current_jito_settings();
-        
-        let from_pubkey_str = signer.get_public_key().await?;
-        let from_pubkey = Pubkey::from_str(&from_pubkey_str)?;
+
+        let from_pubkey = Pubkey::from_str(from_address)?;
         let to_pubkey = Pubkey::from_str(to_address)?;
         let mint_pubkey = Pubkey::from_str(token_mint)?;
         
@@ -586,9 +661,6 @@ impl TransactionClient {
         println!("From token account: {}", from_token_account);
         println!("To token account: {}", to_token_account);
         
-        // Get recent blockhash
-        let recent_blockhash = self.get_recent_blockhash().await?;
-        println!("Using blockhash: {}", recent_blockhash);
         
         // Check if destination token account exists
         let mut instructions = Vec::new();
@@ -608,13 +680,15 @@ impl TransactionClient {
         }
         
         // Create the token transfer instruction
-        let transfer_instruction = token_instruction::transfer(
+        let transfer_instruction = token_instruction::transfer_checked(
             &spl_token::id(),                    // Token program ID
             &from_token_account,                 // Source token account
+            &mint_pubkey,                        // Token mint, checked against the source
             &to_token_account,                   // Destination token account  
             &from_pubkey,                        // Authority (owner of source account)
             &[&from_pubkey],                     // Signers
             amount_units,                        // Amount in token units
+            token_decimals,                      // Decimals, checked against the mint
         )?;
         
         instructions.push(transfer_instruction);
@@ -626,46 +700,9 @@ impl TransactionClient {
         }
         
         // Create a message with all instructions
-        let mut message = Message::new(&instructions, Some(&from_pubkey));
-        message.recent_blockhash = recent_blockhash;
-        
-        // Create a VersionedTransaction with empty signatures
-        let mut transaction = VersionedTransaction {
-            signatures: vec![SolanaSignature::default(); message.header.num_required_signatures as usize],
-            message: VersionedMessage::Legacy(message),
-        };
-        
-        println!("Number of signatures expected: {}", transaction.message.header().num_required_signatures);
-        
-        // Serialize the transaction message for signing
-        let message_bytes = transaction.message.serialize();
-        
-        // Sign the message with our signer
-        let signature_bytes = signer.sign_message(&message_bytes).await?;
-        
-        // Convert to solana signature (expect exactly 64 bytes)
-        if signature_bytes.len() != 64 {
-            return Err(format!("Invalid signature length: expected 64, got {}", signature_bytes.len()).into());
-        }
-        
-        let mut sig_array = [0u8; 64];
-        sig_array.copy_from_slice(&signature_bytes);
-        let solana_signature = SolanaSignature::from(sig_array);
-        
-        // Assign the signature to the transaction
-        if transaction.signatures.len() != 1 {
-            return Err(format!("Expected 1 signature slot, found {}", transaction.signatures.len()).into());
-        }
-        transaction.signatures[0] = solana_signature;
-        
-        // Serialize the entire transaction with signature
-        let serialized_transaction = bincode::serialize(&transaction)?;
-        let encoded_transaction = bs58::encode(serialized_transaction).into_string();
-        
-        println!("Serialized SPL token transaction: {} bytes", encoded_transaction.len());
-        
-        // Send the transaction
-        self.send_transaction(&encoded_transaction).await
+        let message = self.build_message(&from_pubkey, &instructions).await?;
+
+        Ok(PartialTransaction::new(VersionedMessage::Legacy(message)))
     }
 
     /// Get token decimals for a given mint
@@ -754,10 +791,10 @@ impl TransactionClient {
         instructions: &mut Vec<solana_sdk::instruction::Instruction>,
     ) -> Result<(), Box<dyn Error>> {
         // First Jito address (as per JS example)
-        let jito_address1 = Pubkey::from_str("juLesoSmdTcRtzjCzYzRoHrnF8GhVu6KCV7uxq7nJGp")?;
+        let jito_address1 = Pubkey::from_str(JITO_TIP_ACCOUNTS[0])?;
         
         // Second Jito address (as per JS example)
-        let jito_address2 = Pubkey::from_str("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL")?;
+        let jito_address2 = Pubkey::from_str(JITO_TIP_ACCOUNTS[1])?;
 
         // Add two transfer instructions as tips to Jito
         let tip_instruction1 = system_instruction::transfer(
@@ -0,0 +1,198 @@
+// src/transaction_notes.rs
+use crate::schema::{self, Document};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+
+const MAX_NOTE_LEN: usize = 280;
+
+/// A private note the user attached to one of their transactions.
+/// Notes never leave the device except inside an encrypted backup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TransactionNote {
+    pub signature: String,
+    pub note: String,
+    pub updated_at: i64,
+}
+
+fn validate_signature(signature: &str) -> Result<String, String> {
+    let signature = signature.trim();
+    match bs58::decode(signature).into_vec() {
+        Ok(bytes) if bytes.len() == 64 => Ok(signature.to_string()),
+        _ => Err(format!("{} is not a transaction signature", signature)),
+    }
+}
+
+/// Local transaction notes, stored in `notes.json` next to `wallets.json`.
+/// One note per signature; every change is written back immediately.
+pub struct TransactionNotes {
+    notes: Vec<TransactionNote>,
+    #[cfg(not(feature = "web"))]
+    dir: String,
+}
+
+impl TransactionNotes {
+    /// Load from the app storage directory
+    pub fn load() -> Self {
+        #[cfg(feature = "web")]
+        {
+            Self {
+                notes: storage::read_web_document(storage::NOTES_WEB_KEY, Document::TransactionNotes).unwrap_or_default(),
+            }
+        }
+
+        #[cfg(not(feature = "web"))]
+        {
+            Self::load_from(&storage::get_storage_dir_simple())
+        }
+    }
+
+    /// Load from an explicit directory
+    #[cfg(not(feature = "web"))]
+    pub fn load_from(dir: &str) -> Self {
+        let path = format!("{}/{}", dir, storage::NOTES_FILE_NAME);
+        Self {
+            notes: storage::read_document(&path, Document::TransactionNotes).unwrap_or_default(),
+            dir: dir.to_string(),
+        }
+    }
+
+    pub fn notes(&self) -> &[TransactionNote] {
+        &self.notes
+    }
+
+    pub fn get(&self, signature: &str) -> Option<&str> {
+        self.notes
+            .iter()
+            .find(|n| n.signature == signature)
+            .map(|n| n.note.as_str())
+    }
+
+    /// Attach `note` to a transaction, replacing any earlier note. A blank note removes it.
+    pub fn set(&mut self, signature: &str, note: &str) -> Result<(), String> {
+        let signature = validate_signature(signature)?;
+        let note = note.trim();
+        if note.chars().count() > MAX_NOTE_LEN {
+            return Err(format!("Notes must be at most {} characters", MAX_NOTE_LEN));
+        }
+
+        self.notes.retain(|n| n.signature != signature);
+        if !note.is_empty() {
+            self.notes.push(TransactionNote {
+                signature,
+                note: note.to_string(),
+                updated_at: chrono::Utc::now().timestamp(),
+            });
+        }
+        self.save()
+    }
+
+    /// Add notes from elsewhere (e.g. a backup), skipping invalid entries and
+    /// transactions that already have a note here. Returns how many were added.
+    pub fn merge(&mut self, notes: &[TransactionNote]) -> Result<usize, String> {
+        let added = self.add_missing(notes);
+        self.save()?;
+        Ok(added)
+    }
+
+    /// `merge` without saving
+    pub(crate) fn add_missing(&mut self, notes: &[TransactionNote]) -> usize {
+        let mut added = 0;
+        for note in notes {
+            let valid = validate_signature(&note.signature).is_ok()
+                && !note.note.trim().is_empty()
+                && note.note.chars().count() <= MAX_NOTE_LEN;
+            if !valid {
+                log::warn!("⚠️ Skipping transaction note for {}", note.signature);
+            } else if self.get(&note.signature).is_none() {
+                self.notes.push(note.clone());
+                added += 1;
+            }
+        }
+        added
+    }
+
+    /// Swap every note for `notes`
+    pub fn replace_all(&mut self, notes: &[TransactionNote]) -> Result<usize, String> {
+        self.notes.clear();
+        self.merge(notes)
+    }
+
+    /// Forget every note in memory. Nothing is written until the next save.
+    pub(crate) fn clear(&mut self) {
+        self.notes.clear();
+    }
+
+    /// The notes file as `save` would write it, to write together with other documents
+    pub(crate) fn pending_write(&self) -> Result<storage::PendingWrite, String> {
+        let serialized = schema::encode(Document::TransactionNotes, &self.notes)?;
+
+        #[cfg(feature = "web")]
+        {
+            Ok((storage::NOTES_WEB_KEY, serialized))
+        }
+
+        #[cfg(not(feature = "web"))]
+        {
+            Ok((format!("{}/{}", self.dir, storage::NOTES_FILE_NAME), serialized))
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        storage::write_all(&[self.pending_write()?])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> String {
+        let dir = std::env::temp_dir().join(format!("transaction_notes_{}", uuid::Uuid::new_v4()));
+        dir.to_string_lossy().into_owned()
+    }
+
+    fn signature(byte: u8) -> String {
+        bs58::encode([byte; 64]).into_string()
+    }
+
+    #[test]
+    fn test_set_replace_remove_persist() {
+        let dir = temp_dir();
+        let mut notes = TransactionNotes::load_from(&dir);
+
+        notes.set(&signature(1), "  Rent for March ").unwrap();
+        notes.set(&signature(1), "Rent for April").unwrap();
+        notes.set(&signature(2), "Payroll").unwrap();
+        assert!(notes.set("not-a-signature", "x").is_err());
+        assert!(notes.set(&signature(3), &"x".repeat(MAX_NOTE_LEN + 1)).is_err());
+
+        let reloaded = TransactionNotes::load_from(&dir);
+        assert_eq!(reloaded.notes().len(), 2);
+        assert_eq!(reloaded.get(&signature(1)), Some("Rent for April"));
+
+        notes.set(&signature(2), "   ").unwrap();
+        assert_eq!(TransactionNotes::load_from(&dir).get(&signature(2)), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_merge_keeps_local_notes() {
+        let dir = temp_dir();
+        let mut notes = TransactionNotes::load_from(&dir);
+        notes.set(&signature(1), "Local").unwrap();
+
+        let incoming = vec![
+            TransactionNote { signature: signature(1), note: "From backup".to_string(), updated_at: 0 },
+            TransactionNote { signature: signature(2), note: "Payroll".to_string(), updated_at: 0 },
+            TransactionNote { signature: "bad".to_string(), note: "Skipped".to_string(), updated_at: 0 },
+        ];
+        assert_eq!(notes.merge(&incoming).unwrap(), 1);
+        assert_eq!(notes.get(&signature(1)), Some("Local"));
+
+        assert_eq!(notes.replace_all(&incoming).unwrap(), 2);
+        assert_eq!(TransactionNotes::load_from(&dir).get(&signature(1)), Some("From backup"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,266 @@
+// src/vanity.rs
+use crate::wallet::Wallet;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Attempts between updates of the shared counter, so threads don't contend on every key
+const COUNTER_BATCH: u64 = 256;
+
+/// What the grinder is looking for in the base58 public key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VanityPattern {
+    pub prefix: String,
+    pub suffix: String,
+    pub case_sensitive: bool,
+}
+
+impl VanityPattern {
+    /// Build a pattern, rejecting characters that can never appear in a base58 address
+    pub fn new(prefix: &str, suffix: &str, case_sensitive: bool) -> Result<Self, String> {
+        let pattern = Self {
+            prefix: prefix.trim().to_string(),
+            suffix: suffix.trim().to_string(),
+            case_sensitive,
+        };
+
+        if pattern.prefix.is_empty() && pattern.suffix.is_empty() {
+            return Err("Enter a prefix or a suffix".to_string());
+        }
+        for c in pattern.prefix.chars().chain(pattern.suffix.chars()) {
+            if pattern.variants(c) == 0 {
+                return Err(format!(
+                    "'{}' is not a valid base58 character (0, O, I and l are never used)",
+                    c
+                ));
+            }
+        }
+        // 32-byte keys encode to at most 44 characters
+        if pattern.prefix.len() + pattern.suffix.len() > 44 {
+            return Err("Prefix and suffix are longer than an address".to_string());
+        }
+
+        Ok(pattern)
+    }
+
+    /// How many base58 characters satisfy `c` at one position
+    fn variants(&self, c: char) -> usize {
+        if self.case_sensitive {
+            usize::from(BASE58_ALPHABET.contains(c))
+        } else {
+            BASE58_ALPHABET
+                .chars()
+                .filter(|a| a.eq_ignore_ascii_case(&c))
+                .count()
+        }
+    }
+
+    pub fn matches(&self, address: &str) -> bool {
+        if self.case_sensitive {
+            address.starts_with(&self.prefix) && address.ends_with(&self.suffix)
+        } else {
+            let address = address.to_ascii_lowercase();
+            address.starts_with(&self.prefix.to_ascii_lowercase())
+                && address.ends_with(&self.suffix.to_ascii_lowercase())
+        }
+    }
+
+    /// Expected number of keys to try, treating each address character as uniform over base58
+    pub fn expected_attempts(&self) -> f64 {
+        self.prefix
+            .chars()
+            .chain(self.suffix.chars())
+            .map(|c| 58.0 / self.variants(c) as f64)
+            .product()
+    }
+}
+
+/// Snapshot of a running search for the UI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VanityProgress {
+    pub attempts: u64,
+    pub elapsed: Duration,
+    pub expected_attempts: f64,
+}
+
+impl VanityProgress {
+    pub fn attempts_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.attempts as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Seconds until the expected attempt count at the current rate
+    pub fn estimated_seconds_remaining(&self) -> Option<f64> {
+        let rate = self.attempts_per_second();
+        (rate > 0.0).then(|| ((self.expected_attempts - self.attempts as f64) / rate).max(0.0))
+    }
+}
+
+/// Handle to a grinder running on every CPU core. Dropping it cancels the search.
+pub struct VanityGrinder {
+    pattern: VanityPattern,
+    attempts: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    found: Arc<Mutex<Option<Wallet>>>,
+    started: Instant,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl VanityGrinder {
+    /// Start grinding with one worker per available core
+    pub fn start(pattern: VanityPattern, name: String) -> Self {
+        let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        Self::start_with_threads(pattern, name, threads)
+    }
+
+    pub fn start_with_threads(pattern: VanityPattern, name: String, threads: usize) -> Self {
+        let attempts = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+        let found = Arc::new(Mutex::new(None));
+
+        let workers = (0..threads.max(1))
+            .map(|_| {
+                let pattern = pattern.clone();
+                let name = name.clone();
+                let attempts = attempts.clone();
+                let stop = stop.clone();
+                let found = found.clone();
+                thread::spawn(move || {
+                    let mut local = 0u64;
+                    while !stop.load(Ordering::Relaxed) {
+                        let wallet = Wallet::new(name.clone());
+                        local += 1;
+                        if pattern.matches(&wallet.get_public_key()) {
+                            attempts.fetch_add(local, Ordering::Relaxed);
+                            let mut slot = found.lock().unwrap();
+                            if slot.is_none() {
+                                *slot = Some(wallet);
+                            }
+                            stop.store(true, Ordering::Relaxed);
+                            return;
+                        }
+                        if local == COUNTER_BATCH {
+                            attempts.fetch_add(local, Ordering::Relaxed);
+                            local = 0;
+                        }
+                    }
+                    attempts.fetch_add(local, Ordering::Relaxed);
+                })
+            })
+            .collect();
+
+        Self {
+            pattern,
+            attempts,
+            stop,
+            found,
+            started: Instant::now(),
+            workers,
+        }
+    }
+
+    pub fn progress(&self) -> VanityProgress {
+        VanityProgress {
+            attempts: self.attempts.load(Ordering::Relaxed),
+            elapsed: self.started.elapsed(),
+            expected_attempts: self.pattern.expected_attempts(),
+        }
+    }
+
+    /// Take the matching wallet once a worker has found one
+    pub fn take_result(&self) -> Option<Wallet> {
+        self.found.lock().unwrap().take()
+    }
+
+    /// True once a match was found or the search was cancelled
+    pub fn is_finished(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// Block until every worker has exited
+    pub fn join(mut self) {
+        self.cancel();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for VanityGrinder {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pattern_validation() {
+        assert!(VanityPattern::new("Sun", "", true).is_ok());
+        assert!(VanityPattern::new("", "", true).is_err());
+        assert!(VanityPattern::new("0x", "", true).is_err());
+        assert!(VanityPattern::new("", "Il", true).is_err());
+        // 'l' and 'O' have valid other-case counterparts
+        assert!(VanityPattern::new("lO", "", false).is_ok());
+        assert!(VanityPattern::new("0", "", false).is_err());
+    }
+
+    #[test]
+    fn test_matching() {
+        let sensitive = VanityPattern::new("ab", "Z", true).unwrap();
+        assert!(sensitive.matches("abcdefZ"));
+        assert!(!sensitive.matches("ABcdefZ"));
+
+        let insensitive = VanityPattern::new("ab", "z", false).unwrap();
+        assert!(insensitive.matches("ABcdefZ"));
+        assert!(!insensitive.matches("Acdef"));
+    }
+
+    #[test]
+    fn test_expected_attempts() {
+        assert_eq!(VanityPattern::new("A", "", true).unwrap().expected_attempts(), 58.0);
+        assert_eq!(VanityPattern::new("A", "b", true).unwrap().expected_attempts(), 58.0 * 58.0);
+        // 'a' and 'A' both match, '1' has no case
+        assert_eq!(VanityPattern::new("a", "", false).unwrap().expected_attempts(), 29.0);
+        assert_eq!(VanityPattern::new("1", "", false).unwrap().expected_attempts(), 58.0);
+    }
+
+    #[test]
+    fn test_grind_finds_match() {
+        let pattern = VanityPattern::new("", "a", false).unwrap();
+        let grinder = VanityGrinder::start_with_threads(pattern.clone(), "Vanity".into(), 2);
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while !grinder.is_finished() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        let wallet = grinder.take_result().expect("grinder should find a one-character suffix");
+        assert!(pattern.matches(&wallet.get_public_key()));
+        assert!(grinder.progress().attempts >= 1);
+        grinder.join();
+    }
+
+    #[test]
+    fn test_cancel() {
+        // Practically unreachable pattern
+        let pattern = VanityPattern::new("zzzzzzzz", "", true).unwrap();
+        let grinder = VanityGrinder::start_with_threads(pattern, "Vanity".into(), 1);
+        grinder.cancel();
+        assert!(grinder.is_finished());
+        assert!(grinder.take_result().is_none());
+        grinder.join();
+    }
+}
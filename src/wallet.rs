@@ -1,31 +1,223 @@
 // src/wallet.rs
 use ed25519_dalek::{SigningKey, VerifyingKey, Signer, Signature};
-use rand::{rngs::OsRng, Rng};
+use rand::{rngs::OsRng, RngCore};
 use serde::{Deserialize, Serialize};
 use bs58;
+use std::fmt;
+use std::ops::Deref;
+use zeroize::Zeroizing;
+use crate::derivation::{self, DerivationPath};
+use crate::keystore::{self, EncryptedSecret};
+use crate::key_format;
+use crate::signing::offchain::OffchainMessage;
+
+/// Where a wallet's secret key came from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SeedSource {
+    /// Random 32-byte key generated by this app (no recovery phrase)
+    Generated,
+    /// Derived from a BIP39 recovery phrase along `derivation_path`
+    Mnemonic { derivation_path: String },
+    /// Raw private key imported by the user (also the default for older wallets.json entries)
+    #[default]
+    PrivateKey,
+    /// Address only, tracked without a private key
+    WatchOnly,
+    /// Key held by another wallet app on the device, signed through Mobile Wallet Adapter
+    MobileWalletAdapter,
+}
+
+/// Shown wherever a watch-only wallet reaches a signing path
+pub const WATCH_ONLY_MESSAGE: &str = "This is a watch-only wallet. It has no private key and cannot sign transactions.";
+
+/// Whether a signing form must show `WATCH_ONLY_MESSAGE` instead of sending.
+/// Watch-only wallets have no key; only a connected hardware wallet can sign for them.
+pub fn cannot_sign_watch_only(wallet: Option<&WalletInfo>, hardware_connected: bool) -> bool {
+    !hardware_connected && wallet.is_some_and(WalletInfo::is_watch_only)
+}
+
+/// How the user has organised a wallet in the list. Older entries have none of it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WalletMetadata {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// `#rrggbb` accent shown next to the wallet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Unix timestamp (seconds) when the wallet was added to this app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    /// Unix timestamp (seconds) when the wallet was last selected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<i64>,
+    /// Position in the wallet list, shared by keyed and watch-only wallets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<u32>,
+}
 
 /// Persistable wallet info for storage or serialization
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct WalletInfo {
     pub name: String,
     pub address: String,
+    /// Legacy plaintext base58 keypair; empty once the wallet is in the keystore
     pub encrypted_key: String,
+    /// Passphrase-encrypted 64-byte keypair
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<EncryptedSecret>,
+    #[serde(default)]
+    pub seed_source: SeedSource,
+    #[serde(default)]
+    pub account_index: Option<u32>,
+    #[serde(default)]
+    pub metadata: WalletMetadata,
+}
+
+impl fmt::Debug for WalletInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // encrypted_key may still hold a legacy plaintext key
+        f.debug_struct("WalletInfo")
+            .field("name", &self.name)
+            .field("address", &self.address)
+            .field("encrypted_key", &if self.encrypted_key.is_empty() { "" } else { "<redacted>" })
+            .field("keystore", &self.keystore.is_some())
+            .field("seed_source", &self.seed_source)
+            .field("account_index", &self.account_index)
+            .field("metadata", &self.metadata)
+            .finish()
+    }
+}
+
+impl WalletInfo {
+    /// Address-only entry for tracking a wallet this app does not control
+    pub fn watch_only(name: String, address: String) -> Self {
+        Self {
+            name,
+            address,
+            encrypted_key: String::new(),
+            keystore: None,
+            seed_source: SeedSource::WatchOnly,
+            account_index: None,
+            metadata: WalletMetadata::default(),
+        }
+    }
+
+    pub fn is_watch_only(&self) -> bool {
+        self.seed_source == SeedSource::WatchOnly
+    }
+
+    /// Entry for a wallet app connected through Mobile Wallet Adapter
+    pub fn mobile_wallet_adapter(name: String, address: String) -> Self {
+        Self {
+            seed_source: SeedSource::MobileWalletAdapter,
+            ..Self::watch_only(name, address)
+        }
+    }
+
+    pub fn is_mobile_wallet_adapter(&self) -> bool {
+        self.seed_source == SeedSource::MobileWalletAdapter
+    }
 }
 
-/// In-memory wallet holding an ed25519 signing key
-#[derive(Debug, Clone)]
+/// Encodings offered by [`Wallet::export_secret`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretExportFormat {
+    /// Base58 64-byte keypair (Phantom, Solflare)
+    Base58Keypair,
+    /// Base58 32-byte private key only
+    Base58PrivateKey,
+    /// Solana CLI `id.json` byte array
+    CliJson,
+}
+
+/// Exported key material. The buffer is wiped on drop and never printed by `Debug`.
+pub struct ExportedSecret(Zeroizing<String>);
+
+impl Deref for ExportedSecret {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ExportedSecret {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ExportedSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ExportedSecret(<redacted>)")
+    }
+}
+
+/// In-memory wallet holding an ed25519 signing key.
+///
+/// Deliberately not `Clone`: share it behind an `Arc`/`Rc` instead of copying the key.
+/// `SigningKey` zeroizes itself on drop.
 pub struct Wallet {
-    pub signing_key: SigningKey,
+    signing_key: SigningKey,
     pub name: String,
+    pub seed_source: SeedSource,
+    pub account_index: Option<u32>,
+}
+
+impl fmt::Debug for Wallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Wallet")
+            .field("address", &self.get_public_key())
+            .field("name", &self.name)
+            .field("seed_source", &self.seed_source)
+            .field("account_index", &self.account_index)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Wallet {
     /// Generate a new random wallet
     pub fn new(name: String) -> Self {
-        let mut csprng = OsRng;
-        let secret_bytes: [u8; 32] = csprng.gen();
+        let mut secret_bytes = Zeroizing::new([0u8; 32]);
+        OsRng.fill_bytes(secret_bytes.as_mut());
         let signing_key = SigningKey::from_bytes(&secret_bytes);
-        Self { signing_key, name }
+        Self {
+            signing_key,
+            name,
+            seed_source: SeedSource::Generated,
+            account_index: None,
+        }
+    }
+
+    /// Derive the wallet for `account_index` from a BIP39 phrase along `m/44'/501'/account'/0'`
+    pub fn from_mnemonic(
+        phrase: &str,
+        passphrase: &str,
+        account_index: u32,
+        name: String,
+    ) -> Result<Self, String> {
+        let seed = derivation::mnemonic_to_seed(phrase, passphrase)?;
+        let path = DerivationPath::solana_account(account_index);
+        Ok(Self::from_seed(&seed[..], &path, Some(account_index), name))
+    }
+
+    /// Derive a wallet from a BIP39 seed along an arbitrary hardened path
+    pub fn from_seed(
+        seed: &[u8],
+        path: &DerivationPath,
+        account_index: Option<u32>,
+        name: String,
+    ) -> Self {
+        let secret_bytes = derivation::derive_ed25519_key(seed, path);
+        Self {
+            signing_key: SigningKey::from_bytes(&secret_bytes),
+            name,
+            seed_source: SeedSource::Mnemonic {
+                derivation_path: path.to_string(),
+            },
+            account_index,
+        }
     }
 
     /// Reconstruct from a raw private key (32 or 64 bytes)
@@ -33,15 +225,14 @@ impl Wallet {
         private_key_bytes: &[u8],
         name: String,
     ) -> Result<Self, String> {
+        let mut key_bytes = Zeroizing::new([0u8; 32]);
         match private_key_bytes.len() {
             32 => {
-                let mut key_bytes = [0u8; 32];
                 key_bytes.copy_from_slice(private_key_bytes);
                 let signing_key = SigningKey::from_bytes(&key_bytes);
-                Ok(Self { signing_key, name })
+                Ok(Self::from_signing_key(signing_key, name))
             }
             64 => {
-                let mut key_bytes = [0u8; 32];
                 key_bytes.copy_from_slice(&private_key_bytes[..32]);
                 let signing_key = SigningKey::from_bytes(&key_bytes);
                 let verifying_key = signing_key.verifying_key();
@@ -49,47 +240,90 @@ impl Wallet {
                 if verifying_key.as_bytes() != expected_pub {
                     return Err("Public key does not match private key".into());
                 }
-                Ok(Self { signing_key, name })
+                Ok(Self::from_signing_key(signing_key, name))
             }
             len => Err(format!("Invalid key length: {} bytes", len)),
         }
     }
 
+    fn from_signing_key(signing_key: SigningKey, name: String) -> Self {
+        Self {
+            signing_key,
+            name,
+            seed_source: SeedSource::PrivateKey,
+            account_index: None,
+        }
+    }
+
     /// Base58-encoded Solana-style public key
     pub fn get_public_key(&self) -> String {
         let vk = self.signing_key.verifying_key();
         bs58::encode(vk.as_bytes()).into_string()
     }
 
-    /// Base58-encoded Solana-compatible keypair (64 bytes)
-    pub fn get_private_key(&self) -> String {
-        let vk = self.signing_key.verifying_key();
-        let mut buf = Vec::with_capacity(64);
-        buf.extend_from_slice(&self.signing_key.to_bytes());
-        buf.extend_from_slice(vk.as_bytes());
-        bs58::encode(buf).into_string()
+    /// The 32-byte ed25519 secret key
+    pub(crate) fn secret_key_bytes(&self) -> Zeroizing<[u8; 32]> {
+        Zeroizing::new(self.signing_key.to_bytes())
     }
 
-    /// Only the 32-byte private key, base58-encoded
-    pub fn get_private_key_only(&self) -> String {
-        bs58::encode(self.signing_key.to_bytes()).into_string()
+    /// Secret key followed by public key, the 64-byte Solana keypair layout
+    fn keypair_bytes(&self) -> Zeroizing<[u8; 64]> {
+        let mut keypair = Zeroizing::new([0u8; 64]);
+        keypair[..32].copy_from_slice(self.signing_key.as_bytes());
+        keypair[32..].copy_from_slice(self.signing_key.verifying_key().as_bytes());
+        keypair
     }
 
-    /// Serialize into `WalletInfo`
-    pub fn to_wallet_info(&self) -> WalletInfo {
-        WalletInfo {
+    /// The only way to get key material out of a wallet
+    pub fn export_secret(&self, format: SecretExportFormat) -> ExportedSecret {
+        let keypair = self.keypair_bytes();
+        let encoded = match format {
+            SecretExportFormat::Base58Keypair => bs58::encode(keypair.as_ref()).into_string(),
+            SecretExportFormat::Base58PrivateKey => bs58::encode(&keypair[..32]).into_string(),
+            SecretExportFormat::CliJson => key_format::to_cli_json(keypair.as_ref()),
+        };
+        ExportedSecret(Zeroizing::new(encoded))
+    }
+
+    /// Serialize into `WalletInfo`, encrypting the keypair with the unlocked session
+    pub fn to_wallet_info(&self) -> Result<WalletInfo, String> {
+        let keypair = self.keypair_bytes();
+
+        Ok(WalletInfo {
             name: self.name.clone(),
             address: self.get_public_key(),
-            encrypted_key: self.get_private_key(),
-        }
+            encrypted_key: String::new(),
+            keystore: Some(keystore::seal(keypair.as_ref())?),
+            seed_source: self.seed_source.clone(),
+            account_index: self.account_index,
+            metadata: WalletMetadata::default(),
+        })
     }
 
-    /// Deserialize from `WalletInfo`
+    /// Deserialize from `WalletInfo`. Requires an unlocked keystore session,
+    /// even for legacy plaintext entries that have not been migrated yet.
     pub fn from_wallet_info(info: &WalletInfo) -> Result<Self, String> {
-        let bytes = bs58::decode(&info.encrypted_key)
-            .into_vec()
-            .map_err(|e| format!("Decode error: {}", e))?;
-        Self::from_private_key(&bytes, info.name.clone())
+        if info.is_watch_only() {
+            return Err(WATCH_ONLY_MESSAGE.to_string());
+        }
+        if info.is_mobile_wallet_adapter() {
+            return Err("This wallet's key is held by another app. Sign through Mobile Wallet Adapter.".to_string());
+        }
+        if !keystore::is_unlocked() {
+            return Err(keystore::KeystoreError::Locked.into());
+        }
+        let bytes = match &info.keystore {
+            Some(secret) => keystore::open(secret)?,
+            None => Zeroizing::new(
+                bs58::decode(&info.encrypted_key)
+                    .into_vec()
+                    .map_err(|e| format!("Decode error: {}", e))?,
+            ),
+        };
+        let mut wallet = Self::from_private_key(&bytes, info.name.clone())?;
+        wallet.seed_source = info.seed_source.clone();
+        wallet.account_index = info.account_index;
+        Ok(wallet)
     }
 
     /// Sign a transaction message (serialized transaction)
@@ -98,6 +332,11 @@ impl Wallet {
         bs58::encode(signature.to_bytes()).into_string()
     }
 
+    /// Sign a message in the Solana off-chain message format
+    pub fn sign_offchain_message(&self, message: &OffchainMessage) -> Signature {
+        self.signing_key.sign(&message.serialize())
+    }
+
     /// Sign a message with ed25519
     pub fn sign_message(&self, message: &[u8]) -> Signature {
         self.signing_key.sign(message)
@@ -118,12 +357,13 @@ impl Wallet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ed25519_dalek::Verifier;
 
     #[test]
     fn test_wallet_creation() {
         let wallet = Wallet::new("Test Wallet".to_string());
         assert!(!wallet.get_public_key().is_empty());
-        assert!(!wallet.get_private_key().is_empty());
+        assert!(!wallet.export_secret(SecretExportFormat::Base58Keypair).is_empty());
     }
 
     #[test]
@@ -139,12 +379,18 @@ mod tests {
 
     #[test]
     fn test_import_export() {
+        keystore::unlock_test_session();
         let wallet1 = Wallet::new("Test Wallet".to_string());
-        let info = wallet1.to_wallet_info();
+        let info = wallet1.to_wallet_info().unwrap();
+        assert!(info.encrypted_key.is_empty());
+        assert!(info.keystore.is_some());
         let wallet2 = Wallet::from_wallet_info(&info).unwrap();
         
         assert_eq!(wallet1.get_public_key(), wallet2.get_public_key());
-        assert_eq!(wallet1.get_private_key(), wallet2.get_private_key());
+        assert_eq!(
+            &*wallet1.export_secret(SecretExportFormat::Base58Keypair),
+            &*wallet2.export_secret(SecretExportFormat::Base58Keypair)
+        );
     }
 
     #[test]
@@ -157,9 +403,113 @@ mod tests {
         println!("Public key: {}", pubkey);
         
         // Verify the keypair can be exported and re-imported
-        let exported = wallet.get_private_key();
-        let imported_bytes = bs58::decode(&exported).into_vec().unwrap();
+        let exported = wallet.export_secret(SecretExportFormat::Base58Keypair);
+        let imported_bytes = bs58::decode(&*exported).into_vec().unwrap();
         let wallet2 = Wallet::from_private_key(&imported_bytes, "Test2".into()).unwrap();
         assert_eq!(wallet.get_public_key(), wallet2.get_public_key());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_watch_only_cannot_sign() {
+        let info = WalletInfo::watch_only(
+            "Treasury".to_string(),
+            "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk".to_string(),
+        );
+        assert!(info.is_watch_only());
+        assert_eq!(Wallet::from_wallet_info(&info).unwrap_err(), WATCH_ONLY_MESSAGE);
+        assert!(cannot_sign_watch_only(Some(&info), false));
+        assert!(!cannot_sign_watch_only(Some(&info), true));
+        assert!(!cannot_sign_watch_only(None, false));
+
+        let json = serde_json::to_string(&info).unwrap();
+        let parsed: WalletInfo = serde_json::from_str(&json).unwrap();
+        assert!(parsed.is_watch_only());
+    }
+
+    #[test]
+    fn test_cli_json_roundtrip() {
+        let wallet = Wallet::new("Test".to_string());
+        let json = wallet.export_secret(SecretExportFormat::CliJson);
+        let bytes: Vec<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(bytes.len(), 64);
+
+        let (_, parsed) = key_format::parse_private_key(&json).unwrap();
+        let imported = Wallet::from_private_key(&parsed, "Imported".into()).unwrap();
+        assert_eq!(imported.get_public_key(), wallet.get_public_key());
+    }
+
+    #[test]
+    fn test_mnemonic_derivation() {
+        // Same address Phantom and Solflare derive for account 0 of this phrase
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let wallet = Wallet::from_mnemonic(phrase, "", 0, "Test".into()).unwrap();
+        assert_eq!(wallet.get_public_key(), "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+        assert_eq!(wallet.account_index, Some(0));
+        assert_eq!(
+            wallet.seed_source,
+            SeedSource::Mnemonic { derivation_path: "m/44'/501'/0'/0'".to_string() }
+        );
+
+        // A passphrase or a different account index yields a different key
+        let with_passphrase = Wallet::from_mnemonic(phrase, "secret", 0, "Test".into()).unwrap();
+        let second_account = Wallet::from_mnemonic(phrase, "", 1, "Test".into()).unwrap();
+        assert_ne!(wallet.get_public_key(), with_passphrase.get_public_key());
+        assert_ne!(wallet.get_public_key(), second_account.get_public_key());
+
+        // Seed source survives the round trip through WalletInfo
+        keystore::unlock_test_session();
+        let restored = Wallet::from_wallet_info(&wallet.to_wallet_info().unwrap()).unwrap();
+        assert_eq!(restored.seed_source, wallet.seed_source);
+        assert_eq!(restored.account_index, Some(0));
+    }
+
+    #[test]
+    fn test_legacy_wallet_info_parses() {
+        let json = r#"{"name":"Old","address":"addr","encrypted_key":"key"}"#;
+        let info: WalletInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(info.seed_source, SeedSource::PrivateKey);
+        assert_eq!(info.account_index, None);
+        assert_eq!(info.keystore, None);
+        assert_eq!(info.metadata, WalletMetadata::default());
+    }
+
+    #[test]
+    fn test_plaintext_wallet_info_still_opens() {
+        keystore::unlock_test_session();
+        let wallet = Wallet::new("Legacy".to_string());
+        let info = WalletInfo {
+            name: "Legacy".to_string(),
+            address: wallet.get_public_key(),
+            encrypted_key: wallet.export_secret(SecretExportFormat::Base58Keypair).to_string(),
+            keystore: None,
+            seed_source: SeedSource::Generated,
+            account_index: None,
+            metadata: WalletMetadata::default(),
+        };
+        let restored = Wallet::from_wallet_info(&info).unwrap();
+        assert_eq!(restored.get_public_key(), wallet.get_public_key());
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let wallet = Wallet::new("Test".to_string());
+        let keypair = wallet.export_secret(SecretExportFormat::Base58Keypair);
+        let private_key = wallet.export_secret(SecretExportFormat::Base58PrivateKey);
+
+        let debug = format!("{:?} {:?}", wallet, keypair);
+        assert!(debug.contains(&wallet.get_public_key()));
+        assert!(!debug.contains(&*keypair));
+        assert!(!debug.contains(&*private_key));
+
+        let info = WalletInfo {
+            name: "Legacy".to_string(),
+            address: wallet.get_public_key(),
+            encrypted_key: keypair.to_string(),
+            keystore: None,
+            seed_source: SeedSource::Generated,
+            account_index: None,
+            metadata: WalletMetadata::default(),
+        };
+        assert!(!format!("{:?}", info).contains(&*keypair));
+    }
+}
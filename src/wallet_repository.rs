@@ -0,0 +1,543 @@
+// src/wallet_repository.rs
+use crate::schema::{self, Document};
+use crate::storage;
+use crate::wallet::WalletInfo;
+
+/// Accent colors offered when customising a wallet
+pub const WALLET_COLORS: &[&str] = &[
+    "#9945ff", "#14f195", "#00c2ff", "#ff6b6b", "#ffb020", "#f472b6", "#94a3b8",
+];
+
+const MAX_NAME_LEN: usize = 32;
+const MAX_TAG_LEN: usize = 24;
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Wallet name cannot be empty".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("Wallet name must be at most {} characters", MAX_NAME_LEN));
+    }
+    Ok(name.to_string())
+}
+
+fn validate_color(color: &str) -> Result<String, String> {
+    let hex = color.trim().strip_prefix('#').unwrap_or_default();
+    if hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(format!("#{}", hex.to_ascii_lowercase()))
+    } else {
+        Err(format!("Invalid color {}, expected #rrggbb", color))
+    }
+}
+
+/// The user's wallet list: keyed wallets in `wallets.json` and watch-only
+/// wallets in `watch_only.json`, presented as one ordered list.
+///
+/// Every change is made on a copy, written back with atomic file replacement and
+/// only then becomes visible, so a failed save leaves the repository unchanged.
+/// Names are unique (case-insensitive) and so are addresses.
+#[derive(Clone)]
+pub struct WalletRepository {
+    wallets: Vec<WalletInfo>,
+    /// Files that existed but could not be read, as messages for the user
+    load_errors: Vec<String>,
+    #[cfg(not(feature = "web"))]
+    dir: String,
+}
+
+impl WalletRepository {
+    /// Load from the app storage directory
+    pub fn load() -> Self {
+        #[cfg(feature = "web")]
+        {
+            let mut wallets = Vec::new();
+            let mut load_errors = Vec::new();
+            for (key, document) in [
+                (storage::WALLETS_WEB_KEY, Document::Wallets),
+                (storage::WATCH_ONLY_WEB_KEY, Document::WatchOnly),
+            ] {
+                match storage::try_read_web_document::<Vec<WalletInfo>>(key, document) {
+                    Ok(list) => wallets.extend(list.unwrap_or_default()),
+                    Err(e) => load_errors.push(e),
+                }
+            }
+
+            let mut repository = Self::from_wallets(wallets);
+            repository.load_errors = load_errors;
+            repository
+        }
+
+        #[cfg(not(feature = "web"))]
+        {
+            Self::load_from(&storage::get_storage_dir_simple())
+        }
+    }
+
+    /// Load from an explicit directory
+    #[cfg(not(feature = "web"))]
+    pub fn load_from(dir: &str) -> Self {
+        let mut wallets = Vec::new();
+        let mut load_errors = Vec::new();
+        for (file_name, document) in [
+            (storage::WALLETS_FILE_NAME, Document::Wallets),
+            (storage::WATCH_ONLY_FILE_NAME, Document::WatchOnly),
+        ] {
+            match storage::try_read_wallet_file(&format!("{}/{}", dir, file_name), document) {
+                Ok(list) => wallets.extend(list),
+                Err(e) => load_errors.push(e),
+            }
+        }
+
+        let mut repository = Self::from_wallets(wallets);
+        repository.dir = dir.to_string();
+        repository.load_errors = load_errors;
+        repository
+    }
+
+    fn from_wallets(mut wallets: Vec<WalletInfo>) -> Self {
+        // Entries without a position (older files) keep their file order after positioned ones
+        wallets.sort_by_key(|w| w.metadata.position.unwrap_or(u32::MAX));
+        Self {
+            wallets,
+            load_errors: Vec::new(),
+            #[cfg(not(feature = "web"))]
+            dir: String::new(),
+        }
+    }
+
+    pub fn wallets(&self) -> &[WalletInfo] {
+        &self.wallets
+    }
+
+    /// Why stored wallets are missing from the list, if any file failed to load.
+    /// The unreadable file has been copied aside, but the user should be told.
+    pub fn load_errors(&self) -> &[String] {
+        &self.load_errors
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wallets.is_empty()
+    }
+
+    pub fn get(&self, address: &str) -> Option<&WalletInfo> {
+        self.wallets.iter().find(|w| w.address == address)
+    }
+
+    fn index_of(&self, address: &str) -> Result<usize, String> {
+        self.wallets
+            .iter()
+            .position(|w| w.address == address)
+            .ok_or_else(|| format!("No wallet with address {}", address))
+    }
+
+    fn name_taken(&self, name: &str, except_address: Option<&str>) -> bool {
+        self.wallets
+            .iter()
+            .any(|w| same_name(&w.name, name) && Some(w.address.as_str()) != except_address)
+    }
+
+    /// `name`, or `name (2)`, `name (3)`... whichever is free
+    pub fn unique_name(&self, name: &str) -> String {
+        let base = name.trim();
+        if !self.name_taken(base, None) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{} ({})", base, n))
+            .find(|candidate| !self.name_taken(candidate, None))
+            .expect("some suffix is always free")
+    }
+
+    fn insert(&mut self, mut wallet: WalletInfo) -> Result<(), String> {
+        if self.get(&wallet.address).is_some() {
+            return Err(format!("{} is already in your wallet list", wallet.address));
+        }
+        // Names from imports and older files can be longer than a typed one may be
+        let name: String = wallet.name.trim().chars().take(MAX_NAME_LEN).collect();
+        let name = validate_name(&name)?;
+        wallet.name = self.unique_name(&name);
+        wallet.metadata.created_at.get_or_insert_with(now);
+
+        log::info!("➕ Adding wallet {} ({})", wallet.name, wallet.address);
+        self.wallets.push(wallet);
+        Ok(())
+    }
+
+    fn apply_name(&mut self, address: &str, new_name: &str) -> Result<(), String> {
+        let index = self.index_of(address)?;
+        let name = validate_name(new_name)?;
+        if self.name_taken(&name, Some(address)) {
+            return Err(format!("A wallet named {} already exists", name));
+        }
+
+        self.wallets[index].name = name;
+        Ok(())
+    }
+
+    fn apply_tags(&mut self, address: &str, tags: &[String]) -> Result<(), String> {
+        let index = self.index_of(address)?;
+        let mut cleaned: Vec<String> = Vec::new();
+        for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if tag.chars().count() > MAX_TAG_LEN {
+                return Err(format!("Tags must be at most {} characters", MAX_TAG_LEN));
+            }
+            if !cleaned.iter().any(|t| same_name(t, tag)) {
+                cleaned.push(tag.to_string());
+            }
+        }
+
+        self.wallets[index].metadata.tags = cleaned;
+        Ok(())
+    }
+
+    /// Apply `change` to a copy and keep it only if the copy was saved
+    fn commit<T>(&mut self, change: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        self.commit_with(&[], change)
+    }
+
+    /// `commit`, writing `other` documents in the same change as the wallet files
+    fn commit_with<T>(
+        &mut self,
+        other: &[storage::PendingWrite],
+        change: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut staged = self.clone();
+        let value = change(&mut staged)?;
+        staged.save(other)?;
+        *self = staged;
+        Ok(value)
+    }
+
+    /// Append a wallet. A clashing name gets a numeric suffix; a duplicate address is refused.
+    pub fn add(&mut self, wallet: WalletInfo) -> Result<WalletInfo, String> {
+        self.commit(|repository| {
+            repository.insert(wallet)?;
+            Ok(repository.wallets[repository.wallets.len() - 1].clone())
+        })
+    }
+
+    /// Swap the whole list for `wallets` (e.g. restoring a backup), keeping their order.
+    /// Names are made unique as in `add` and repeated addresses are dropped.
+    pub fn replace_all(&mut self, wallets: Vec<WalletInfo>) -> Result<(), String> {
+        self.replace_all_with(wallets, &[])
+    }
+
+    /// `replace_all`, writing `other` documents in the same change
+    pub(crate) fn replace_all_with(
+        &mut self,
+        wallets: Vec<WalletInfo>,
+        other: &[storage::PendingWrite],
+    ) -> Result<(), String> {
+        log::info!("♻️ Replacing {} wallets with {}", self.wallets.len(), wallets.len());
+        self.commit_with(other, |repository| {
+            repository.wallets.clear();
+            for wallet in wallets {
+                if repository.get(&wallet.address).is_none() {
+                    repository.insert(wallet)?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Append each wallet whose address isn't listed yet and write `other` documents in
+    /// the same change. Returns how many were added.
+    pub(crate) fn merge_with(&mut self, wallets: Vec<WalletInfo>, other: &[storage::PendingWrite]) -> Result<usize, String> {
+        self.commit_with(other, |repository| {
+            let mut added = 0;
+            for wallet in wallets {
+                if repository.get(&wallet.address).is_none() {
+                    repository.insert(wallet)?;
+                    added += 1;
+                }
+            }
+            Ok(added)
+        })
+    }
+
+    pub fn rename(&mut self, address: &str, new_name: &str) -> Result<(), String> {
+        self.commit(|repository| repository.apply_name(address, new_name))
+    }
+
+    /// Rename and retag a wallet in one save, so an edit is never half-applied
+    pub fn update(&mut self, address: &str, new_name: &str, tags: &[String]) -> Result<(), String> {
+        self.commit(|repository| {
+            repository.apply_name(address, new_name)?;
+            repository.apply_tags(address, tags)
+        })
+    }
+
+    /// Remove a wallet and its stored key
+    pub fn delete(&mut self, address: &str) -> Result<WalletInfo, String> {
+        self.commit(|repository| {
+            let index = repository.index_of(address)?;
+            let removed = repository.wallets.remove(index);
+            log::info!("🗑️ Deleting wallet {} ({})", removed.name, removed.address);
+            Ok(removed)
+        })
+    }
+
+    /// Move a wallet to `new_index` in the list (clamped to the end)
+    pub fn move_to(&mut self, address: &str, new_index: usize) -> Result<(), String> {
+        self.commit(|repository| {
+            let index = repository.index_of(address)?;
+            let wallet = repository.wallets.remove(index);
+            let new_index = new_index.min(repository.wallets.len());
+            repository.wallets.insert(new_index, wallet);
+            Ok(())
+        })
+    }
+
+    /// Replace a wallet's tags. Blank and repeated tags are dropped.
+    pub fn set_tags(&mut self, address: &str, tags: &[String]) -> Result<(), String> {
+        self.commit(|repository| repository.apply_tags(address, tags))
+    }
+
+    pub fn set_color(&mut self, address: &str, color: Option<&str>) -> Result<(), String> {
+        self.commit(|repository| {
+            let index = repository.index_of(address)?;
+            repository.wallets[index].metadata.color = color.map(validate_color).transpose()?;
+            Ok(())
+        })
+    }
+
+    /// Record that the wallet was just selected
+    pub fn touch(&mut self, address: &str) -> Result<(), String> {
+        self.commit(|repository| {
+            let index = repository.index_of(address)?;
+            repository.wallets[index].metadata.last_used_at = Some(now());
+            Ok(())
+        })
+    }
+
+    /// Renumber positions and write keyed and watch-only wallets to their files
+    /// together with `other` documents, all or none
+    fn save(&mut self, other: &[storage::PendingWrite]) -> Result<(), String> {
+        for (position, wallet) in self.wallets.iter_mut().enumerate() {
+            wallet.metadata.position = Some(position as u32);
+        }
+        let (watch_only, keyed): (Vec<WalletInfo>, Vec<WalletInfo>) =
+            self.wallets.iter().cloned().partition(|w| w.is_watch_only());
+        let wallets = schema::encode(Document::Wallets, &keyed)?;
+        let watch_only_wallets = schema::encode(Document::WatchOnly, &watch_only)?;
+
+        #[cfg(feature = "web")]
+        let mut writes = vec![
+            (storage::WALLETS_WEB_KEY, wallets),
+            (storage::WATCH_ONLY_WEB_KEY, watch_only_wallets),
+        ];
+
+        #[cfg(not(feature = "web"))]
+        let mut writes = vec![
+            (format!("{}/{}", self.dir, storage::WALLETS_FILE_NAME), wallets),
+            (format!("{}/{}", self.dir, storage::WATCH_ONLY_FILE_NAME), watch_only_wallets),
+        ];
+
+        writes.extend_from_slice(other);
+        storage::write_all(&writes)?;
+
+        log::info!("✅ Saved {} wallets and {} watch-only wallets", keyed.len(), watch_only.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystore;
+    use crate::wallet::Wallet;
+
+    fn temp_dir() -> String {
+        let dir = std::env::temp_dir().join(format!("wallet_repository_{}", uuid::Uuid::new_v4()));
+        dir.to_string_lossy().into_owned()
+    }
+
+    fn keyed(name: &str) -> WalletInfo {
+        keystore::unlock_test_session();
+        Wallet::new(name.to_string()).to_wallet_info().unwrap()
+    }
+
+    fn names(repository: &WalletRepository) -> Vec<&str> {
+        repository.wallets().iter().map(|w| w.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_add_rename_delete_persist() {
+        let dir = temp_dir();
+        let mut repository = WalletRepository::load_from(&dir);
+        assert!(repository.is_empty());
+        // A missing file is a first run, not a load failure
+        assert!(repository.load_errors().is_empty());
+
+        let main = repository.add(keyed("Main")).unwrap();
+        assert!(main.metadata.created_at.is_some());
+        let watched = repository
+            .add(WalletInfo::watch_only(
+                "Treasury".to_string(),
+                "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk".to_string(),
+            ))
+            .unwrap();
+        repository.rename(&main.address, "  Savings ").unwrap();
+
+        // Keyed and watch-only wallets land in separate files but reload as one list
+        let reloaded = WalletRepository::load_from(&dir);
+        assert_eq!(names(&reloaded), ["Savings", "Treasury"]);
+        let keyed_file = storage::read_wallet_file(&format!("{}/{}", dir, storage::WALLETS_FILE_NAME), Document::Wallets);
+        assert_eq!(keyed_file.len(), 1);
+        assert!(!std::path::Path::new(&format!("{}/{}.tmp", dir, storage::WALLETS_FILE_NAME)).exists());
+
+        assert!(repository.add(watched.clone()).is_err());
+        repository.delete(&watched.address).unwrap();
+        assert_eq!(names(&WalletRepository::load_from(&dir)), ["Savings"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unique_names() {
+        let dir = temp_dir();
+        let mut repository = WalletRepository::load_from(&dir);
+        let first = repository.add(keyed("Wallet")).unwrap();
+        let second = repository.add(keyed("wallet")).unwrap();
+        assert_eq!(second.name, "wallet (2)");
+
+        assert!(repository.rename(&second.address, "WALLET").is_err());
+        assert!(repository.rename(&second.address, "   ").is_err());
+        // Renaming to its own name with different case is fine
+        repository.rename(&first.address, "WALLET").unwrap();
+
+        // Added wallets keep their name, cut to the limit, and still need one
+        let long = repository.add(keyed(&"Cold storage ".repeat(4))).unwrap();
+        assert_eq!(long.name, "Cold storage Cold storage Cold s");
+        assert!(repository.add(keyed("  ")).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_reorder_across_files() {
+        let dir = temp_dir();
+        let mut repository = WalletRepository::load_from(&dir);
+        let a = repository.add(keyed("A")).unwrap();
+        repository.add(keyed("B")).unwrap();
+        let watched = repository
+            .add(WalletInfo::watch_only(
+                "W".to_string(),
+                "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk".to_string(),
+            ))
+            .unwrap();
+
+        repository.move_to(&watched.address, 0).unwrap();
+        repository.move_to(&a.address, 99).unwrap();
+        assert_eq!(names(&repository), ["W", "B", "A"]);
+        assert_eq!(names(&WalletRepository::load_from(&dir)), ["W", "B", "A"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_tags_color_and_last_used() {
+        let dir = temp_dir();
+        let mut repository = WalletRepository::load_from(&dir);
+        let wallet = repository.add(keyed("Main")).unwrap();
+
+        let tags = vec![" trading ".to_string(), "".to_string(), "Trading".to_string(), "cold".to_string()];
+        repository.set_tags(&wallet.address, &tags).unwrap();
+        repository.set_color(&wallet.address, Some("#9945FF")).unwrap();
+        assert!(repository.set_color(&wallet.address, Some("purple")).is_err());
+        repository.touch(&wallet.address).unwrap();
+
+        let reloaded = WalletRepository::load_from(&dir);
+        let metadata = &reloaded.get(&wallet.address).unwrap().metadata;
+        assert_eq!(metadata.tags, ["trading", "cold"]);
+        assert_eq!(metadata.color.as_deref(), Some("#9945ff"));
+        assert!(metadata.last_used_at.is_some());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_failed_save_changes_nothing() {
+        let dir = temp_dir();
+        let mut repository = WalletRepository::load_from(&dir);
+        let wallet = repository.add(keyed("Main")).unwrap();
+        let other = repository.add(keyed("Other")).unwrap();
+
+        repository
+            .update(&wallet.address, "Savings", &["cold".to_string()])
+            .unwrap();
+        assert!(repository.update(&other.address, "savings", &["hot".to_string()]).is_err());
+        // The name clash refused the whole edit, tags included
+        assert!(repository.get(&other.address).unwrap().metadata.tags.is_empty());
+
+        // Put a file where the directory was so every save fails
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::write(&dir, "").unwrap();
+
+        assert!(repository.update(&wallet.address, "Spending", &["hot".to_string()]).is_err());
+        assert!(repository.move_to(&other.address, 0).is_err());
+        assert!(repository.delete(&other.address).is_err());
+        let unchanged = repository.get(&wallet.address).unwrap();
+        assert_eq!(unchanged.name, "Savings");
+        assert_eq!(unchanged.metadata.tags, ["cold"]);
+        assert_eq!(names(&repository), ["Savings", "Other"]);
+
+        let _ = std::fs::remove_file(&dir);
+    }
+
+    #[test]
+    fn test_wallet_files_are_saved_together() {
+        let dir = temp_dir();
+        let mut repository = WalletRepository::load_from(&dir);
+        repository.add(keyed("Main")).unwrap();
+        let wallets_file = format!("{}/{}", dir, storage::WALLETS_FILE_NAME);
+        let saved = std::fs::read_to_string(&wallets_file).unwrap();
+
+        // A directory in place of watch_only.json makes only the second file fail
+        let watch_only_file = format!("{}/{}", dir, storage::WATCH_ONLY_FILE_NAME);
+        std::fs::remove_file(&watch_only_file).unwrap();
+        std::fs::create_dir_all(format!("{}/blocked", watch_only_file)).unwrap();
+
+        assert!(repository.add(keyed("Second")).is_err());
+        assert_eq!(std::fs::read_to_string(&wallets_file).unwrap(), saved);
+        assert_eq!(names(&WalletRepository::load_from(&dir)), ["Main"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unreadable_file_is_preserved() {
+        let dir = temp_dir();
+        let wallets_file = format!("{}/{}", dir, storage::WALLETS_FILE_NAME);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&wallets_file, r#"{"schema_version": 99, "data": []}"#).unwrap();
+
+        let mut repository = WalletRepository::load_from(&dir);
+        assert!(repository.is_empty());
+        assert_eq!(repository.load_errors().len(), 1);
+        assert!(repository.load_errors()[0].contains(".unreadable-"));
+        // Reading it again points at the same copy instead of making another
+        assert_eq!(WalletRepository::load_from(&dir).load_errors(), repository.load_errors());
+        repository.add(keyed("Fresh")).unwrap();
+
+        // The file from the newer version survives as a backup
+        let backups: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".unreadable-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        assert!(std::fs::read_to_string(backups[0].path()).unwrap().contains("99"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}